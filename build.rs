@@ -0,0 +1,49 @@
+//! Captures build details for `jcal::build_info` (see `src/build_info.rs`).
+
+use std::path::Path;
+
+/// Dependencies whose resolved versions are reported.
+const DEPENDENCIES: &[&str] = &["jelal", "jiff"];
+
+fn main() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let lock_path = Path::new(&manifest_dir).join("Cargo.lock");
+    println!("cargo:rerun-if-changed=build.rs");
+    // the lock is missing when built as a dependency, the versions are then reported unknown
+    // (watching a missing file would run this on every build)
+    if lock_path.exists() {
+        println!("cargo:rerun-if-changed={}", lock_path.display());
+    }
+
+    let lock = std::fs::read_to_string(&lock_path).unwrap_or_default();
+    for name in DEPENDENCIES {
+        if let Some(version) = locked_version(&lock, name) {
+            println!(
+                "cargo:rustc-env=JCAL_{}_VERSION={}",
+                name.to_uppercase(),
+                version
+            );
+        }
+    }
+
+    let mut features = std::env::vars()
+        .filter_map(|(k, _)| {
+            k.strip_prefix("CARGO_FEATURE_")
+                .map(|i| i.to_lowercase().replace('_', "-"))
+        })
+        .collect::<Vec<_>>();
+    features.sort();
+    println!("cargo:rustc-env=JCAL_FEATURES={}", features.join(","));
+}
+
+/// Find the version of the first package with the given name in a `Cargo.lock`.
+fn locked_version<'a>(lock: &'a str, name: &str) -> Option<&'a str> {
+    let name_line = format!("name = \"{}\"", name);
+    let mut lines = lock.lines().skip_while(|i| i.trim() != name_line);
+    lines.next()?;
+    lines
+        .next()?
+        .trim()
+        .strip_prefix("version = \"")?
+        .strip_suffix('"')
+}
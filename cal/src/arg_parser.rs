@@ -5,35 +5,25 @@ use clap::{
     value_parser,
 };
 use jcal::{
+    build_info,
     clap_helper::{ArgMatchesExt, CommandFactoryExt, StaticMap},
-    date::{CommonDate, Date},
-    parser::{parse_jalali_month, parse_month, parse_weekday},
+    config::{ColorMode, Config},
+    date::{CalendarKind, CommonDate, Date},
+    parser::{parse_hijri_month, parse_jalali_month, parse_month, parse_weekday},
+    strftime::{DATE_DIRECTIVES, date_strftime},
 };
-use jelal::{MonthDay, Weekday};
-use jiff::{Timestamp, ToSpan};
+use jelal::{IYear, MonthDay, UMonth, UMonthDay, Weekday};
+use jiff::{Timestamp, civil, tz::TimeZone};
 
-use crate::layout::{Highlight, Layout, WeekNumConfig};
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum ColorMode {
-    Auto,
-    Always,
-    Never,
-}
-
-impl ColorMode {
-    pub const PARSER_DEFAULT: &'static str = "auto";
-
-    pub const PARSER_MAP: StaticMap<&'static Self> = StaticMap(&[
-        (Self::PARSER_DEFAULT, &Self::Auto),
-        ("always", &Self::Always),
-        ("never", &Self::Never),
-    ]);
-}
+use crate::{
+    events::{parse_date, parse_events},
+    layout::{Highlight, Layout, WeekNumConfig},
+    string::{Style, StyleConfig},
+};
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
-enum Reform {
+pub enum Reform {
     Y1752,
     Gregorian,
     Julian,
@@ -49,10 +39,149 @@ impl Reform {
     ]);
 }
 
+/// How the calendar is printed (`--output`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// The aligned (and colored) months.
+    Text,
+    /// The months as values (see [`Layout::to_json`]).
+    Json,
+    /// A table for each month (see [`Layout::to_html`]).
+    Html,
+    /// Comma separated records (see [`Layout::to_delimited`]).
+    Csv,
+    /// Tab separated records.
+    Tsv,
+}
+
+impl OutputFormat {
+    pub const PARSER_DEFAULT: &'static str = "text";
+
+    pub const PARSER_MAP: StaticMap<&'static Self> = StaticMap(&[
+        (Self::PARSER_DEFAULT, &Self::Text),
+        ("json", &Self::Json),
+        ("html", &Self::Html),
+        ("csv", &Self::Csv),
+        ("tsv", &Self::Tsv),
+    ]);
+}
+
+/// Which side of the given month gets the extra month of an even span (`--span-bias`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpanBias {
+    Before,
+    /// Like `ncal`.
+    After,
+}
+
+impl SpanBias {
+    pub const PARSER_DEFAULT: &'static str = "before";
+
+    pub const PARSER_MAP: StaticMap<&'static Self> = StaticMap(&[
+        (Self::PARSER_DEFAULT, &Self::Before),
+        ("after", &Self::After),
+    ]);
+}
+
+/// The width of the output in characters: a positive `COLUMNS` (given as `columns`), else the
+/// current width of the terminal, else 80 (e.g. piped without `COLUMNS`).
+///
+/// This is read each time so a resized terminal is seen by the next [`Args`].
+pub fn terminal_width(columns: Option<&str>) -> usize {
+    if let Some(width) = columns
+        .and_then(|i| i.trim().parse::<usize>().ok())
+        .filter(|&i| i > 0)
+    {
+        return width;
+    }
+    terminal_size::terminal_size()
+        .map(|(w, _)| w.0)
+        .unwrap_or(80) as usize
+}
+
+/// A flag or a value that was accepted but does not take effect as given (see [`lint_args`]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lint {
+    /// Spanning a single month is the same as not spanning.
+    SpanSingleMonth,
+    /// The week number decides the month so the day is ignored.
+    WeekWithDay,
+    /// A row never gets more columns than the printed months.
+    ColumnsOverMonths { columns: usize, months: usize },
+    /// The year is out of the range of the calendar so the closest one is printed.
+    YearOutOfRange { given: IYear, used: IYear },
+    /// A line of the events file is skipped since its date cannot be read.
+    MalformedEvent { line: usize, reason: String },
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SpanSingleMonth => write!(f, "`--span` has no effect with a single month"),
+            Self::WeekWithDay => write!(f, "the DAY is ignored since `--week` picks the month"),
+            Self::ColumnsOverMonths { columns, months } => write!(
+                f,
+                "`--columns {}` has no effect when printing {} month(s)",
+                columns, months
+            ),
+            Self::YearOutOfRange { given, used } => write!(
+                f,
+                "year {} is out of the range of the calendar, printing {} instead",
+                given, used
+            ),
+            Self::MalformedEvent { line, reason } => {
+                write!(f, "line {} of the events file is skipped: {}", line, reason)
+            }
+        }
+    }
+}
+
+/// Find the flags that parse fine but do nothing together.
+pub fn lint_args(args: &Args) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    if args.explicit_span && args.span && args.months == 1 {
+        lints.push(Lint::SpanSingleMonth);
+    }
+    if args.explicit_day
+        && args
+            .layout
+            .highlights
+            .iter()
+            .any(|i| matches!(i, Highlight::Week { .. }))
+    {
+        lints.push(Lint::WeekWithDay);
+    }
+    if let Some((given, used)) = args.year_out_of_range {
+        lints.push(Lint::YearOutOfRange { given, used });
+    }
+    for (line, reason) in &args.malformed_events {
+        lints.push(Lint::MalformedEvent {
+            line: *line,
+            reason: reason.clone(),
+        });
+    }
+    if args.explicit_columns && !args.auto_columns && args.columns > args.months {
+        lints.push(Lint::ColumnsOverMonths {
+            columns: args.columns,
+            months: args.months,
+        });
+    }
+    lints
+}
+
+/// Where the highlight of [`Args::layout`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HighlightSource {
+    /// The day of [`Args::now`], kept up to date by the sync of the layout.
+    Now,
+    /// Given on the command line (`--highlight`, `--week` or a `@TIMESTAMP`), never replaced.
+    Explicit,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Args {
-    // since calendar is only proleptic, nothing is saved
-    // pub reform: Reform,
+    /// The given reform, since calendar is only proleptic, nothing changes with this.
+    pub reform: Option<Reform>,
     /// non-zero, how many months is in the given span.
     pub months: usize,
     pub span: bool,
@@ -68,10 +197,95 @@ pub struct Args {
     /// This is the basis for calculating the "start date" of the layout.
     pub now: Date,
     pub layout: Layout,
+    /// If [`Layout::highlight`] follows now or was given.
+    pub highlight_source: HighlightSource,
     pub full_year_mode: bool,
+    /// If true, the lints are errors (see [`lint_args`]).
+    pub strict: bool,
+    /// If `--span` (or `-3`) was given on the command line, not just the configuration.
+    pub explicit_span: bool,
+    /// If the DAY positional was given.
+    pub explicit_day: bool,
+    /// If `--columns` was given on the command line.
+    pub explicit_columns: bool,
+    /// If `--width` was given, [`Self::refresh`] keeps it instead of the width of the terminal.
+    pub explicit_width: bool,
+    /// If a date was given (the positionals), [`Self::refresh`] keeps it instead of today.
+    pub explicit_date: bool,
+    /// If the starting weekday was given (in the configuration or on the command line), switching
+    /// the calendar keeps it instead of its default.
+    pub explicit_weekday: bool,
+    /// The given year and the year used instead if it was out of range.
+    pub year_out_of_range: Option<(IYear, IYear)>,
+    /// If true, the screen is cleared before printing.
+    pub clear: bool,
+    /// If given, print every this many seconds (implies [`Self::clear`]).
+    pub watch: Option<u64>,
+    /// If given, print the weekday of this month and day over [`Self::years`] instead.
+    pub weekday_of: Option<(UMonth, UMonthDay)>,
+    /// How many years `--weekday-of` prints starting from the year of [`Self::now`].
+    pub years: usize,
+    /// If true, read keys to move the months (see [`crate::interactive`]).
+    pub interactive: bool,
+    /// How many of [`Self::months`] are before the month of [`Self::now`] (`-B`, no span).
+    pub before: usize,
+    /// The lines of the events file that are skipped with why (see [`Lint::MalformedEvent`]).
+    pub malformed_events: Vec<(usize, String)>,
+    /// How the calendar is printed.
+    pub output: OutputFormat,
+    /// The side of the extra month when spanning an even number of months.
+    pub span_bias: SpanBias,
+    /// How the week numbers (`-w`) are counted.
+    pub week_numbering: WeekNumConfig,
 }
 
 impl Args {
+    /// Switch to Jalali and default the starting weekday to Saturday (see
+    /// [`Self::explicit_weekday`]).
+    fn set_jalali(&mut self) {
+        self.now = self.now.to_jalali();
+        if !self.explicit_weekday {
+            self.layout.base_row.column.content.grid.base_weekday = Weekday::SAT;
+        }
+    }
+
+    /// Switch to Gregorian and default the starting weekday to Sunday (see
+    /// [`Self::explicit_weekday`]).
+    fn set_gregorian(&mut self) {
+        // now is either today or a saturated value so it is always convertible
+        self.now = self.now.to_gregorian();
+        if !self.explicit_weekday {
+            self.layout.base_row.column.content.grid.base_weekday = Weekday::SUN;
+        }
+    }
+
+    /// Apply the configuration file values as if they were the built-in defaults.
+    pub fn apply_config(&mut self, config: &Config) {
+        match config.jalali {
+            Some(true) => self.set_jalali(),
+            Some(false) => self.set_gregorian(),
+            None => {}
+        }
+        // after Jalali since that defaults to Saturday
+        if let Some(weekday) = &config.weekday {
+            self.explicit_weekday = true;
+            self.layout.base_row.column.content.grid.base_weekday = weekday.clone();
+        }
+        if let Some(color) = &config.color {
+            self.color = color.clone();
+        }
+        if let Some(week_numbers) = config.week_numbers {
+            self.layout.base_row.column.content.weeknums =
+                week_numbers.then_some(WeekNumConfig::Based);
+        }
+        if let Some(vertical) = config.vertical {
+            self.layout.base_row.column.vertical = vertical;
+        }
+        if let Some(span) = config.span {
+            self.span = span;
+        }
+    }
+
     /// Set now field and sync it with the layout.
     fn sync_layout(&mut self) {
         self.layout.base_row.column.content.grid.date = self.start_month();
@@ -79,20 +293,70 @@ impl Args {
         self.layout.next_row_after_column = self.suggested_columns();
 
         // Default to having now highlighted, this differs with cal
-        match self.layout.highlight {
-            Some(Highlight::Day(_)) | None => {
-                self.layout.highlight = Some(Highlight::Day(self.now.clone()))
-            }
-            Some(Highlight::Week(_)) => {}
+        if self.highlight_source == HighlightSource::Now {
+            self.layout.highlights = vec![Highlight::Day(self.now.clone())];
+        }
+
+        // no preset or override survives an explicit no
+        if self.color == ColorMode::Never {
+            self.layout.base_row.column.content.grid.style = StyleConfig {
+                today: Style::PLAIN,
+                weekend: Style::PLAIN,
+            };
         }
 
         let column = &mut self.layout.base_row.column;
-        if column.vertical {
+        // the week numbers under their weeks
+        if column.transposed() {
             column.content.weeknums_before_grid = false;
+        }
+        if column.vertical {
             self.layout.common_weekday = Some(true);
         }
     }
 
+    /// Drop the highlight of now when the text is not printed to a terminal (like util-linux
+    /// `cal`), unless `--color always` asks for the escapes anyway.
+    ///
+    /// The other outputs keep it since the highlighted days are a part of their values.
+    pub fn set_output_terminal(&mut self, is_terminal: bool) {
+        if !is_terminal
+            && self.color != ColorMode::Always
+            && self.output == OutputFormat::Text
+            && self.highlight_source == HighlightSource::Now
+        {
+            self.set_highlights(Vec::new());
+        }
+    }
+
+    /// Highlight the given sections (or nothing) instead of now.
+    fn set_highlights(&mut self, highlights: Vec<Highlight>) {
+        self.layout.highlights = highlights;
+        self.highlight_source = HighlightSource::Explicit;
+    }
+
+    /// Follow the given today and width of the terminal (see [`terminal_width`]) for the next
+    /// print of `--watch` unless a date or `--width` was given.
+    ///
+    /// The rest is kept as parsed, the configuration and the events are not read again.
+    pub fn refresh(&mut self, today: Date, width_chars: usize) {
+        if !self.explicit_date {
+            self.now = today.convert_to(self.now.kind());
+        }
+        if !self.explicit_width {
+            self.width_chars = width_chars;
+        }
+        self.sync_layout();
+    }
+
+    /// Print the months around the given date instead, keeping the highlight.
+    pub fn set_anchor(&mut self, anchor: Date) {
+        let highlights = std::mem::take(&mut self.layout.highlights);
+        self.now = anchor;
+        self.sync_layout();
+        self.layout.highlights = highlights;
+    }
+
     /// What is the earliest month to be printed.
     ///
     /// This removes the need for "spanning" mechanism to complicate [`CalendarLayout`].
@@ -108,22 +372,108 @@ impl Args {
         let mut now = self.now.clone();
         now.set_saturating_day(1);
 
-        if !self.span || (self.months == 1) {
-            return now;
+        // `-B` is zero unless given and never given with a span
+        let mut months_before = self.before;
+        if self.span && self.months > 1 {
+            // basically if in span mode, put the given time at the center of the span which
+            // naturally sends the start month half of the span behind
+            months_before = (self.months - 1) / 2; // remove the initial month
+            let months_before_rem = (self.months - 1) % 2;
+            if self.span_bias == SpanBias::Before {
+                months_before += months_before_rem; // if not even, put the odd one behind the current
+            }
         }
-        // basically if in span mode, put the given time at the center of the span which naturally
-        // sends the start month half of the span behind
-        let mut months_before = (self.months - 1) / 2; // remove the initial month
-        let months_before_rem = (self.months - 1) % 2;
-        months_before += months_before_rem; // if not even, put the odd one behind the current
 
-        let months_before: jelal::IDayDiff =
-            months_before.try_into().unwrap_or(jelal::IDayDiff::MAX);
+        // bounded by `MAX_MONTHS` but never cast blindly
+        let months_before =
+            jelal::IDayDiff::try_from(months_before).unwrap_or(jelal::IDayDiff::MAX);
 
         now.set_saturating_months_offset(-months_before);
         now
     }
 
+    /// Set the year of now saturating to the range of the calendar (see [`Lint::YearOutOfRange`]).
+    fn set_year(&mut self, year: IYear) {
+        if let Err(used) = self.now.try_set_year(year) {
+            self.now.set_saturating_year(year);
+            self.year_out_of_range = Some((year, used));
+        }
+    }
+
+    /// Parse the seconds of a `@TIMESTAMP` (`-1`, `1.5`), truncating the fraction toward negative
+    /// infinity so `-0.5` is still in the last second of the day before the epoch.
+    fn timestamp_seconds(s: &str) -> Result<i64, String> {
+        let (integer, fraction) = match s.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (s, None),
+        };
+        let all_digits = |i: &str| !i.is_empty() && i.bytes().all(|b| b.is_ascii_digit());
+        let negative = integer.starts_with('-');
+        if !all_digits(integer.strip_prefix('-').unwrap_or(integer))
+            || fraction.is_some_and(|i| !all_digits(i))
+        {
+            return Err(format!("`{}` is not a number of seconds", s));
+        }
+
+        // only an overflow is left to fail
+        let seconds: i64 = integer
+            .parse()
+            .map_err(|_| Self::timestamp_out_of_range())?;
+        if negative && fraction.is_some_and(|i| i.bytes().any(|b| b != b'0')) {
+            return seconds
+                .checked_sub(1)
+                .ok_or_else(Self::timestamp_out_of_range);
+        }
+        Ok(seconds)
+    }
+
+    /// The error of a `@TIMESTAMP` out of the range of [`Timestamp`].
+    fn timestamp_out_of_range() -> String {
+        format!(
+            "out of the range of {} to {} seconds",
+            Timestamp::MIN.as_second(),
+            Timestamp::MAX.as_second()
+        )
+    }
+
+    /// Split a `YYYY-MM` or `YYYY/MM` positional (a 3 or 4 digit year) into the year and the month.
+    fn split_year_month(s: &str) -> Option<(IYear, &str)> {
+        let (year, month) = s.split_once(['-', '/'])?;
+        let digits = |i: &str, lens: std::ops::RangeInclusive<usize>| {
+            lens.contains(&i.len()) && i.bytes().all(|b| b.is_ascii_digit())
+        };
+        if !digits(year, 3..=4) || !digits(month, 1..=2) {
+            return None;
+        }
+        Some((year.parse().ok()?, month))
+    }
+
+    /// Parse the value of `--highlight` in the calendar of now, `none` is no highlight at all.
+    fn parse_highlight(&self, s: &str) -> Result<Option<Highlight>, clap::Error> {
+        let calendar = self.now.kind();
+        let date = if s.eq_ignore_ascii_case("none") {
+            return Ok(None);
+        } else if s.eq_ignore_ascii_case("today") {
+            Date::Gregorian(jiff::Zoned::now().date()).convert_to(calendar)
+        } else {
+            parse_date(calendar, s).map_err(|e| {
+                Self::error(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "expected YYYY/MM/DD, `today` or `none` to highlight ({})",
+                        e
+                    ),
+                )
+            })?
+        };
+        Ok(Some(Highlight::Day(date)))
+    }
+
+    /// The date of a `@TIMESTAMP` (seconds since the Unix epoch) in the given zone.
+    fn timestamp_date(seconds: i64, tz: TimeZone) -> Result<civil::Date, jiff::Error> {
+        Ok(Timestamp::new(seconds, 0)?.to_zoned(tz).date())
+    }
+
     /// How many months does should this calendar print.
     ///
     /// This keeps the "fitting" concern away from [`CalendarLayout`].
@@ -139,28 +489,161 @@ impl Args {
     }
 }
 
+/// Parse a number of months up to [`Args::MAX_MONTHS`].
+fn parse_months(s: &str) -> Result<usize, String> {
+    let v: usize = s.parse().map_err(|e: ParseIntError| e.to_string())?;
+    if v > Args::MAX_MONTHS {
+        return Err(format!(
+            "at most {} months can be printed",
+            Args::MAX_MONTHS
+        ));
+    }
+    Ok(v)
+}
+
+/// Parse a number of months like [`parse_months`], negative for the months ending at this one.
+fn parse_signed_months(s: &str) -> Result<i64, String> {
+    let v: i64 = s.parse().map_err(|e: ParseIntError| e.to_string())?;
+    if v.unsigned_abs() > Args::MAX_MONTHS as u64 {
+        return Err(format!(
+            "at most {} months can be printed",
+            Args::MAX_MONTHS
+        ));
+    }
+    Ok(v)
+}
+
+/// Parse the comma separated weekdays of `--weekend`, none for the weekend of the calendar.
+fn parse_weekend(s: &str) -> Result<Option<Vec<Weekday>>, String> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.split(',')
+        .map(|i| parse_weekday(i.trim()).map_err(|e| format!("`{}`: {}", i, e)))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// Parse the `MM-DD` of `--weekday-of` (whether the day exists is up to the year).
+fn parse_month_day(s: &str) -> Result<(UMonth, UMonthDay), String> {
+    let invalid = || format!("expected MM-DD, got `{}`", s);
+    let (month, day) = s.split_once('-').ok_or_else(invalid)?;
+    let month: UMonth = month.parse().map_err(|_| invalid())?;
+    let day: UMonthDay = day.parse().map_err(|_| invalid())?;
+    if !(1..=12).contains(&month) || !(1..=MonthDay::MAX_DAY).contains(&day) {
+        return Err(format!("no month or day of the month matches `{}`", s));
+    }
+    Ok((month, day))
+}
+
 impl Args {
     pub const MONTHS_1_LONG: &str = "one";
     pub const MONTHS_3_LONG: &str = "three";
     pub const MONTHS_12_LONG: &str = "twelve";
     pub const MONTHS_LONG: &str = "months";
     pub const SPAN_LONG: &str = "span";
+    pub const NO_SPAN_LONG: &str = "no-span";
+    pub const SPAN_BIAS_LONG: &str = "span-bias";
     pub const SUNDAY_LONG: &str = "sunday";
     pub const MONDAY_LONG: &str = "monday";
     pub const WEEKDAY_LONG: &str = "weekday";
+    pub const WEEKEND_LONG: &str = "weekend";
     pub const ORDINAL_LONG: &str = "julian";
+    pub const SHOW_ADJACENT_LONG: &str = "show-adjacent";
+    pub const TRIM_WEEKS_LONG: &str = "trim-weeks";
+    pub const MOON_LONG: &str = "moon";
+    pub const DUAL_LONG: &str = "dual";
+    pub const HIGHLIGHT_LONG: &str = "highlight";
+    pub const NO_HIGHLIGHT_LONG: &str = "no-highlight";
+    pub const EVENTS_LONG: &str = "events";
+    pub const LIST_EVENTS_LONG: &str = "list-events";
     pub const REFORM_LONG: &str = "reform";
     pub const ISO_LONG: &str = "iso";
     pub const YEAR_LONG: &str = "year";
     pub const WEEK_LONG: &str = "week";
+    pub const NO_WEEK_NUMBERS_LONG: &str = "no-week-numbers";
+    pub const WEEK_NUMBERING_LONG: &str = "week-numbering";
     pub const VERTICAL_LONG: &str = "vertical";
+    pub const NO_VERTICAL_LONG: &str = "no-vertical";
+    pub const TRANSPOSE_LONG: &str = "transpose";
+    pub const NO_TRANSPOSE_LONG: &str = "no-transpose";
     pub const COLUMNS_LONG: &str = "columns";
+    pub const WIDTH_LONG: &str = "width";
     pub const COLOR_LONG: &str = "color";
+    pub const OUTPUT_LONG: &str = "output";
+    pub const STYLE_PRESET_LONG: &str = "style-preset";
+    pub const STYLE_LONG: &str = "style";
+    pub const HEADER_FORMAT_LONG: &str = "header-format";
+    pub const YEAR_HEADER_FORMAT_LONG: &str = "year-header-format";
     pub const JALALI_LONG: &str = "jalali";
+    pub const NO_JALALI_LONG: &str = "no-jalali";
+    pub const STRICT_LONG: &str = "strict";
+    pub const CLEAR_LONG: &str = "clear";
+    pub const WATCH_LONG: &str = "watch";
+    pub const WEEKDAY_OF_LONG: &str = "weekday-of";
+    pub const YEARS_LONG: &str = "years";
+    pub const INTERACTIVE_LONG: &str = "interactive";
+    pub const HELP_EXAMPLES_LONG: &str = "help-examples";
+    pub const AFTER_LONG: &str = "after";
+    pub const BEFORE_LONG: &str = "before";
     pub const POSITIONAL_1_ID: &str = "opt1";
     pub const POSITIONAL_2_ID: &str = "opt2";
     pub const POSITIONAL_3_ID: &str = "opt3";
 
+    // the sections of `--help`
+    pub const CALENDAR_HEADING: &str = "Calendar";
+    pub const RANGE_HEADING: &str = "Range";
+    pub const LAYOUT_HEADING: &str = "Layout";
+    pub const WEEK_NUMBERS_HEADING: &str = "Week numbers";
+    pub const HIGHLIGHTING_HEADING: &str = "Highlighting";
+    pub const OUTPUT_HEADING: &str = "Output";
+
+    /// The invocations of `--help-examples` (without `cal`) and what they print.
+    pub const EXAMPLES: &[(&str, &str)] = &[
+        ("", "this month with today highlighted"),
+        ("-3", "the previous, this and the next month"),
+        ("-A 2 -B 1", "a month before this one and two after it"),
+        ("-J -y 1404", "the whole Jalali year 1404"),
+        (
+            "-J 1403 1405",
+            "the Jalali years 1403, 1404 and 1405 each under its own header",
+        ),
+        ("--no-jalali 2025-11", "November 2025 in Gregorian"),
+        (
+            "-J --dual 1 8 1404",
+            "Aban 1404 with the Gregorian day after each day",
+        ),
+        (
+            "-w 45 --no-jalali 2025",
+            "the month of the week 45 of 2025 with the week highlighted",
+        ),
+        (
+            "-J --highlight 1404/08/12 --highlight 1404/08/20 1 8 1404",
+            "Aban 1404 with two days highlighted",
+        ),
+        (
+            "-J --weekend thu,fri -3",
+            "three months with Thursday and Friday in red",
+        ),
+        (
+            "--moon --trim-weeks",
+            "this month with the new and full moons and no empty weeks",
+        ),
+        (
+            "@1762545600",
+            "the month of a Unix timestamp with its day highlighted",
+        ),
+        (
+            "--no-jalali --weekday-of 12-25 --years 3",
+            "the weekday of December 25 in this year and the next two",
+        ),
+    ];
+
+    /// The most months printed at once (a century), more is too long to be of any use.
+    pub const MAX_MONTHS: usize = 1200;
+    /// The most whole years printed by a range of two YEARs.
+    pub const MAX_YEARS: usize = 50;
+
     pub const MONTHS_SETTERS_ARGS: &[&str] = &[
         Self::MONTHS_1_LONG,
         Self::MONTHS_3_LONG,
@@ -170,74 +653,212 @@ impl Args {
     pub const REFORM_SETTERS_ARGS: &[&str] = &[Self::REFORM_LONG, Self::ISO_LONG];
     pub const WEEKDAY_SETTERS_ARGS: &[&str] =
         &[Self::SUNDAY_LONG, Self::MONDAY_LONG, Self::WEEKDAY_LONG];
+    // the negating pairs, the last one given wins
+    pub const SPAN_SETTERS_ARGS: &[&str] = &[Self::SPAN_LONG, Self::NO_SPAN_LONG];
+    pub const WEEK_SETTERS_ARGS: &[&str] = &[Self::WEEK_LONG, Self::NO_WEEK_NUMBERS_LONG];
+    pub const VERTICAL_SETTERS_ARGS: &[&str] = &[Self::VERTICAL_LONG, Self::NO_VERTICAL_LONG];
+    pub const TRANSPOSE_SETTERS_ARGS: &[&str] = &[Self::TRANSPOSE_LONG, Self::NO_TRANSPOSE_LONG];
+    pub const JALALI_SETTERS_ARGS: &[&str] = &[Self::JALALI_LONG, Self::NO_JALALI_LONG];
+    // `-A` and `-B` decide the months on their own
+    pub const AROUND_CONFLICTS_ARGS: &[&str] = &[
+        Self::MONTHS_1_LONG,
+        Self::MONTHS_3_LONG,
+        Self::MONTHS_12_LONG,
+        Self::MONTHS_LONG,
+        Self::SPAN_LONG,
+        Self::YEAR_LONG,
+    ];
+
+    /// The text of `--help-examples`.
+    pub fn examples_help() -> String {
+        let mut v = String::from("Examples:\n");
+        for (args, description) in Self::EXAMPLES {
+            v += &format!(
+                "  {}\n      {}\n",
+                format!("cal {}", args).trim_end(),
+                description
+            );
+        }
+        v
+    }
 
-    pub fn args() -> [Arg; 20] {
+    pub fn args() -> [Arg; 52] {
         [
             Arg::new(Self::MONTHS_1_LONG)
                 .long(Self::MONTHS_1_LONG)
+                .help_heading(Self::RANGE_HEADING)
                 .short('1')
                 .overrides_with_all(Self::MONTHS_SETTERS_ARGS)
                 .help("print one month (default, equal to `--months 1`)")
                 .action(ArgAction::SetTrue),
             Arg::new(Self::MONTHS_3_LONG)
                 .long(Self::MONTHS_3_LONG)
+                .help_heading(Self::RANGE_HEADING)
                 .short('3')
                 .overrides_with_all(Self::MONTHS_SETTERS_ARGS)
                 .help("print 3 month spanning (equal to `--months 3 --span`)")
                 .action(ArgAction::SetTrue),
             Arg::new(Self::MONTHS_12_LONG)
                 .long(Self::MONTHS_12_LONG)
+                .help_heading(Self::RANGE_HEADING)
                 .short('Y')
                 .overrides_with_all(Self::MONTHS_SETTERS_ARGS)
                 .help("print 11 months after this one (equal to `--months 12`)")
                 .action(ArgAction::SetTrue),
             Arg::new(Self::MONTHS_LONG)
                 .long(Self::MONTHS_LONG)
+                .help_heading(Self::RANGE_HEADING)
                 .short('n')
                 .overrides_with_all(Self::MONTHS_SETTERS_ARGS)
-                .help("print the number of months (starting with this one if not spanning)")
-                .value_parser(value_parser!(usize)),
+                .allow_negative_numbers(true)
+                .help(
+                    "print the number of months (starting with this one if not spanning, ending \
+                     with it if negative)",
+                )
+                .value_parser(parse_signed_months),
+            Arg::new(Self::AFTER_LONG)
+                .long(Self::AFTER_LONG)
+                .help_heading(Self::RANGE_HEADING)
+                .short('A')
+                .value_name("N")
+                .conflicts_with_all(Self::AROUND_CONFLICTS_ARGS)
+                .help("print N months after this one (with `-B`, instead of `--months`)")
+                .value_parser(parse_months),
+            Arg::new(Self::BEFORE_LONG)
+                .long(Self::BEFORE_LONG)
+                .help_heading(Self::RANGE_HEADING)
+                .short('B')
+                .value_name("N")
+                .conflicts_with_all(Self::AROUND_CONFLICTS_ARGS)
+                .help("print N months before this one (with `-A`, instead of `--months`)")
+                .value_parser(parse_months),
             Arg::new(Self::SPAN_LONG)
                 .long(Self::SPAN_LONG)
+                .help_heading(Self::RANGE_HEADING)
                 .short('S')
+                .overrides_with_all(Self::SPAN_SETTERS_ARGS)
                 .help("put the current month in the middle of multiple months")
                 .action(ArgAction::SetTrue),
+            Arg::new(Self::NO_SPAN_LONG)
+                .long(Self::NO_SPAN_LONG)
+                .help_heading(Self::RANGE_HEADING)
+                .overrides_with_all(Self::SPAN_SETTERS_ARGS)
+                .help("start from the current month (default, negates `--span` and `-3`)")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::SPAN_BIAS_LONG)
+                .long(Self::SPAN_BIAS_LONG)
+                .help_heading(Self::RANGE_HEADING)
+                .value_name("SIDE")
+                .overrides_with(Self::SPAN_BIAS_LONG)
+                .value_parser(SpanBias::PARSER_MAP)
+                .ignore_case(true)
+                .help(format!(
+                    "the side of this month that gets the extra month of an even span (`before` \
+                     or `after`) [default: {}]",
+                    SpanBias::PARSER_DEFAULT
+                )),
             Arg::new(Self::SUNDAY_LONG)
                 .long(Self::SUNDAY_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
                 .short('s')
                 .overrides_with_all(Self::WEEKDAY_SETTERS_ARGS)
                 .help("set Sunday as the first weekday (default, equal to `--weekday Sunday`)")
                 .action(ArgAction::SetTrue),
             Arg::new(Self::MONDAY_LONG)
                 .long(Self::MONDAY_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
                 .short('m')
                 .overrides_with_all(Self::WEEKDAY_SETTERS_ARGS)
                 .help("set Monday as the first weekday (equal to `--weekday Monday`)")
                 .action(ArgAction::SetTrue),
             Arg::new(Self::WEEKDAY_LONG)
                 .long(Self::WEEKDAY_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
                 .overrides_with_all(Self::WEEKDAY_SETTERS_ARGS)
                 .value_parser(parse_weekday)
                 .help("set the given as the first weekday (`sunday = 0`)"),
+            Arg::new(Self::WEEKEND_LONG)
+                .long(Self::WEEKEND_LONG)
+                .help_heading(Self::HIGHLIGHTING_HEADING)
+                .value_name("DAYS")
+                .num_args(0..=1) // if not given don't push the default
+                .overrides_with(Self::WEEKEND_LONG)
+                .default_missing_value("")
+                .value_parser(parse_weekend)
+                .help(
+                    "color the weekend, the given comma separated weekdays or the one of the \
+                     calendar (`fri` in Jalali, `sat,sun` otherwise)",
+                ),
             Arg::new(Self::ORDINAL_LONG)
                 .long(Self::ORDINAL_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
                 .short('j')
                 .overrides_with(Self::ORDINAL_LONG)
                 .help("use ordinals instead of day of month")
                 .action(ArgAction::SetTrue),
+            Arg::new(Self::SHOW_ADJACENT_LONG)
+                .long(Self::SHOW_ADJACENT_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .help("fill the empty cells with the days of the neighbor months (dimmed)")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::TRIM_WEEKS_LONG)
+                .long(Self::TRIM_WEEKS_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .help("drop the trailing weeks without a day of the month")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::MOON_LONG)
+                .long(Self::MOON_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .help("mark the days of new (●) and full (○) moons (±1 day)")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::DUAL_LONG)
+                .long(Self::DUAL_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .help("follow each day with the same day in the other calendar (12/4)")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::HIGHLIGHT_LONG)
+                .long(Self::HIGHLIGHT_LONG)
+                .help_heading(Self::HIGHLIGHTING_HEADING)
+                .value_name("DATE")
+                .action(ArgAction::Append)
+                .help(
+                    "highlight DATE (`YYYY/MM/DD` in the printed calendar), `today` or `none` \
+                     instead of the given day, repeat for more days",
+                ),
+            Arg::new(Self::NO_HIGHLIGHT_LONG)
+                .long(Self::NO_HIGHLIGHT_LONG)
+                .help_heading(Self::HIGHLIGHTING_HEADING)
+                .conflicts_with(Self::HIGHLIGHT_LONG)
+                .help("highlight no day, not even with `--week` or a @TIMESTAMP")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::EVENTS_LONG)
+                .long(Self::EVENTS_LONG)
+                .help_heading(Self::HIGHLIGHTING_HEADING)
+                .value_name("FILE")
+                .overrides_with(Self::EVENTS_LONG)
+                .help("highlight the days of FILE, each line a `YYYY/MM/DD` and a description"),
+            Arg::new(Self::LIST_EVENTS_LONG)
+                .long(Self::LIST_EVENTS_LONG)
+                .help_heading(Self::HIGHLIGHTING_HEADING)
+                .requires(Self::EVENTS_LONG)
+                .help("list the events of the printed months under the calendar")
+                .action(ArgAction::SetTrue),
             Arg::new(Self::REFORM_LONG)
                 .long(Self::REFORM_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
                 .overrides_with_all(Self::REFORM_SETTERS_ARGS)
                 .value_parser(Reform::PARSER_MAP)
                 .ignore_case(true)
                 .help("reform Gregorian calendar (for now, no option but proleptic is supported)"),
             Arg::new(Self::ISO_LONG)
                 .long(Self::ISO_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
                 .overrides_with_all(Self::REFORM_SETTERS_ARGS)
                 .help("reform Gregorian calendar in ISO (equal to `--reform iso`)")
                 .action(ArgAction::SetTrue),
             Arg::new(Self::YEAR_LONG)
                 .long(Self::YEAR_LONG)
+                .help_heading(Self::RANGE_HEADING)
                 .short('y')
                 .help("print the full year calendar")
                 .overrides_with(Self::YEAR_LONG)
@@ -245,30 +866,70 @@ impl Args {
                 .action(ArgAction::SetTrue),
             Arg::new(Self::WEEK_LONG)
                 .long(Self::WEEK_LONG)
+                .help_heading(Self::WEEK_NUMBERS_HEADING)
                 .short('w')
                 .num_args(0..=1) // if not given don't push the default
-                .overrides_with(Self::WEEK_LONG)
+                .overrides_with_all(Self::WEEK_SETTERS_ARGS)
                 .default_missing_value("")
                 .value_parser(|s: &str| -> Result<Option<usize>, String> {
                     if s.is_empty() {
                         return Ok(None);
                     }
                     let v: usize = s.parse().map_err(|e: ParseIntError| e.to_string())?;
-                    if (1..=54).contains(&v) {
-                        Ok(Some(v - 1))
+                    if (1..=53).contains(&v) {
+                        Ok(Some(v))
                     } else {
-                        Err("a week number must be between 1..=54".to_string())
+                        // as printed, no year reaches a week 54
+                        Err("a week number must be between 1..=53".to_string())
                     }
                 })
                 .help("print the week numbers in US or ISO format"),
+            Arg::new(Self::NO_WEEK_NUMBERS_LONG)
+                .long(Self::NO_WEEK_NUMBERS_LONG)
+                .help_heading(Self::WEEK_NUMBERS_HEADING)
+                .overrides_with_all(Self::WEEK_SETTERS_ARGS)
+                .help("do not print the week numbers (default, negates `--week`)")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::WEEK_NUMBERING_LONG)
+                .long(Self::WEEK_NUMBERING_LONG)
+                .help_heading(Self::WEEK_NUMBERS_HEADING)
+                .value_name("SYSTEM")
+                .overrides_with(Self::WEEK_NUMBERING_LONG)
+                .value_parser(WeekNumConfig::PARSER_MAP)
+                .ignore_case(true)
+                .help(format!(
+                    "count the weeks from the first base weekday of the year (`based`) or by ISO \
+                     8601 (`iso`, weeks of Monday to Sunday) [default: {}]",
+                    WeekNumConfig::PARSER_DEFAULT
+                )),
             Arg::new(Self::VERTICAL_LONG)
                 .long(Self::VERTICAL_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
                 .short('v')
-                .overrides_with(Self::VERTICAL_LONG)
+                .overrides_with_all(Self::VERTICAL_SETTERS_ARGS)
                 .help("print a week as a vertical line instead")
                 .action(ArgAction::SetTrue),
+            Arg::new(Self::NO_VERTICAL_LONG)
+                .long(Self::NO_VERTICAL_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .overrides_with_all(Self::VERTICAL_SETTERS_ARGS)
+                .help("print a week as a horizontal line (default, negates `--vertical`)")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::TRANSPOSE_LONG)
+                .long(Self::TRANSPOSE_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .overrides_with_all(Self::TRANSPOSE_SETTERS_ARGS)
+                .help("print a week as a vertical line with the weekdays kept in each month")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::NO_TRANSPOSE_LONG)
+                .long(Self::NO_TRANSPOSE_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .overrides_with_all(Self::TRANSPOSE_SETTERS_ARGS)
+                .help("keep the weeks as rows in each month (default, negates `--transpose`)")
+                .action(ArgAction::SetTrue),
             Arg::new(Self::COLUMNS_LONG)
                 .long(Self::COLUMNS_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
                 .short('c')
                 .overrides_with(Self::COLUMNS_LONG)
                 .num_args(0..=1) // if not given don't push the default
@@ -280,22 +941,140 @@ impl Args {
                     Ok(Some(v.max(1)))
                 })
                 .help("how many months to fit in one row (`auto` for the length of output)"),
+            Arg::new(Self::WIDTH_LONG)
+                .long(Self::WIDTH_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .value_name("N")
+                .value_parser(value_parser!(u64).range(1..))
+                .overrides_with(Self::WIDTH_LONG)
+                .help("the width of the output in characters [default: `COLUMNS` or the terminal]"),
             Arg::new(Self::COLOR_LONG)
                 .long(Self::COLOR_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
                 .overrides_with(Self::COLOR_LONG)
                 .num_args(0..=1) // if not given don't push the default
                 .default_missing_value(ColorMode::PARSER_DEFAULT)
                 .value_parser(ColorMode::PARSER_MAP)
                 .ignore_case(true)
                 .help("set coloring behavior"),
+            Arg::new(Self::OUTPUT_LONG)
+                .long(Self::OUTPUT_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
+                .value_name("FORMAT")
+                .overrides_with(Self::OUTPUT_LONG)
+                .conflicts_with_all([Self::INTERACTIVE_LONG, Self::WEEKDAY_OF_LONG])
+                .value_parser(OutputFormat::PARSER_MAP)
+                .ignore_case(true)
+                .help(format!(
+                    "print the months as `json` values, `html` tables or `csv`/`tsv` records \
+                     instead of `text` [default: {}]",
+                    OutputFormat::PARSER_DEFAULT
+                )),
+            Arg::new(Self::STYLE_PRESET_LONG)
+                .long(Self::STYLE_PRESET_LONG)
+                .help_heading(Self::HIGHLIGHTING_HEADING)
+                .value_name("PRESET")
+                .overrides_with(Self::STYLE_PRESET_LONG)
+                .value_parser(StyleConfig::PRESETS)
+                .ignore_case(true)
+                .help("set the styles of all the elements at once"),
+            Arg::new(Self::STYLE_LONG)
+                .long(Self::STYLE_LONG)
+                .help_heading(Self::HIGHLIGHTING_HEADING)
+                .value_name("ELEMENT=ATTRIBUTES")
+                .action(ArgAction::Append)
+                .value_parser(StyleConfig::parse_override)
+                .help(format!(
+                    "style an element ({}) on top of the preset (e.g. `today=bold,underline,33`)",
+                    StyleConfig::ELEMENTS.join(", "),
+                )),
+            Arg::new(Self::HEADER_FORMAT_LONG)
+                .long(Self::HEADER_FORMAT_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .value_name("FORMAT")
+                .overrides_with(Self::HEADER_FORMAT_LONG)
+                .value_parser(|s: &str| -> Result<String, String> {
+                    // any date tells if the directives are supported
+                    date_strftime(s, &Date::default()).map_err(|e| e.to_string())?;
+                    Ok(s.to_owned())
+                })
+                .help(format!(
+                    "format the month headers (supports {} and the `date` extensions)",
+                    DATE_DIRECTIVES.join(", ")
+                )),
+            Arg::new(Self::YEAR_HEADER_FORMAT_LONG)
+                .long(Self::YEAR_HEADER_FORMAT_LONG)
+                .help_heading(Self::LAYOUT_HEADING)
+                .value_name("FORMAT")
+                .overrides_with(Self::YEAR_HEADER_FORMAT_LONG)
+                .value_parser(|s: &str| -> Result<String, String> {
+                    date_strftime(s, &Date::default()).map_err(|e| e.to_string())?;
+                    Ok(s.to_owned())
+                })
+                .help("format the year line of a whole year like --header-format (`%Y / %EG`)"),
             Arg::new(Self::JALALI_LONG)
                 .long(Self::JALALI_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
                 .short('J')
+                .overrides_with_all(Self::JALALI_SETTERS_ARGS)
                 .help("print the calendar in Jalali and default the starting weekday to Saturday")
                 .action(ArgAction::SetTrue),
+            Arg::new(Self::NO_JALALI_LONG)
+                .long(Self::NO_JALALI_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
+                .overrides_with_all(Self::JALALI_SETTERS_ARGS)
+                .help("print the calendar in Gregorian and default the starting weekday to Sunday")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::STRICT_LONG)
+                .long(Self::STRICT_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
+                .help("fail on flags that have no effect together instead of warning")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::CLEAR_LONG)
+                .long(Self::CLEAR_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
+                .help("clear the screen before printing")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::WATCH_LONG)
+                .long(Self::WATCH_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
+                .value_name("SECS")
+                .value_parser(value_parser!(u64).range(1..))
+                .help("print again every SECS seconds until interrupted (implies `--clear`)"),
+            Arg::new(Self::INTERACTIVE_LONG)
+                .long(Self::INTERACTIVE_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
+                .short('i')
+                .conflicts_with(Self::WATCH_LONG)
+                .help("page the months with the arrows or h/l, y/Y for years, t for today, q quits")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::WEEKDAY_OF_LONG)
+                .long(Self::WEEKDAY_OF_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
+                .value_name("MM-DD")
+                .value_parser(parse_month_day)
+                .overrides_with(Self::WEEKDAY_OF_LONG)
+                .help("instead of the calendar, print the weekday of MM-DD in the following years"),
+            Arg::new(Self::YEARS_LONG)
+                .long(Self::YEARS_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
+                .value_name("N")
+                .requires(Self::WEEKDAY_OF_LONG)
+                .value_parser(value_parser!(u64).range(1..))
+                .overrides_with(Self::YEARS_LONG)
+                .help("how many years `--weekday-of` prints [default: 5]"),
+            Arg::new(Self::HELP_EXAMPLES_LONG)
+                .long(Self::HELP_EXAMPLES_LONG)
+                .help_heading(Self::OUTPUT_HEADING)
+                .help("print some example invocations and exit")
+                .action(ArgAction::SetTrue),
             Arg::new(Self::POSITIONAL_1_ID)
-                .value_name("[[[DAY] MONTH] YEAR]|MONTH|@TIMESTAMP")
-                .help("optionally give a `@timestamp`, month name or date in `dmy` order"),
+                .value_name("[[[DAY] MONTH] YEAR]|MONTH|YEAR-MONTH|YEAR YEAR|@TIMESTAMP")
+                .help(
+                    "optionally give a `@timestamp`, month name, `YYYY-MM` (or `YYYY/MM`), date \
+                     in `dmy` order (or a year and a month) or the first and the last of a range \
+                     of whole years",
+                ),
             Arg::new(Self::POSITIONAL_2_ID).hide(true),
             Arg::new(Self::POSITIONAL_3_ID).hide(true),
         ]
@@ -305,6 +1084,8 @@ impl Args {
 impl CommandFactory for Args {
     fn command() -> Command {
         command!(/* with version, about and author */)
+            .disable_version_flag(true)
+            .args(build_info::args())
             // TODO add a -c/--calendar that passes to jiff-icu
             .args(Self::args())
     }
@@ -317,47 +1098,118 @@ impl CommandFactory for Args {
 impl Default for Args {
     fn default() -> Self {
         Self {
+            reform: None,
             months: 1.try_into().unwrap(),
             span: false,
             color: ColorMode::Auto,
             columns: 3,
             auto_columns: true,
             now: Date::Gregorian(jiff::Zoned::now().date()),
-            width_chars: terminal_size::terminal_size()
-                .map(|(w, _)| w.0)
-                .unwrap_or(80) as usize,
+            width_chars: terminal_width(std::env::var("COLUMNS").ok().as_deref()),
             // Doesn't matter what it is as of now.
             layout: Default::default(),
+            highlight_source: HighlightSource::Now,
             full_year_mode: false,
+            strict: false,
+            explicit_span: false,
+            explicit_day: false,
+            explicit_columns: false,
+            explicit_width: false,
+            explicit_date: false,
+            explicit_weekday: false,
+            year_out_of_range: None,
+            clear: false,
+            watch: None,
+            weekday_of: None,
+            years: 5,
+            interactive: false,
+            before: 0,
+            malformed_events: Vec::new(),
+            output: OutputFormat::Text,
+            span_bias: SpanBias::Before,
+            week_numbering: WeekNumConfig::Based,
         }
     }
 }
 
 impl FromArgMatches for Args {
     fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        build_info::exit_on_version(&Self::command(), matches);
+        if matches.get_flag(Self::HELP_EXAMPLES_LONG) {
+            print!("{}", Self::examples_help());
+            std::process::exit(0);
+        }
         let mut v = Self::default();
+        let (config, warnings) =
+            Config::load_default().map_err(|e| Self::error(ErrorKind::InvalidValue, e))?;
+        for warning in warnings {
+            eprintln!("warning: {}", warning);
+        }
+        v.apply_config(&config);
         v.update_from_arg_matches(matches)?;
         Ok(v)
     }
 
     fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
         // flags
+        self.strict = self.strict || matches.get_flag(Self::STRICT_LONG);
+        if let Some(&secs) = matches.get_one::<u64>(Self::WATCH_LONG) {
+            self.watch = Some(secs);
+        }
+        self.interactive = self.interactive || matches.get_flag(Self::INTERACTIVE_LONG);
+        self.clear = self.clear
+            || self.watch.is_some()
+            || self.interactive
+            || matches.get_flag(Self::CLEAR_LONG);
         if matches.get_flag(Self::SPAN_LONG) {
             self.span = true;
         }
+        if let Some(&month_day) = matches.get_one::<(UMonth, UMonthDay)>(Self::WEEKDAY_OF_LONG) {
+            self.weekday_of = Some(month_day);
+        }
+        if let Some(&years) = matches.get_one::<u64>(Self::YEARS_LONG) {
+            self.years = years as usize;
+        }
         if matches.get_flag(Self::ORDINAL_LONG) {
             self.layout.base_row.column.content.grid.ordinal_mode = true;
         }
+        if matches.get_flag(Self::SHOW_ADJACENT_LONG) {
+            self.layout.base_row.column.content.grid.adjacent_days = true;
+        }
+        if matches.get_flag(Self::TRIM_WEEKS_LONG) {
+            self.layout.base_row.column.content.trim_empty_weeks = true;
+        }
+        if matches.get_flag(Self::MOON_LONG) {
+            self.layout.base_row.column.content.grid.moon = true;
+        }
         if matches.get_flag(Self::VERTICAL_LONG) {
             self.layout.base_row.column.vertical = true;
+        } else if matches.get_flag(Self::NO_VERTICAL_LONG) {
+            self.layout.base_row.column.vertical = false;
+        }
+        if matches.get_flag(Self::TRANSPOSE_LONG) {
+            self.layout.base_row.column.content.transposed = true;
+        } else if matches.get_flag(Self::NO_TRANSPOSE_LONG) {
+            self.layout.base_row.column.content.transposed = false;
         }
 
+        // the calendar never replaces a given weekday, wherever they are in the arguments
+        if Self::WEEKDAY_SETTERS_ARGS
+            .iter()
+            .any(|&i| matches.is_explicit(i))
+        {
+            self.explicit_weekday = true;
+        }
         if matches.get_flag(Self::JALALI_LONG) {
-            self.now = match self.now.clone() {
-                Date::Gregorian(date) => Date::Jalali(date.into()),
-                v @ Date::Jalali(_) => v,
-            };
-            self.layout.base_row.column.content.grid.base_weekday = Weekday::SAT;
+            self.set_jalali();
+        } else if matches.get_flag(Self::NO_JALALI_LONG) {
+            self.set_gregorian();
+        }
+        if matches.get_flag(Self::DUAL_LONG) {
+            self.layout.base_row.column.content.grid.secondary = Some(match self.now.kind() {
+                CalendarKind::Jalali => CalendarKind::Gregorian,
+                CalendarKind::Gregorian | CalendarKind::Hijri => CalendarKind::Jalali,
+            });
         }
 
         // MONTHS_SETTERS_ARGS
@@ -368,30 +1220,105 @@ impl FromArgMatches for Args {
             self.span = true;
         } else if matches.get_flag(Self::MONTHS_12_LONG) {
             self.months = 12;
-        } else if let Some(&months) = matches.get_one::<usize>(Self::MONTHS_LONG) {
-            self.months = months.max(1);
+        } else if let Some(&months) = matches.get_one::<i64>(Self::MONTHS_LONG) {
+            // bounded by `MAX_MONTHS` when parsed
+            self.months = (months.unsigned_abs() as usize).max(1);
+            if months < 0 {
+                if matches.get_flag(Self::SPAN_LONG) {
+                    return Err(Self::error(
+                        ErrorKind::ArgumentConflict,
+                        "negative `--months` end at this month so they cannot span around it",
+                    ));
+                }
+                // like `-B` over the span of the configuration too
+                self.before = self.months - 1;
+                self.span = false;
+            }
+        }
+        // after MONTHS_SETTERS_ARGS, the later of it and the span implied by `-3` wins
+        if matches.get_flag(Self::NO_SPAN_LONG)
+            && matches.index_of(Self::NO_SPAN_LONG) > matches.index_of(Self::MONTHS_3_LONG)
+        {
+            self.span = false;
+        }
+        if let Some(&&bias) = matches.get_one::<&SpanBias>(Self::SPAN_BIAS_LONG) {
+            self.span_bias = bias;
+        }
+        let after = matches.get_one::<usize>(Self::AFTER_LONG);
+        let before = matches.get_one::<usize>(Self::BEFORE_LONG);
+        if after.is_some() || before.is_some() {
+            self.before = before.copied().unwrap_or(0);
+            self.months = self.before + 1 + after.copied().unwrap_or(0);
+            if self.months > Self::MAX_MONTHS {
+                return Err(Self::error(
+                    ErrorKind::ValueValidation,
+                    format!("`-A` and `-B` print at most {} months", Self::MAX_MONTHS),
+                ));
+            }
+            // over the span of the configuration too
+            self.span = false;
         }
+        self.explicit_span = self.explicit_span
+            || matches.get_flag(Self::SPAN_LONG)
+            || matches.get_flag(Self::MONTHS_3_LONG);
 
         // REFORM_SETTERS_ARGS
-        // if matches.get_flag(Self::ISO_LONG) {
-        //     self.reform = Reform::Iso;
-        // } else if let Some(&reform) = matches.get_one::<&'static Reform>(Self::REFORM_LONG) {
-        //     self.reform = reform.clone();
-        // }
+        if matches.get_flag(Self::ISO_LONG) {
+            self.reform = Some(Reform::Gregorian);
+        } else if let Some(&reform) = matches.get_one::<&'static Reform>(Self::REFORM_LONG) {
+            self.reform = Some(reform.clone());
+        }
 
         if let Some(columns) = matches.get_one::<Option<usize>>(Self::COLUMNS_LONG) {
             (self.columns, self.auto_columns) = match columns {
                 Some(v) => (*v, false),
                 None => (usize::MAX, true),
             };
+            self.explicit_columns = true;
+        }
+
+        if let Some(&width) = matches.get_one::<u64>(Self::WIDTH_LONG) {
+            self.width_chars = width as usize;
+            self.explicit_width = true;
         }
 
         if let Some(&color) = matches.get_one::<&ColorMode>(Self::COLOR_LONG) {
             self.color = color.clone();
         }
+        if let Some(&&output) = matches.get_one::<&OutputFormat>(Self::OUTPUT_LONG) {
+            self.output = output;
+        }
+
+        if let Some(format) = matches.get_one::<String>(Self::HEADER_FORMAT_LONG) {
+            self.layout.base_row.column.header_format = Some(format.clone());
+        }
+        if let Some(format) = matches.get_one::<String>(Self::YEAR_HEADER_FORMAT_LONG) {
+            self.layout.year_header_format = Some(format.clone());
+        }
+
+        let style = &mut self.layout.base_row.column.content.grid.style;
+        if let Some(&preset) = matches.get_one::<&StyleConfig>(Self::STYLE_PRESET_LONG) {
+            *style = *preset;
+        }
+        // before the overrides so `--style weekend=...` still wins over the color
+        if let Some(weekend) = matches.get_one::<Option<Vec<Weekday>>>(Self::WEEKEND_LONG) {
+            if style.weekend == Style::PLAIN {
+                style.weekend = Style::WEEKEND;
+            }
+            self.layout.base_row.column.content.grid.weekend = weekend.clone();
+        }
+        // in order so the last one given for an element wins
+        for (element, v) in matches
+            .get_many::<(String, Style)>(Self::STYLE_LONG)
+            .into_iter()
+            .flatten()
+        {
+            style.set(element, *v);
+        }
 
         // POSITIONAL
         if let Some(pos1) = matches.get_one::<String>(Self::POSITIONAL_1_ID) {
+            self.explicit_date = true;
             if pos1.starts_with("@") {
                 if matches.is_explicit(Self::POSITIONAL_2_ID)
                     || matches.is_explicit(Self::POSITIONAL_3_ID)
@@ -403,25 +1330,13 @@ impl FromArgMatches for Args {
                 }
 
                 // parse
-                match pos1[1..]
-                    .parse()
-                    .map_err(|e: ParseIntError| e.to_string())
-                    .and_then(|i: i64| {
-                        let tz = jiff::tz::TimeZone::system();
-                        let v = Timestamp::new(i, 0).map_err(|e| e.to_string())?;
-                        let offset = tz.to_offset(v).seconds();
-                        match v.checked_add(ToSpan::seconds(offset)) {
-                            Ok(v) => Ok(v.to_zoned(tz)),
-                            Err(e) => Err(e.to_string()),
-                        }
-                    }) {
+                match Self::timestamp_seconds(&pos1[1..]).and_then(|i| {
+                    Self::timestamp_date(i, TimeZone::system())
+                        .map_err(|_| Self::timestamp_out_of_range())
+                }) {
                     Ok(v) => {
-                        self.now = match self.now {
-                            Date::Jalali(_) => Date::Jalali(v.into()),
-                            Date::Gregorian(_) => Date::Gregorian(v.date()),
-                        };
-                        // will get synced later
-                        self.layout.highlight = Some(Highlight::Day(Default::default()));
+                        self.now = Date::Gregorian(v).convert_to(self.now.kind());
+                        self.set_highlights(vec![Highlight::Day(self.now.clone())]);
                     }
                     Err(e) => {
                         return Err(Self::error(
@@ -430,39 +1345,111 @@ impl FromArgMatches for Args {
                         ));
                     }
                 }
-            } else if let Ok(pos1) = i16::from_str_radix(pos1, 10) {
+            } else if let Some((year, month)) = Self::split_year_month(pos1) {
+                if matches.is_explicit(Self::POSITIONAL_2_ID)
+                    || matches.is_explicit(Self::POSITIONAL_3_ID)
+                {
+                    return Err(Self::error(
+                        ErrorKind::ArgumentConflict,
+                        "given a YEAR-MONTH, no other parameters for setting the date can be used",
+                    ));
+                }
+
+                let month = match self.now {
+                    Date::Jalali(_) => parse_jalali_month(month),
+                    Date::Gregorian(_) => parse_month(month),
+                    Date::Hijri(_) => parse_hijri_month(month),
+                }
+                .map_err(|e| Self::error(ErrorKind::InvalidValue, e))?;
+                self.set_year(year);
+                self.now.set_saturating_month(month);
+                self.layout.base_row.column.year_in_header = true;
+            } else if let Ok(pos1) = pos1.parse::<IYear>() {
+                let mut year_range = false;
                 (|| {
                     let Some(pos2) = matches.get_one::<String>(Self::POSITIONAL_2_ID) else {
                         // pos1 could be the day so we set it here not earlier not to modify
                         // it twice and/or saturate/wrap to make invalid values
-                        self.now.set_saturating_year(pos1 as i32);
+                        self.set_year(pos1);
 
-                        // since year is set, also set the year flag
-                        self.layout.base_row.column.year_in_header = true;
+                        // since year is set, show it above the months
+                        self.layout.year_header = true;
 
                         return Ok(()); // [YEAR]
                     };
 
+                    let Some(pos3) = matches.get_one::<String>(Self::POSITIONAL_3_ID) else {
+                        if !(1..=12).contains(&pos1) {
+                            // a range never ends before it starts so a month after the year is
+                            // the same as YEAR-MONTH
+                            let month = match self.now {
+                                Date::Jalali(_) => parse_jalali_month(pos2),
+                                Date::Gregorian(_) => parse_month(pos2),
+                                Date::Hijri(_) => parse_hijri_month(pos2),
+                            };
+                            if let Ok(month) = month {
+                                self.set_year(pos1);
+                                self.now.set_saturating_month(month);
+                                self.layout.base_row.column.year_in_header = true;
+                                return Ok(()); // [YEAR MONTH]
+                            }
+                        }
+
+                        let Ok(year) = pos2.parse::<IYear>() else {
+                            return Err(Self::error(ErrorKind::InvalidValue, "year is invalid"));
+                        };
+                        if !(1..=12).contains(&pos1) {
+                            // not a month so the whole years from pos1 to pos2
+                            let years = year
+                                .checked_sub(pos1)
+                                .and_then(|i| usize::try_from(i).ok())
+                                .map(|i| i + 1)
+                                .filter(|&i| i <= Self::MAX_YEARS)
+                                .ok_or_else(|| {
+                                    Self::error(
+                                        ErrorKind::ValueValidation,
+                                        format!(
+                                            "a range of years ends at or after its start and \
+                                             prints at most {} years",
+                                            Self::MAX_YEARS
+                                        ),
+                                    )
+                                })?;
+                            self.set_year(pos1);
+                            self.months = 12 * years;
+                            year_range = true;
+                            return Ok(()); // [YEAR YEAR]
+                        }
+
+                        self.set_year(year);
+                        self.now.set_saturating_month(pos1 as u8);
+                        self.layout.base_row.column.year_in_header = true;
+                        return Ok(()); // [[MONTH] YEAR]
+                    };
+
                     let month = match self.now {
                         Date::Jalali(_) => parse_jalali_month(pos2),
                         Date::Gregorian(_) => parse_month(pos2),
+                        Date::Hijri(_) => parse_hijri_month(pos2),
                     }
                     .map_err(|e| Self::error(ErrorKind::InvalidValue, e))?;
                     self.now.set_saturating_month(month);
 
-                    let Some(pos3) = matches.get_one::<String>(Self::POSITIONAL_3_ID) else {
-                        return Ok(()); // [[MONTH] YEAR]
-                    };
-
                     let Ok(year) = pos3.parse() else {
                         return Err(Self::error(ErrorKind::InvalidValue, "year is invalid"));
                     };
 
-                    let day = pos1.clamp(1, MonthDay::MAX_DAY as i16) as u8; // not to wrap
-                    self.now.set_saturating_year(year);
+                    let day = pos1.clamp(1, MonthDay::MAX_DAY as IYear) as u8; // not to wrap
+                    self.set_year(year);
                     self.now.set_saturating_day(day);
+                    self.explicit_day = true;
                     Ok(()) // [[[DAY] MONTH] YEAR]
                 })()?;
+                if year_range {
+                    self.layout.base_row.column.year_in_header = false;
+                    self.layout.year_header = true;
+                    self.full_year_mode = true;
+                }
             } else {
                 if matches.is_explicit(Self::POSITIONAL_2_ID)
                     || matches.is_explicit(Self::POSITIONAL_3_ID)
@@ -476,6 +1463,7 @@ impl FromArgMatches for Args {
                 let month = match &self.now {
                     Date::Jalali(_) => parse_jalali_month(pos1),
                     Date::Gregorian(_) => parse_month(pos1),
+                    Date::Hijri(_) => parse_hijri_month(pos1),
                 }
                 .map_err(|_| {
                     Self::error(
@@ -489,7 +1477,7 @@ impl FromArgMatches for Args {
 
         // is this java?
         let base_weekday = &mut self.layout.base_row.column.content.grid.base_weekday;
-        // WEEKDAY_SETTERS_ARGS (must come after Jalali since that default to Sat)
+        // WEEKDAY_SETTERS_ARGS (see `explicit_weekday` above)
         if matches.get_flag(Self::SUNDAY_LONG) {
             *base_weekday = Weekday::SUN;
         } else if matches.get_flag(Self::MONDAY_LONG) {
@@ -497,23 +1485,73 @@ impl FromArgMatches for Args {
         } else if let Some(weekday) = matches.get_one::<Weekday>(Self::WEEKDAY_LONG) {
             *base_weekday = weekday.clone();
         }
+        if let Some(&&numbering) = matches.get_one::<&WeekNumConfig>(Self::WEEK_NUMBERING_LONG) {
+            self.week_numbering = numbering;
+        }
         // after WEEKDAY_SETTERS_ARGS and after now since this has precedence over other NOW options
         if let Some(when_week) = matches.get_one::<Option<usize>>(Self::WEEK_LONG) {
             if let Some(week) = when_week {
-                self.now.set_saturating_weeknum(*week, base_weekday.clone());
-                self.layout.highlight = Some(Highlight::Week(*week + 1));
+                match self.week_numbering {
+                    WeekNumConfig::Based => {
+                        self.now.set_saturating_weeknum(*week, base_weekday.clone())
+                    }
+                    // the Thursday of the week, always in the ISO year unlike its Monday
+                    WeekNumConfig::Iso => {
+                        // the 4th of the first month is always in the week 1
+                        self.now.set_saturating_ordinal(4);
+                        let monday = Weekday::MON.till_next(&self.now.weekday()) as jelal::IDayDiff;
+                        let week = (*week).clamp(1, 53) as jelal::IDayDiff;
+                        self.now
+                            .set_saturating_days_offset((week - 1) * 7 + 3 - monday);
+                    }
+                }
+                self.set_highlights(vec![Highlight::Week {
+                    number: *week,
+                    anchor: self.now.clone(),
+                }]);
             }
-            // Without reform there is no way now to set ISO as the weeknumconfig
             self.layout
                 .base_row
                 .column
                 .content
                 .weeknums
                 .get_or_insert(WeekNumConfig::Based);
+        } else if matches.get_flag(Self::NO_WEEK_NUMBERS_LONG) {
+            self.layout.base_row.column.content.weeknums = None;
+        }
+        // the numbers of the configuration are counted the same
+        if let Some(weeknums) = &mut self.layout.base_row.column.content.weeknums {
+            *weeknums = self.week_numbering;
+        }
+
+        // after the calendar is known and replaces the week of `--week`
+        if let Some(values) = matches.get_many::<String>(Self::HIGHLIGHT_LONG) {
+            let mut highlights = Vec::new();
+            for v in values {
+                highlights.extend(self.parse_highlight(v)?);
+            }
+            self.set_highlights(highlights);
+        }
+        if matches.get_flag(Self::NO_HIGHLIGHT_LONG) {
+            self.set_highlights(Vec::new());
         }
 
-        if matches.get_flag(Self::YEAR_LONG) {
+        // in the calendar of now like `--highlight`
+        if let Some(path) = matches.get_one::<String>(Self::EVENTS_LONG) {
+            let text = std::fs::read_to_string(path).map_err(|e| {
+                Self::error(
+                    ErrorKind::Io,
+                    format!("cannot read the events file `{}` ({})", path, e),
+                )
+            })?;
+            (self.layout.events, self.malformed_events) = parse_events(&text, self.now.kind());
+        }
+        self.layout.list_events = matches.get_flag(Self::LIST_EVENTS_LONG);
+
+        // a range of years is already in full years
+        if matches.get_flag(Self::YEAR_LONG) && !self.full_year_mode {
             self.layout.base_row.column.year_in_header = false;
+            self.layout.year_header = true;
             self.months = 12;
             self.full_year_mode = true;
         }
@@ -523,3 +1561,1253 @@ impl FromArgMatches for Args {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        layout::{Cell, WEEK_COUNT, WEEK_DAYS},
+        string::{ansi_width, highlight, set_colors},
+    };
+
+    /// Parse the arguments on top of the given configuration.
+    fn call_with(config: &str, no_0_args: &[&str]) -> Args {
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(no_0_args);
+        let mut v = Args::default();
+        v.apply_config(&Config::parse(config).unwrap().0);
+        v.update_from_arg_matches(&matches).unwrap();
+        v
+    }
+
+    fn base_weekday(args: &Args) -> Weekday {
+        args.layout
+            .base_row
+            .column
+            .content
+            .grid
+            .base_weekday
+            .clone()
+    }
+
+    #[test]
+    fn test_config_defaults_applied() {
+        let v = call_with(
+            "calendar = \"jalali\"\ncolor = \"never\"\nweek_numbers = true",
+            &[],
+        );
+        assert!(matches!(v.now, Date::Jalali(_)));
+        assert_eq!(base_weekday(&v), Weekday::SAT);
+        assert_eq!(v.color, ColorMode::Never);
+        assert_eq!(
+            v.layout.base_row.column.content.weeknums,
+            Some(WeekNumConfig::Based)
+        );
+    }
+
+    #[test]
+    fn test_config_cli_precedence() {
+        let v = call_with(
+            "weekday = \"monday\"\ncolor = \"never\"",
+            &["-s", "--color", "always"],
+        );
+        assert_eq!(base_weekday(&v), Weekday::SUN);
+        assert_eq!(v.color, ColorMode::Always);
+
+        // config weekday overrides the Jalali default but not the explicit flags
+        let v = call_with("calendar = \"jalali\"\nweekday = \"monday\"", &[]);
+        assert_eq!(base_weekday(&v), Weekday::MON);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let v = call_with("", &["--no-jalali", "-w", "--transpose"]);
+        let column = &v.layout.base_row.column;
+        assert!(column.transposed() && !column.vertical);
+        assert!(!column.content.weeknums_before_grid);
+        assert!(!v.layout.common_weekdays_is_enabled());
+
+        let v = call_with("", &["--no-jalali", "-v"]);
+        assert!(v.layout.base_row.column.transposed());
+        assert!(v.layout.common_weekdays_is_enabled());
+
+        // the last one given wins
+        let transposed = |args: &[&str]| call_with("", args).layout.base_row.column.transposed();
+        assert!(!transposed(&["--transpose", "--no-transpose"]));
+        assert!(transposed(&["--no-transpose", "--transpose"]));
+        assert!(transposed(&["--transpose", "--transpose"]));
+    }
+
+    #[test]
+    fn test_weekday_precedence() {
+        // the calendar only defaults the weekday, in any order
+        for (weekday, expected) in [
+            (&["-m"][..], Weekday::MON),
+            (&["-s"][..], Weekday::SUN),
+            (&["--weekday", "friday"][..], Weekday::FRI),
+        ] {
+            for calendar in ["-J", "--no-jalali"] {
+                let before = [&[calendar][..], weekday].concat();
+                let after = [weekday, &[calendar][..]].concat();
+                for args in [before, after] {
+                    assert_eq!(base_weekday(&call_with("", &args)), expected, "{:?}", args);
+                }
+            }
+        }
+        assert_eq!(base_weekday(&call_with("", &["-J"])), Weekday::SAT);
+        assert_eq!(base_weekday(&call_with("", &["--no-jalali"])), Weekday::SUN);
+        assert_eq!(
+            base_weekday(&call_with("calendar = \"jalali\"", &["--no-jalali"])),
+            Weekday::SUN
+        );
+
+        // the one of the configuration too
+        for calendar in ["-J", "--no-jalali"] {
+            let v = call_with("weekday = \"monday\"", &[calendar]);
+            assert_eq!(base_weekday(&v), Weekday::MON, "{}", calendar);
+        }
+    }
+
+    #[test]
+    fn test_negation_flags() {
+        // (config on, config off, CLI on, CLI off, is on)
+        let table: [(&str, &str, &str, &str, fn(&Args) -> bool); 4] = [
+            (
+                "week_numbers = true",
+                "week_numbers = false",
+                "--week",
+                "--no-week-numbers",
+                |v| v.layout.base_row.column.content.weeknums.is_some(),
+            ),
+            (
+                "calendar = \"jalali\"",
+                "calendar = \"gregorian\"",
+                "--jalali",
+                "--no-jalali",
+                |v| matches!(v.now, Date::Jalali(_)),
+            ),
+            (
+                "vertical = true",
+                "vertical = false",
+                "--vertical",
+                "--no-vertical",
+                |v| v.layout.base_row.column.vertical,
+            ),
+            ("span = true", "span = false", "--span", "--no-span", |v| {
+                v.span
+            }),
+        ];
+
+        for (config_on, config_off, on, off, is_on) in table {
+            assert!(is_on(&call_with(config_on, &[])), "{}", config_on);
+            assert!(
+                !is_on(&call_with(config_on, &[off])),
+                "{} {}",
+                config_on,
+                off
+            );
+            assert!(!is_on(&call_with(config_off, &[])), "{}", config_off);
+            assert!(
+                is_on(&call_with(config_off, &[on])),
+                "{} {}",
+                config_off,
+                on
+            );
+            // the last one given wins
+            assert!(!is_on(&call_with("", &[on, off])), "{} {}", on, off);
+            assert!(is_on(&call_with("", &[off, on])), "{} {}", off, on);
+        }
+    }
+
+    #[test]
+    fn test_lint_args() {
+        let lints = |args: &[&str]| lint_args(&call_with("", args));
+        assert_eq!(lints(&[]), vec![]);
+        assert_eq!(lints(&["-3", "-c", "3"]), vec![]);
+
+        assert_eq!(lints(&["--span"]), vec![Lint::SpanSingleMonth]);
+        assert_eq!(lints(&["--span", "-n", "2"]), vec![]);
+        // a spanning configuration is not the user's fault for each call
+        assert_eq!(lint_args(&call_with("span = true", &[])), vec![]);
+
+        assert_eq!(
+            lints(&["-w", "10", "4", "11", "2025"]),
+            vec![Lint::WeekWithDay]
+        );
+        assert_eq!(lints(&["-w", "10", "2025"]), vec![]);
+
+        assert_eq!(lints(&["--reform", "iso"]), vec![]);
+
+        assert_eq!(
+            lints(&["-n", "2", "-c", "4"]),
+            vec![Lint::ColumnsOverMonths {
+                columns: 4,
+                months: 2
+            }]
+        );
+        assert_eq!(lints(&["-n", "2", "-c", "auto"]), vec![]);
+        // only the columns given on the command line
+        let mut v = call_with("", &["-n", "2"]);
+        (v.columns, v.auto_columns) = (4, false);
+        assert_eq!(lint_args(&v), vec![]);
+
+        let v = call_with("", &["--no-jalali", "99999"]);
+        assert_eq!(v.now.year(), 9999);
+        assert_eq!(
+            lint_args(&v),
+            vec![Lint::YearOutOfRange {
+                given: 99999,
+                used: 9999
+            }]
+        );
+        assert_eq!(lints(&["--no-jalali", "9999"]), vec![]);
+        assert!(call_with("", &["--strict"]).strict);
+    }
+
+    #[test]
+    fn test_clear_and_watch() {
+        let v = call_with("", &[]);
+        assert!(!v.clear);
+        assert_eq!(v.watch, None);
+        assert!(call_with("", &["--clear"]).clear);
+
+        let v = call_with("", &["--watch", "60"]);
+        assert!(v.clear);
+        assert_eq!(v.watch, Some(60));
+
+        let matches = Args::command()
+            .no_binary_name(true)
+            .try_get_matches_from(["--watch", "0"]);
+        assert!(matches.is_err());
+    }
+
+    #[test]
+    fn test_interactive_anchor() {
+        let mut v = call_with("", &["-i", "--no-jalali", "7", "11", "2025"]);
+        assert!(v.interactive && v.clear);
+        let highlights = v.layout.highlights.clone();
+
+        let anchor = Date::Gregorian(civil::date(2026, 2, 1));
+        v.set_anchor(anchor.clone());
+        assert_eq!(v.layout.base_row.column.content.grid.date, anchor);
+        assert_eq!(v.layout.highlights, highlights);
+
+        let matches = Args::command()
+            .no_binary_name(true)
+            .try_get_matches_from(["-i", "--watch", "1"]);
+        assert!(matches.is_err());
+    }
+
+    #[test]
+    fn test_columns_follow_width() {
+        let mut v = call_with("", &["-y"]);
+        let column = v.layout.base_row.column.width();
+        let delimiter = v.layout.base_row.delimiter.len();
+
+        for (width, columns) in [
+            (column * 3 + delimiter * 2, 3),
+            (column * 3 + delimiter * 2 - 1, 2),
+            (column, 1),
+            (0, 1), // never less than a column
+            (usize::MAX, 3),
+        ] {
+            v.width_chars = width;
+            assert_eq!(v.suggested_columns(), columns, "width {}", width);
+        }
+    }
+
+    #[test]
+    fn test_width() {
+        assert_eq!(terminal_width(Some("40")), 40);
+        assert_eq!(terminal_width(Some(" 132\n")), 132);
+        // not a width so probed like no `COLUMNS`
+        assert_eq!(terminal_width(Some("0")), terminal_width(None));
+        assert_eq!(terminal_width(Some("wide")), terminal_width(None));
+
+        let mut v = call_with("", &["--no-jalali", "--width", "40"]);
+        assert_eq!(v.width_chars, 40);
+        assert_eq!(v.suggested_columns(), 1);
+        v = call_with("", &["--no-jalali", "--width", "40", "--width", "1000"]);
+        assert_eq!(v.suggested_columns(), 3);
+        assert!(
+            Args::command()
+                .try_get_matches_from(["cal", "--width", "0"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_refresh() {
+        let today = Date::Gregorian(civil::date(2030, 1, 15));
+        let mut v = call_with("", &["-J", "-3"]);
+        v.refresh(today.clone(), 40);
+        assert_eq!(v.now, today.convert_to(CalendarKind::Jalali));
+        assert_eq!(v.layout.highlights, vec![Highlight::Day(v.now.clone())]);
+        let start = &v.layout.base_row.column.content.grid.date;
+        assert_eq!((start.year(), start.month()), (1408, 9));
+        assert_eq!(v.width_chars, 40);
+        assert_eq!(v.layout.next_row_after_column, 1);
+        v.refresh(today.clone(), 1000);
+        assert_eq!(v.layout.next_row_after_column, 3);
+
+        // the given date, width and highlight stay
+        let mut v = call_with(
+            "",
+            &[
+                "--no-jalali",
+                "--width",
+                "1000",
+                "--no-highlight",
+                "11",
+                "2025",
+            ],
+        );
+        v.refresh(today, 40);
+        assert_eq!((v.now.year(), v.now.month()), (2025, 11));
+        assert_eq!(v.width_chars, 1000);
+        assert!(v.layout.highlights.is_empty());
+    }
+
+    #[test]
+    fn test_year_header() {
+        let mut v = call_with("", &["--no-jalali", "-y", "2025"]);
+        assert!(v.layout.year_header);
+        let column = v.layout.base_row.column.width();
+        let delimiter = v.layout.base_row.delimiter.len();
+        v.width_chars = column * 3 + delimiter * 2;
+        v.sync_layout();
+
+        let lines = v.layout.clone().format();
+        assert_eq!(lines[0].trim(), "2025");
+        assert_eq!(ansi_width(&lines[0]), v.width_chars);
+
+        assert!(call_with("", &["2025"]).layout.year_header);
+        assert!(!call_with("", &["11", "2025"]).layout.year_header);
+        assert!(!call_with("", &[]).layout.year_header);
+    }
+
+    #[test]
+    fn test_span_bias() {
+        let names = |args: &[&str]| {
+            let lines = call_with("", args).layout.format();
+            lines
+                .join(" ")
+                .split_whitespace()
+                .filter(|i| ["April", "May", "June", "July", "August"].contains(i))
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        let args = [
+            "--no-jalali",
+            "-c",
+            "4",
+            "-n",
+            "4",
+            "--span",
+            "1",
+            "6",
+            "2025",
+        ];
+        let before = ["April", "May", "June", "July"];
+        assert_eq!(names(&args), before);
+        assert_eq!(
+            names(&[&args[..], &["--span-bias", "before"]].concat()),
+            before
+        );
+        assert_eq!(
+            names(&[&args[..], &["--span-bias", "after"]].concat()),
+            ["May", "June", "July", "August"]
+        );
+        // only the even spans have an extra month
+        let args = [
+            "--no-jalali",
+            "-n",
+            "3",
+            "--span",
+            "--span-bias",
+            "after",
+            "1",
+            "6",
+            "2025",
+        ];
+        assert_eq!(names(&args), ["May", "June", "July"]);
+    }
+
+    #[test]
+    fn test_negative_months() {
+        let first = |config: &str, args: &[&str]| {
+            let v = call_with(config, args);
+            let date = v.layout.base_row.column.content.grid.date.clone();
+            (v.months, date.year(), date.month())
+        };
+        assert_eq!(
+            first("", &["-J", "-n", "-3", "1", "8", "1404"]),
+            (3, 1404, 6)
+        );
+        assert_eq!(
+            first("", &["-J", "-n", "3", "1", "8", "1404"]),
+            (3, 1404, 8)
+        );
+        assert_eq!(
+            first("", &["--no-jalali", "-n", "-3", "1", "2", "2025"]),
+            (3, 2024, 12)
+        );
+        assert_eq!(
+            first("", &["--no-jalali", "--months=-1", "1", "2", "2025"]),
+            (1, 2025, 2)
+        );
+        // the span of the configuration gives way
+        assert_eq!(
+            first(
+                "span = true",
+                &["--no-jalali", "-n", "-3", "1", "2", "2025"]
+            ),
+            (3, 2024, 12)
+        );
+
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["-n", "-3", "--span"]);
+        assert!(Args::default().update_from_arg_matches(&matches).is_err());
+        assert!(
+            Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(["-n", "-1201"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_year_range() {
+        let v = call_with("", &["-J", "1403", "1405"]);
+        assert_eq!(v.months, 36);
+        assert!(v.full_year_mode);
+        let date = &v.layout.base_row.column.content.grid.date;
+        assert_eq!(
+            (date.kind(), date.year(), date.month()),
+            (CalendarKind::Jalali, 1403, 1)
+        );
+
+        // each year is centered above its own rows
+        let lines = v.layout.format();
+        let years: Vec<_> = lines
+            .iter()
+            .map(|i| i.trim())
+            .filter(|i| ["1403", "1404", "1405"].contains(i))
+            .collect();
+        assert_eq!(years, ["1403", "1404", "1405"]);
+        assert_eq!(lines[0].trim(), "1403");
+        // `-y` keeps the range
+        assert_eq!(call_with("", &["-J", "-y", "1403", "1405"]).months, 36);
+        assert_eq!(call_with("", &["-J", "1404", "1404"]).months, 12);
+
+        for range in [["1403", "1500"], ["1405", "1403"]] {
+            let matches = Args::command().no_binary_name(true).get_matches_from(range);
+            assert!(Args::default().update_from_arg_matches(&matches).is_err());
+        }
+
+        // a month before or after the year is not a range
+        for args in [["11", "2025"], ["2025", "11"], ["2025", "nov"]] {
+            let v = call_with("", &[&["--no-jalali"], &args[..]].concat());
+            assert_eq!((v.now.year(), v.now.month()), (2025, 11));
+            assert_eq!(v.months, 1);
+            assert!(!v.full_year_mode);
+        }
+    }
+
+    #[test]
+    fn test_show_adjacent() {
+        assert!(
+            !call_with("", &[])
+                .layout
+                .base_row
+                .column
+                .content
+                .grid
+                .adjacent_days
+        );
+        let v = call_with("", &["--show-adjacent", "-j"]);
+        assert!(v.layout.base_row.column.content.grid.adjacent_days);
+        assert!(v.layout.base_row.column.content.grid.ordinal_mode);
+    }
+
+    #[test]
+    fn test_trim_weeks() {
+        assert!(
+            !call_with("", &[])
+                .layout
+                .base_row
+                .column
+                .content
+                .trim_empty_weeks
+        );
+        let v = call_with("", &["--trim-weeks", "--no-jalali", "1", "2", "2026"]);
+        assert!(v.layout.base_row.column.content.trim_empty_weeks);
+        // 1 header, 1 weekdays and 4 weeks
+        assert_eq!(v.layout.format().len(), 6);
+    }
+
+    #[test]
+    fn test_moon() {
+        assert!(!call_with("", &[]).layout.base_row.column.content.grid.moon);
+        let v = call_with("", &["--moon", "-j"]);
+        assert!(v.layout.base_row.column.content.grid.moon);
+        assert_eq!(v.layout.base_row.column.content.grid.day_cell_width(), 4);
+    }
+
+    #[test]
+    fn test_highlight() {
+        let v = call_with("", &["-J", "--highlight", "1404/08/12", "1", "8", "1404"]);
+        let day = Date::Jalali(jelal::Date::from((1404, 8, 12)));
+        assert_eq!(v.layout.highlights, [Highlight::Day(day)]);
+        let v = call_with(
+            "",
+            &["--no-jalali", "--highlight", "2025-11-20", "1", "8", "1404"],
+        );
+        let day = Date::Gregorian(civil::date(2025, 11, 20));
+        assert_eq!(v.layout.highlights, [Highlight::Day(day)]);
+        // replaces the week too
+        let v = call_with(
+            "",
+            &["--no-jalali", "-w", "10", "--highlight", "2025-11-20"],
+        );
+        assert_eq!(v.layout.highlights, [Highlight::Day(day)]);
+
+        let v = call_with(
+            "",
+            &["--no-jalali", "--highlight", "TODAY", "1", "1", "2020"],
+        );
+        let today = Date::Gregorian(jiff::Zoned::now().date());
+        assert_eq!(v.layout.highlights, [Highlight::Day(today)]);
+        let v = call_with("", &["--highlight", "none"]);
+        assert!(v.layout.highlights.is_empty());
+        // repeated for more days, `none` adds nothing
+        let v = call_with(
+            "",
+            &[
+                "--no-jalali",
+                "--highlight",
+                "2025/11/03",
+                "--highlight",
+                "none",
+                "--highlight",
+                "2025/11/27",
+            ],
+        );
+        let days = [3, 27].map(|i| Highlight::Day(Date::Gregorian(civil::date(2025, 11, i))));
+        assert_eq!(v.layout.highlights, days);
+
+        // a day that is not printed changes nothing
+        set_colors(Some(true));
+        let format = |highlight: &str| {
+            call_with("", &["-J", "--highlight", highlight, "1", "8", "1404"])
+                .layout
+                .format()
+        };
+        assert_eq!(format("1400/01/01"), format("none"));
+        assert_ne!(format("1404/08/12"), format("none"));
+
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["--highlight", "tomorrow"]);
+        assert!(Args::default().update_from_arg_matches(&matches).is_err());
+    }
+
+    #[test]
+    fn test_highlight_source() {
+        // the default follows now
+        let mut v = call_with("", &["--no-jalali"]);
+        assert_eq!(v.highlight_source, HighlightSource::Now);
+        let today = Date::Gregorian(jiff::Zoned::now().date());
+        assert_eq!(v.layout.highlights, [Highlight::Day(today)]);
+        v.now = Date::Gregorian(civil::date(2025, 11, 20));
+        v.sync_layout();
+        assert_eq!(v.layout.highlights, [Highlight::Day(v.now.clone())]);
+
+        // an explicit one survives the sync
+        for (args, expected) in [
+            (
+                &["--no-jalali", "--highlight", "2025/11/20"][..],
+                vec![Highlight::Day(Date::Gregorian(civil::date(2025, 11, 20)))],
+            ),
+            (&["--highlight", "none"][..], vec![]),
+        ] {
+            let mut v = call_with("", args);
+            assert_eq!(v.highlight_source, HighlightSource::Explicit);
+            v.now = Date::Gregorian(civil::date(2020, 1, 1));
+            v.sync_layout();
+            assert_eq!(v.layout.highlights, expected, "{:?}", args);
+        }
+
+        // the timestamp sets its day itself
+        let v = call_with("", &["--no-jalali", "@1762545600"]);
+        assert_eq!(v.highlight_source, HighlightSource::Explicit);
+        let day = Args::timestamp_date(1762545600, TimeZone::system()).unwrap();
+        assert_eq!(v.layout.highlights, [Highlight::Day(Date::Gregorian(day))]);
+
+        // nothing at all, over the other sources
+        for args in [
+            &["--no-highlight"][..],
+            &["--no-jalali", "--no-highlight", "@1762545600"],
+            &["--no-jalali", "--no-highlight", "--week", "45", "2025"],
+        ] {
+            let mut v = call_with("", args);
+            v.sync_layout();
+            assert!(v.layout.highlights.is_empty(), "{:?}", args);
+        }
+        assert!(
+            Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(["--no-highlight", "--highlight", "today"])
+                .is_err()
+        );
+
+        // not a terminal
+        for (args, is_terminal, highlighted) in [
+            (&[][..], false, false),
+            (&[][..], true, true),
+            (&["--color", "always"][..], false, true),
+            (&["--output", "json"][..], false, true),
+            (&["--highlight", "today"][..], false, true),
+        ] {
+            let mut v = call_with("", args);
+            v.set_output_terminal(is_terminal);
+            v.sync_layout();
+            assert_eq!(!v.layout.highlights.is_empty(), highlighted, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_weekend() {
+        set_colors(Some(true));
+        // the columns with an escape sequence, every day in them has one
+        let colored = |date: &[&str], args: &[&str]| {
+            // the date first, a bare `--weekend` would take the day as its value
+            let args = [date, args, &["--highlight", "none"][..]].concat();
+            let v = call_with("", &args);
+            let cells = v.layout.base_row.column.content.grid.format(&[]);
+            let columns: Vec<usize> = (0..WEEK_DAYS)
+                .filter(|&j| cells.iter().any(|row| row[j].contains('\x1b')))
+                .collect();
+            for row in &cells {
+                for &j in &columns {
+                    assert!(row[j].trim().is_empty() || row[j].contains('\x1b'));
+                }
+            }
+            columns
+        };
+
+        let november = ["1", "11", "2025"];
+        let aban = ["1", "8", "1404"];
+        assert!(colored(&november, &["--no-jalali"]).is_empty());
+        assert_eq!(colored(&november, &["--no-jalali", "--weekend"]), [0, 6]);
+        assert_eq!(colored(&aban, &["-J", "--weekend"]), [6]);
+        assert_eq!(colored(&aban, &["-J", "--weekend", "thu,fri"]), [5, 6]);
+        let never = ["--no-jalali", "--weekend", "--color", "never"];
+        assert!(colored(&november, &never).is_empty());
+
+        let v = call_with("", &["--no-jalali", "--weekend", "--style", "weekend=bold"]);
+        let style = v.layout.base_row.column.content.grid.style;
+        assert_eq!(style.weekend, Style::parse("bold").unwrap());
+        let v = call_with("", &["--no-jalali", "--style-preset", "mono", "--weekend"]);
+        let style = v.layout.base_row.column.content.grid.style;
+        assert_eq!(style.weekend, StyleConfig::MONO.weekend);
+
+        // composes with the highlight
+        let v = call_with("", &["2", "11", "2025", "--no-jalali", "--weekend"]);
+        let cells = v.layout.base_row.column.content.grid.format(&[&v.now]);
+        assert_eq!(
+            cells[1][0],
+            Style::WEEKEND.merge(StyleConfig::DEFAULT.today).paint(" 2")
+        );
+
+        assert!(parse_weekend("fri,noday").is_err());
+        assert_eq!(parse_weekend(""), Ok(None));
+    }
+
+    #[test]
+    fn test_events() {
+        let path = std::env::temp_dir().join(format!("jcal-test-events-{}", std::process::id()));
+        std::fs::write(
+            &path,
+            "# Aban 1404\n\
+             1404/08/20\tDentist\n\
+             1404/08/12  Payday\n\
+             1404/09/01\tout of the month\n\
+             tomorrow\tParty\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        set_colors(Some(true));
+        let args = [
+            "1",
+            "8",
+            "1404",
+            "-J",
+            "--highlight",
+            "none",
+            "--events",
+            path,
+            "--list-events",
+        ];
+        let v = call_with("", &args);
+        assert_eq!(v.layout.events.len(), 3);
+
+        // the malformed line is only a warning
+        let lints = lint_args(&v);
+        assert_eq!(lints.len(), 1);
+        assert!(matches!(lints[0], Lint::MalformedEvent { line: 5, .. }));
+        assert!(lints[0].to_string().contains("`tomorrow`"));
+
+        // the two days of Aban are highlighted while Azar 1 is not printed
+        let lines = v.layout.clone().format();
+        let highlighted: Vec<_> = lines
+            .iter()
+            .flat_map(|i| i.match_indices("\x1b[7m"))
+            .collect();
+        assert_eq!(highlighted.len(), 2);
+        assert!(lines.iter().any(|i| i.contains(&highlight("12"))));
+        assert!(lines.iter().any(|i| i.contains(&highlight("20"))));
+        // and listed in order under the calendar
+        assert_eq!(
+            lines[lines.len() - 3..],
+            ["", "1404/08/12  Payday", "1404/08/20  Dentist"]
+        );
+
+        let v = call_with("", &args[..args.len() - 1]);
+        assert!(!v.layout.format().iter().any(|i| i.contains("Payday")));
+
+        std::fs::remove_file(path).unwrap();
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["--events", path]);
+        assert!(Args::default().update_from_arg_matches(&matches).is_err());
+    }
+
+    #[test]
+    fn test_dual() {
+        let secondary = |v: Args| v.layout.base_row.column.content.grid.secondary;
+        assert_eq!(secondary(call_with("", &[])), None);
+        assert_eq!(
+            secondary(call_with("", &["--dual", "--jalali"])),
+            Some(CalendarKind::Gregorian)
+        );
+        assert_eq!(
+            secondary(call_with(
+                "calendar = \"jalali\"",
+                &["--dual", "--no-jalali"]
+            )),
+            Some(CalendarKind::Jalali)
+        );
+    }
+
+    #[test]
+    fn test_no_span_and_three_last_wins() {
+        let v = call_with("", &["-3", "--no-span"]);
+        assert_eq!(v.months, 3);
+        assert!(!v.span);
+
+        // the last one wins either way
+        let v = call_with("", &["--no-span", "-3"]);
+        assert_eq!(v.months, 3);
+        assert!(v.span);
+    }
+
+    #[test]
+    fn test_after_and_before() {
+        let start = |v: &Args| v.layout.base_row.column.content.grid.date.clone();
+        let gregorian = |m, d| Date::Gregorian(civil::date(2025, m, d));
+
+        let v = call_with(
+            "",
+            &["--no-jalali", "-B", "2", "-A", "1", "7", "11", "2025"],
+        );
+        assert_eq!(v.months, 4);
+        assert_eq!(start(&v), gregorian(9, 1));
+        assert_eq!(v.layout.base_row.more_columns, 3);
+        assert_eq!(v.layout.highlights, [Highlight::Day(gregorian(11, 7))]);
+
+        let v = call_with("", &["--no-jalali", "-A", "2", "7", "11", "2025"]);
+        assert_eq!((v.months, start(&v)), (3, gregorian(11, 1)));
+        let v = call_with("", &["--no-jalali", "-B", "1", "7", "11", "2025"]);
+        assert_eq!((v.months, start(&v)), (2, gregorian(10, 1)));
+
+        // the span of the configuration does not move the start
+        let v = call_with(
+            "span = true",
+            &["--no-jalali", "-A", "1", "7", "11", "2025"],
+        );
+        assert!(!v.span);
+        assert_eq!((v.months, start(&v)), (2, gregorian(11, 1)));
+
+        for conflict in [
+            &["-A", "1", "-3"][..],
+            &["-B", "1", "-n", "4"],
+            &["-A", "1", "-y"],
+        ] {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(conflict);
+            assert!(matches.is_err(), "{:?}", conflict);
+        }
+    }
+
+    #[test]
+    fn test_months_bound() {
+        for args in [
+            &["-n", "1201"][..],
+            &["-n", "999999999"],
+            &["-n", "99999999999999999999999"],
+            &["-A", "1201"],
+        ] {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(args);
+            assert!(matches.is_err(), "{:?}", args);
+        }
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["-A", "600", "-B", "600"]);
+        assert!(Args::default().update_from_arg_matches(&matches).is_err());
+
+        // the most months are printed whole, even spanning back from the first year
+        for args in [
+            &["--no-jalali", "-n", "1200", "1", "2025"][..],
+            &["--no-jalali", "-n", "1200", "-S", "1", "1", "1"],
+            &["--jalali", "-A", "600", "-B", "599", "1", "1", "1404"],
+        ] {
+            let v = call_with("", args);
+            assert_eq!(v.months, 1200);
+            let rows = v.months.div_ceil(v.layout.next_row_after_column);
+            let lines = v.layout.format();
+            assert_eq!(lines.len(), rows * (1 + 1 + WEEK_COUNT), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_week_highlight_only_in_anchor_week() {
+        set_colors(Some(true));
+        // the week 10 of 2025 is March 9 to 15 (Sunday based)
+        let mut v = call_with("", &["--no-jalali", "-s", "-w", "10", "-y", "2025"]);
+        let highlighted = highlight(" 1"); // any value to find the escape sequence
+        let escape = &highlighted[..highlighted.find(' ').unwrap()];
+
+        let content = &mut v.layout.base_row.column.content;
+        let mut count = 0;
+        let mut days = Vec::new();
+        for _ in 0..v.months {
+            count += content
+                .format(&v.layout.highlights)
+                .iter()
+                .flatten()
+                .filter(|i| i.contains(escape))
+                .count();
+            for cell in content.cells(&v.layout.highlights).into_iter().flatten() {
+                if let Cell::Day {
+                    value,
+                    highlighted: true,
+                    ..
+                } = cell
+                {
+                    days.push((content.grid.date.month(), value));
+                }
+            }
+            content.grid.date.set_saturating_months_offset(1);
+        }
+        // the seven days of the week and its number, nothing else of the year
+        assert_eq!(count, WEEK_DAYS + 1);
+        assert_eq!(days, (9..=15).map(|i| (3, i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_week_numbering() {
+        let weeknums = |config: &str, args: &[&str]| {
+            let v = call_with(config, args);
+            v.layout.base_row.column.content.month_info().weeknums
+        };
+        // the first row of 2027 is the last ISO week of 2026
+        let iso = ["--no-jalali", "--week-numbering", "iso", "1", "2027"];
+        assert_eq!(
+            weeknums("", &[&iso[..], &["-w"]].concat()),
+            Some([53, 1, 2, 3, 4, 5])
+        );
+        assert_eq!(
+            weeknums("week_numbers = true", &iso),
+            Some([53, 1, 2, 3, 4, 5])
+        );
+        assert_eq!(weeknums("", &iso), None);
+        assert_eq!(
+            weeknums("", &["--no-jalali", "-s", "1", "2027", "-w"]),
+            Some([52, 1, 2, 3, 4, 5])
+        );
+        let lines = call_with("", &[&iso[..], &["-w"]].concat()).layout.format();
+        assert!(lines.iter().any(|i| i.contains("53")));
+
+        // the week is picked by its Thursday
+        let day_of = |args: &[&str]| {
+            let v = call_with("", args);
+            (v.now.year(), v.now.month(), v.now.day())
+        };
+        let iso = ["--no-jalali", "--week-numbering", "iso"];
+        assert_eq!(
+            day_of(&[&iso[..], &["-w", "53", "2026"]].concat()),
+            (2026, 12, 31)
+        );
+        assert_eq!(
+            day_of(&[&iso[..], &["-w", "1", "2027"]].concat()),
+            (2027, 1, 7)
+        );
+    }
+
+    #[test]
+    fn test_week_selects_its_month() {
+        let month_of = |args: &[&str]| {
+            let v = call_with("", args);
+            (v.now.year(), v.now.month(), v.now.day())
+        };
+        // the numbers are the printed ones, the first week is 1 not 0
+        assert_eq!(
+            month_of(&["--no-jalali", "-s", "-w", "1", "2025"]),
+            (2025, 1, 5)
+        );
+        for week in ["0", "54"] {
+            let parsed = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(["-w", week]);
+            assert!(parsed.is_err(), "{}", week);
+        }
+        // weeks starting on the first day of a month or right before it
+        assert_eq!(
+            month_of(&["--no-jalali", "-s", "-w", "22", "2025"]),
+            (2025, 6, 1)
+        );
+        assert_eq!(
+            month_of(&["--no-jalali", "-s", "-w", "21", "2025"]),
+            (2025, 5, 25)
+        );
+        assert_eq!(
+            month_of(&["--no-jalali", "-m", "-w", "1", "2025"]),
+            (2025, 1, 6)
+        );
+        assert_eq!(month_of(&["-j", "-w", "23", "1404"]), (1404, 6, 1));
+        assert_eq!(month_of(&["-j", "-w", "22", "1404"]), (1404, 5, 25));
+
+        // the highlighted week is always in the selected month
+        set_colors(Some(true));
+        let highlighted = highlight(" 1");
+        let escape = &highlighted[..highlighted.find(' ').unwrap()];
+        for week in 1..=52 {
+            let week = week.to_string();
+            let mut v = call_with("", &["--no-jalali", "-s", "-w", &week, "2025"]);
+            let content = &mut v.layout.base_row.column.content;
+            content.grid.date = v.now.clone();
+            let count = content
+                .format(&v.layout.highlights)
+                .iter()
+                .flatten()
+                .filter(|i| i.contains(escape))
+                .count();
+            assert_eq!(count, 1, "week {}", week);
+        }
+    }
+
+    #[test]
+    fn test_timestamp_date() {
+        let tehran = TimeZone::fixed(jiff::tz::Offset::from_seconds(12600).unwrap());
+        let new_york = TimeZone::fixed(jiff::tz::offset(-5));
+        // the same as `TZ=... date -d @N +%F`
+        for (seconds, tz, expected) in [
+            (0, TimeZone::UTC, civil::date(1970, 1, 1)),
+            (0, tehran.clone(), civil::date(1970, 1, 1)),
+            (0, new_york.clone(), civil::date(1969, 12, 31)),
+            // 23:30 in Tehran
+            (1762545600, tehran.clone(), civil::date(2025, 11, 7)),
+            (1762545600 + 1800, tehran.clone(), civil::date(2025, 11, 8)),
+            (1762545600, TimeZone::UTC, civil::date(2025, 11, 7)),
+            (-1, TimeZone::UTC, civil::date(1969, 12, 31)),
+            (-1, tehran.clone(), civil::date(1970, 1, 1)),
+            (-86400 * 365, new_york, civil::date(1968, 12, 31)),
+        ] {
+            assert_eq!(
+                Args::timestamp_date(seconds, tz.clone()).unwrap(),
+                expected,
+                "@{} in {:?}",
+                seconds,
+                tz
+            );
+        }
+        assert!(Args::timestamp_date(i64::MAX, TimeZone::UTC).is_err());
+
+        // the highlighted day is the day of the timestamp in the system zone
+        for seconds in [0, 1762545600, -1] {
+            let v = call_with("", &["--no-jalali", &format!("@{}", seconds)]);
+            let expected = Args::timestamp_date(seconds, TimeZone::system()).unwrap();
+            assert_eq!(v.now, Date::Gregorian(expected));
+            assert_eq!(v.layout.highlights, [Highlight::Day(v.now.clone())]);
+        }
+    }
+
+    #[test]
+    fn test_year_month_positional() {
+        for (args, expected) in [
+            (
+                &["--no-jalali", "2025-11"][..],
+                Date::Gregorian(civil::date(2025, 11, 1)),
+            ),
+            (
+                &["--no-jalali", "2025/2"],
+                Date::Gregorian(civil::date(2025, 2, 1)),
+            ),
+            (
+                &["--jalali", "1404/08"],
+                Date::Jalali(jelal::Date::from((1404, 8, 1))),
+            ),
+            (
+                &["--jalali", "1404-8"],
+                Date::Jalali(jelal::Date::from((1404, 8, 1))),
+            ),
+            (
+                &["--no-jalali", "999-01"],
+                Date::Gregorian(civil::date(999, 1, 1)),
+            ),
+        ] {
+            let v = call_with("", args);
+            assert_eq!(
+                v.layout.base_row.column.content.grid.date, expected,
+                "{:?}",
+                args
+            );
+            assert!(v.layout.base_row.column.year_in_header);
+        }
+
+        for invalid in [
+            &["--no-jalali", "2025-13"][..],
+            &["--jalali", "1404/00"],
+            &["--no-jalali", "2025-11", "2025"],
+        ] {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .get_matches_from(invalid);
+            assert!(
+                Args::default().update_from_arg_matches(&matches).is_err(),
+                "{:?}",
+                invalid
+            );
+        }
+        assert_eq!(Args::split_year_month("20251-1"), None);
+        assert_eq!(Args::split_year_month("2025-111"), None);
+        assert_eq!(Args::split_year_month("2025.11"), None);
+    }
+
+    #[test]
+    fn test_timestamp_seconds() {
+        for (given, expected) in [
+            ("0", 0),
+            ("-1", -1),
+            ("-86400", -86400),
+            ("1747785600.5", 1747785600),
+            ("1747785600.999", 1747785600),
+            ("-1.5", -2),
+            ("-0.5", -1),
+            ("-1.000", -1),
+        ] {
+            assert_eq!(Args::timestamp_seconds(given), Ok(expected), "{}", given);
+        }
+        for invalid in [
+            "", "-", "1.", ".5", "1.-5", "+1", "--1", "1e3", "1.5.5", "abc",
+        ] {
+            assert!(Args::timestamp_seconds(invalid).is_err(), "{}", invalid);
+        }
+
+        // `@-1` is the last second of 1969-12-31 in UTC, the day before the epoch in the west too
+        let seconds = Args::timestamp_seconds("-1").unwrap();
+        for tz in [TimeZone::UTC, TimeZone::fixed(jiff::tz::offset(-5))] {
+            assert_eq!(
+                Args::timestamp_date(seconds, tz).unwrap(),
+                civil::date(1969, 12, 31)
+            );
+        }
+        let v = call_with("", &["--no-jalali", "@1747785600.5"]);
+        let expected = Args::timestamp_date(1747785600, TimeZone::system()).unwrap();
+        assert_eq!(v.now, Date::Gregorian(expected));
+
+        // out of the range is an error rather than a panic
+        for given in [
+            "@99999999999999999999",
+            "@-99999999999999999999.5",
+            "@253402300800",
+        ] {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .get_matches_from(["--no-jalali", given]);
+            let e = Args::default()
+                .update_from_arg_matches(&matches)
+                .unwrap_err();
+            assert!(
+                e.to_string().contains("out of the range"),
+                "{}: {}",
+                given,
+                e
+            );
+        }
+    }
+
+    #[test]
+    fn test_weekday_of() {
+        assert_eq!(parse_month_day("12-30"), Ok((12, 30)));
+        assert_eq!(parse_month_day("02-29"), Ok((2, 29)));
+        for invalid in ["12", "13-01", "01-32", "00-01", "1-x", "12-30-1"] {
+            assert!(parse_month_day(invalid).is_err(), "{}", invalid);
+        }
+
+        let v = call_with("", &["--weekday-of", "12-30", "1403"]);
+        assert_eq!(v.weekday_of, Some((12, 30)));
+        assert_eq!(v.years, 5);
+        let v = call_with("", &["--weekday-of", "02-29", "--years", "8"]);
+        assert_eq!(v.years, 8);
+
+        // `--years` means nothing alone
+        assert!(
+            Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(["--years", "3"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_style_presets() {
+        set_colors(Some(true));
+        // Nov 2025 starts on a Saturday, the 4th is a Tuesday
+        let cells = |args: &[&str]| {
+            let v = call_with("", &[args, &["4", "11", "2025"][..]].concat());
+            let grid = &v.layout.base_row.column.content.grid;
+            let days: Vec<_> = v
+                .layout
+                .highlights
+                .iter()
+                .filter_map(Highlight::day)
+                .collect();
+            let cells = grid.format(&days);
+            // (today, Saturday, Sunday, Monday)
+            [
+                cells[1][2].clone(),
+                cells[0][6].clone(),
+                cells[1][0].clone(),
+                cells[1][1].clone(),
+            ]
+        };
+
+        let table = [
+            ("default", ["\x1b[7m 4\x1b[0m", " 1", " 2"]),
+            (
+                "high-contrast",
+                ["\x1b[1;4m 4\x1b[0m", "\x1b[1m 1\x1b[0m", "\x1b[1m 2\x1b[0m"],
+            ),
+            (
+                "mono",
+                ["\x1b[1m 4\x1b[0m", "\x1b[2m 1\x1b[0m", "\x1b[2m 2\x1b[0m"],
+            ),
+            (
+                "solarized",
+                [
+                    "\x1b[7;38;5;33m 4\x1b[0m",
+                    "\x1b[38;5;160m 1\x1b[0m",
+                    "\x1b[38;5;160m 2\x1b[0m",
+                ],
+            ),
+        ];
+        for (preset, [today, saturday, sunday]) in table {
+            assert_eq!(
+                cells(&["--style-preset", preset]),
+                [today, saturday, sunday, " 3"],
+                "{}",
+                preset
+            );
+        }
+
+        // overrides go on top of the preset
+        assert_eq!(
+            cells(&["--style-preset", "mono", "--style", "today=underline"]),
+            [
+                "\x1b[4m 4\x1b[0m",
+                "\x1b[2m 1\x1b[0m",
+                "\x1b[2m 2\x1b[0m",
+                " 3"
+            ]
+        );
+
+        // never beats any preset or override
+        assert_eq!(
+            cells(&[
+                "--color",
+                "never",
+                "--style-preset",
+                "solarized",
+                "--style",
+                "weekend=bold"
+            ]),
+            [" 4", " 1", " 2", " 3"]
+        );
+    }
+
+    #[test]
+    fn test_header_format() {
+        let header = |format: &str| {
+            let v = call_with("", &["--header-format", format, "1", "11", "2025"]);
+            v.layout.format()[0].trim().to_owned()
+        };
+        assert_eq!(header("%b '%y"), "Nov '25");
+        assert_eq!(header("%m/%Y"), "11/2025");
+
+        let e = Args::command()
+            .no_binary_name(true)
+            .try_get_matches_from(["--header-format", "%B %H:%M"])
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("%H"), "{}", e);
+    }
+
+    #[test]
+    fn test_year_header_format() {
+        let v = call_with("", &["-J", "--year-header-format", "%Y / %EG", "1404"]);
+        assert_eq!(v.layout.format()[0].trim(), "1404 / 2025–2026");
+        let v = call_with("", &["--no-jalali", "--year-header-format", "%EN", "2025"]);
+        assert_eq!(v.layout.format()[0].trim(), "1403");
+
+        let e = Args::command()
+            .no_binary_name(true)
+            .try_get_matches_from(["--year-header-format", "%T"])
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("%T"), "{}", e);
+    }
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(call_with("", &[]).output, OutputFormat::Text);
+        let v = call_with("", &["--output", "JSON", "-J", "1", "8", "1404"]);
+        assert_eq!(v.output, OutputFormat::Json);
+        assert!(v.layout.to_json().contains("\"month_name\":\"Aban\""));
+        let v = call_with("", &["--output", "html"]);
+        assert_eq!(v.output, OutputFormat::Html);
+        assert_eq!(
+            call_with("", &["--output", "csv"]).output,
+            OutputFormat::Csv
+        );
+        assert_eq!(
+            call_with("", &["--output", "tsv"]).output,
+            OutputFormat::Tsv
+        );
+
+        let result = Args::command().no_binary_name(true).try_get_matches_from([
+            "--output",
+            "json",
+            "--interactive",
+        ]);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Days read from an events file (`--events`), like the calendar files of BSD `calendar`.
+//!
+//! Each line is a `YYYY/MM/DD` date in the printed calendar followed by a tab or spaces and a
+//! description. Blank lines and lines starting with `#` are skipped.
+
+use jcal::{
+    date::{CalendarKind, CommonDate, Date},
+    parser::{parse_ymd_gregorian, parse_ymd_jalali},
+};
+
+/// A day of the events file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub date: Date,
+    pub description: String,
+}
+
+impl Event {
+    /// The line printed under the calendar (`--list-events`), the date in the given calendar.
+    pub fn line(&self, calendar: CalendarKind) -> String {
+        let date = self.date.convert_to(calendar);
+        let date = format!("{:04}/{:02}/{:02}", date.year(), date.month(), date.day());
+        if self.description.is_empty() {
+            date
+        } else {
+            format!("{}  {}", date, self.description)
+        }
+    }
+}
+
+/// Parse a `YYYY/MM/DD` date of the given calendar (also `YYYY-MM-DD` in Gregorian).
+pub fn parse_date(calendar: CalendarKind, s: &str) -> Result<Date, jiff::Error> {
+    match calendar {
+        CalendarKind::Jalali => parse_ymd_jalali(s).map(Date::Jalali),
+        CalendarKind::Gregorian => parse_ymd_gregorian(s).map(Date::Gregorian),
+        // not printed by cal, read like the `@TIMESTAMP` days
+        CalendarKind::Hijri => parse_ymd_gregorian(s).map(|i| Date::Gregorian(i).to_hijri()),
+    }
+}
+
+/// Read the events of a file, the malformed lines are returned with their (1-based) numbers
+/// instead of failing the rest.
+pub fn parse_events(text: &str, calendar: CalendarKind) -> (Vec<Event>, Vec<(usize, String)>) {
+    let mut events = Vec::new();
+    let mut malformed = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (date, description) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match parse_date(calendar, date) {
+            Ok(date) => events.push(Event {
+                date,
+                description: description.trim().to_owned(),
+            }),
+            Err(e) => malformed.push((
+                i + 1,
+                format!("`{}` is not a YYYY/MM/DD date ({})", date, e),
+            )),
+        }
+    }
+    (events, malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events() {
+        let text = "# holidays\n\
+                    1404/08/12\tPayday\n\
+                    \n\
+                    1404/08/20  Dentist at 10\n\
+                    1404/09/01\n\
+                    someday\tParty\n";
+        let (events, malformed) = parse_events(text, CalendarKind::Jalali);
+        let jalali = |m, d| Date::Jalali(jelal::Date::from((1404, m, d)));
+        assert_eq!(
+            events,
+            [
+                Event {
+                    date: jalali(8, 12),
+                    description: "Payday".to_owned(),
+                },
+                Event {
+                    date: jalali(8, 20),
+                    description: "Dentist at 10".to_owned(),
+                },
+                Event {
+                    date: jalali(9, 1),
+                    description: String::new(),
+                },
+            ]
+        );
+        assert_eq!(malformed.len(), 1);
+        assert_eq!(malformed[0].0, 6);
+        assert!(malformed[0].1.contains("`someday`"));
+
+        let (events, _) = parse_events("2025-11-20 Birthday", CalendarKind::Gregorian);
+        assert_eq!(
+            events[0].date,
+            Date::Gregorian(jiff::civil::date(2025, 11, 20))
+        );
+        assert_eq!(events[0].line(CalendarKind::Jalali), "1404/08/29  Birthday");
+    }
+}
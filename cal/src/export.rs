@@ -0,0 +1,419 @@
+//! The calendar as values instead of aligned text (`--output`).
+//!
+//! The months are collected from the raw grid (see [`crate::layout::Grid::new_grid_with`]) and the
+//! cells (see [`crate::layout::Grid::cells_with`] and [`crate::layout::ColumnContent::cells`]) so
+//! the colors, the padding and the widths of the text never leak in.
+
+use core::array;
+
+use jcal::{
+    date::{CalendarKind, CommonDate, Date},
+    names::NameProvider,
+};
+use jelal::{UOrdinal, Weekday};
+
+use crate::layout::{Cell, Column, Highlight, Layout, WEEK_DAYS};
+
+/// A printed month of a [`Layout`] (see [`Layout::months`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Month {
+    /// The first day of the month.
+    pub date: Date,
+    /// The text of the column header with the year, not padded.
+    pub header: String,
+    /// The weekday of the first column.
+    pub base_weekday: Weekday,
+    /// If true, the weeks hold the ordinals instead of the days of the month.
+    pub ordinal_mode: bool,
+    /// The days (or ordinals) of each week, 0 for the blanks.
+    pub weeks: Vec<[UOrdinal; WEEK_DAYS]>,
+    /// If each day of [`Self::weeks`] is highlighted.
+    pub highlighted: Vec<[bool; WEEK_DAYS]>,
+    /// The week number of each week if requested, 0 for the weeks without a day of the month.
+    pub weeknums: Option<Vec<usize>>,
+}
+
+impl Month {
+    /// The highlighted days in order.
+    pub fn highlighted_days(&self) -> Vec<Date> {
+        let mut v = Vec::new();
+        let mut date = self.date.clone();
+        for (week, highlighted) in self.weeks.iter().zip(&self.highlighted) {
+            for (&value, &highlighted) in week.iter().zip(highlighted) {
+                if value == 0 || !highlighted {
+                    continue;
+                }
+                if self.ordinal_mode {
+                    date.set_saturating_ordinal(value);
+                } else {
+                    date.set_saturating_day(value as _);
+                }
+                v.push(date.clone());
+            }
+        }
+        v
+    }
+
+    /// The name of the calendar of the month as written in the configuration.
+    pub fn calendar_name(&self) -> &'static str {
+        match self.date.kind() {
+            CalendarKind::Jalali => "jalali",
+            CalendarKind::Gregorian => "gregorian",
+            CalendarKind::Hijri => "hijri",
+        }
+    }
+
+    /// A JSON object of the month.
+    pub fn to_json(&self) -> String {
+        let list = |v: Vec<String>| format!("[{}]", v.join(","));
+        let numbers = |v: &[usize]| list(v.iter().map(|i| i.to_string()).collect());
+        let weeks = list(
+            self.weeks
+                .iter()
+                .map(|week| list(week.iter().map(|i| i.to_string()).collect()))
+                .collect(),
+        );
+        let weeknums = self.weeknums.as_deref().map_or("null".to_owned(), numbers);
+        let highlights = list(
+            self.highlighted_days()
+                .iter()
+                .map(|i| {
+                    let v = format!("{:04}-{:02}-{:02}", i.year(), i.month(), i.day());
+                    json_string(&v)
+                })
+                .collect(),
+        );
+        format!(
+            "{{\"calendar\":{},\"year\":{},\"month\":{},\"month_name\":{},\"header\":{},\
+             \"base_weekday\":{},\"ordinals\":{},\"weeks\":{},\"week_numbers\":{},\
+             \"highlights\":{}}}",
+            json_string(self.calendar_name()),
+            self.date.year(),
+            self.date.month(),
+            json_string(self.date.month_name()),
+            json_string(&self.header),
+            json_string(NameProvider::DEFAULT.weekday(self.base_weekday)),
+            self.ordinal_mode,
+            weeks,
+            weeknums,
+            highlights,
+        )
+    }
+}
+
+/// Quote a JSON string.
+fn json_string(s: &str) -> String {
+    let mut v = String::with_capacity(s.len() + 2);
+    v.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => v.push_str("\\\""),
+            '\\' => v.push_str("\\\\"),
+            '\n' => v.push_str("\\n"),
+            c if c.is_control() => v.push_str(&format!("\\u{:04x}", c as u32)),
+            c => v.push(c),
+        }
+    }
+    v.push('"');
+    v
+}
+
+/// Escape the text of an HTML element.
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A `<td>` (or `<th>` for the weekdays) of a cell.
+fn html_cell(cell: &Cell) -> String {
+    match cell {
+        Cell::Empty => "<td class=\"empty\"></td>".to_owned(),
+        Cell::Weekday(name) => format!("<th>{}</th>", html_escape(name)),
+        Cell::WeekNumber(v) => format!("<td class=\"week\">{}</td>", v),
+        Cell::Day {
+            value,
+            highlighted,
+            weekend,
+        } => {
+            let classes: Vec<&str> = [(*weekend, "weekend"), (*highlighted, "today")]
+                .into_iter()
+                .filter_map(|(enabled, class)| enabled.then_some(class))
+                .collect();
+            if classes.is_empty() {
+                format!("<td>{}</td>", value)
+            } else {
+                format!("<td class=\"{}\">{}</td>", classes.join(" "), value)
+            }
+        }
+    }
+}
+
+/// Quote a field of a CSV (or TSV) record as RFC 4180 if it holds the delimiter, a quote or a line
+/// break.
+fn delimited_field(s: &str, delimiter: char) -> String {
+    if s.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+/// Machine-readable outputs
+impl Layout {
+    /// The column of each printed month, each with its own weeks (not aligned to a row) and the
+    /// year in the header.
+    fn printed_columns(&self) -> Vec<Column> {
+        let mut column = self.base_row.column.clone();
+        column.year_in_header = true;
+        column.content.min_weeks = 0;
+        column.content.grid.date.set_saturating_day(1);
+        (0..=self.base_row.more_columns)
+            .map(|_| {
+                let v = column.clone();
+                column.content.grid.date.set_saturating_months_offset(1);
+                v
+            })
+            .collect()
+    }
+
+    /// The printed months as values, each with its own weeks (not aligned to a row).
+    pub fn months(&self) -> Vec<Month> {
+        let highlights = self.all_highlights();
+        let days: Vec<&Date> = highlights.iter().filter_map(Highlight::day).collect();
+
+        let mut months = Vec::with_capacity(self.base_row.more_columns + 1);
+        for column in self.printed_columns() {
+            let content = &column.content;
+            let grid = &content.grid;
+            let info = content.month_info();
+            let raw = grid.new_grid_with(&info);
+            let weeks = content.weeks_with(&info);
+            let week_rows = content.week_rows(&info, &highlights);
+            let cells = grid.cells_with(&info, &days, &week_rows);
+            let highlighted = cells[..weeks]
+                .iter()
+                .map(|row| {
+                    array::from_fn(|j| {
+                        matches!(
+                            row[j],
+                            Cell::Day {
+                                highlighted: true,
+                                ..
+                            }
+                        )
+                    })
+                })
+                .collect();
+            // the days of the neighbor months do not make a week of this month
+            let weeknums = info.weeknums.map(|weeknums| {
+                (0..weeks)
+                    .map(|i| {
+                        if raw[i].iter().all(|&c| c == 0) {
+                            0
+                        } else {
+                            weeknums[i]
+                        }
+                    })
+                    .collect()
+            });
+
+            months.push(Month {
+                date: grid.date.clone(),
+                header: column.header_text(),
+                base_weekday: grid.base_weekday,
+                ordinal_mode: grid.ordinal_mode,
+                weeks: raw[..weeks].to_vec(),
+                highlighted,
+                weeknums,
+            });
+        }
+        months
+    }
+
+    /// The printed months as a JSON array (see [`Month::to_json`]).
+    pub fn to_json(&self) -> String {
+        let months: Vec<String> = self.months().iter().map(Month::to_json).collect();
+        format!("[{}]", months.join(","))
+    }
+
+    /// The printed months as CSV (or TSV with a tab) records, a blank line between the months.
+    ///
+    /// Each month is its header, the weekdays and then one record for each week with empty fields
+    /// for the blanks. The week numbers are a field of their own with `-w`.
+    pub fn to_delimited(&self, delimiter: char) -> String {
+        let highlights = self.all_highlights();
+        let mut months = Vec::with_capacity(self.base_row.more_columns + 1);
+        for mut column in self.printed_columns() {
+            column.content.weekdays = true;
+            column.content.weekdays_before_grid = true;
+            let mut lines = vec![delimited_field(&column.header_text(), delimiter)];
+            for row in column.content.cells(&highlights) {
+                let fields: Vec<String> = row
+                    .iter()
+                    .map(|cell| match cell {
+                        Cell::Empty => String::new(),
+                        Cell::Weekday(name) => delimited_field(name, delimiter),
+                        Cell::WeekNumber(v) => v.to_string(),
+                        Cell::Day { value, .. } => value.to_string(),
+                    })
+                    .collect();
+                lines.push(fields.join(&delimiter.to_string()));
+            }
+            months.push(lines.join("\n"));
+        }
+        months.join("\n\n")
+    }
+
+    /// The printed months as HTML tables.
+    ///
+    /// The weekdays are `<th>` cells of each table and the days are marked with the `today`
+    /// (highlighted), `weekend` and `empty` classes. In [`Column::transposed`] the weeks are the
+    /// columns.
+    pub fn to_html(&self) -> String {
+        let highlights = self.all_highlights();
+        let mut tables = Vec::with_capacity(self.base_row.more_columns + 1);
+        for mut column in self.printed_columns() {
+            // each table names its own weekdays
+            column.content.weekdays = true;
+            let mut cells = column.content.cells(&highlights);
+            if column.transposed() {
+                cells = (0..cells[0].len())
+                    .map(|j| cells.iter().map(|row| row[j].clone()).collect())
+                    .collect();
+            }
+
+            let mut lines = vec![
+                "<table class=\"month\">".to_owned(),
+                format!("<caption>{}</caption>", html_escape(&column.header_text())),
+            ];
+            for row in cells {
+                let row: String = row.iter().map(html_cell).collect();
+                lines.push(format!("<tr>{}</tr>", row));
+            }
+            lines.push("</table>".to_owned());
+            tables.push(lines.join("\n"));
+        }
+        tables.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil;
+
+    use super::*;
+    use crate::layout::{NOV_2025_SUN, WeekNumConfig};
+
+    #[test]
+    fn test_json_nov_2025() {
+        let mut layout = Layout::default();
+        let content = &mut layout.base_row.column.content;
+        content.grid.date = Date::Gregorian(civil::date(2025, 11, 20));
+        content.weeknums = Some(WeekNumConfig::Based);
+        // colors never reach the values
+        content.grid.style = crate::string::StyleConfig::SOLARIZED;
+        layout.highlights = vec![Highlight::Day(Date::Gregorian(civil::date(2025, 11, 4)))];
+        layout.base_row.more_columns = 1;
+
+        let json: serde_json::Value = serde_json::from_str(&layout.to_json()).unwrap();
+        let months = json.as_array().unwrap();
+        assert_eq!(months.len(), 2);
+
+        let nov = &months[0];
+        assert_eq!(nov["calendar"], "gregorian");
+        assert_eq!(nov["year"], 2025);
+        assert_eq!(nov["month"], 11);
+        assert_eq!(nov["month_name"], "November");
+        assert_eq!(nov["header"], "November 2025");
+        assert_eq!(nov["base_weekday"], "Sunday");
+        assert_eq!(nov["ordinals"], false);
+        assert_eq!(nov["weeks"], serde_json::json!(NOV_2025_SUN));
+        assert_eq!(
+            nov["week_numbers"],
+            serde_json::json!([43, 44, 45, 46, 47, 48])
+        );
+        assert_eq!(nov["highlights"], serde_json::json!(["2025-11-04"]));
+
+        let dec = &months[1];
+        assert_eq!(dec["month"], 12);
+        assert_eq!(dec["highlights"], serde_json::json!([]));
+        assert_eq!(dec["weeks"][0], serde_json::json!([0, 1, 2, 3, 4, 5, 6]));
+
+        assert_eq!(json_string("a \"b\"\\\n\t"), r#""a \"b\"\\\n\u0009""#);
+    }
+
+    #[test]
+    fn test_html_nov_2025() {
+        let mut layout = Layout::default();
+        let content = &mut layout.base_row.column.content;
+        content.grid.date = Date::Gregorian(civil::date(2025, 11, 20));
+        content.grid.style = crate::string::StyleConfig::SOLARIZED;
+        layout.highlights = vec![Highlight::Day(Date::Gregorian(civil::date(2025, 11, 4)))];
+
+        assert_eq!(
+            layout.to_html() + "\n",
+            include_str!("../testdata/nov_2025.html")
+        );
+
+        // the weeks are the columns, the weekdays lead the rows
+        let empty = "<td class=\"empty\"></td>";
+        layout.base_row.column.vertical = true;
+        let html = layout.to_html();
+        let lines: Vec<_> = html.lines().collect();
+        assert_eq!(lines.len(), 2 + WEEK_DAYS + 1);
+        assert_eq!(
+            lines[2],
+            format!(
+                "<tr><th>Sun</th>{}<td class=\"weekend\">2</td><td class=\"weekend\">9</td>\
+                 <td class=\"weekend\">16</td><td class=\"weekend\">23</td>\
+                 <td class=\"weekend\">30</td></tr>",
+                empty
+            )
+        );
+        assert_eq!(
+            lines[4],
+            "<tr><th>Tue</th><td class=\"empty\"></td><td class=\"today\">4</td><td>11</td>\
+             <td>18</td><td>25</td><td class=\"empty\"></td></tr>"
+        );
+
+        assert_eq!(html_escape("<b>&"), "&lt;b&gt;&amp;");
+    }
+
+    #[test]
+    fn test_csv_nov_2025() {
+        let mut layout = Layout::default();
+        let content = &mut layout.base_row.column.content;
+        content.grid.date = Date::Gregorian(civil::date(2025, 11, 20));
+        content.weeknums = Some(WeekNumConfig::Based);
+        layout.highlights = vec![Highlight::Day(Date::Gregorian(civil::date(2025, 11, 4)))];
+
+        assert_eq!(
+            layout.to_delimited(','),
+            "November 2025\n\
+             ,Sun,Mon,Tue,Wed,Thu,Fri,Sat\n\
+             43,,,,,,,1\n\
+             44,2,3,4,5,6,7,8\n\
+             45,9,10,11,12,13,14,15\n\
+             46,16,17,18,19,20,21,22\n\
+             47,23,24,25,26,27,28,29\n\
+             48,30,,,,,,"
+        );
+
+        // the header is quoted only where it holds the delimiter
+        layout.base_row.column.header_format = Some("%B, %Y".to_owned());
+        layout.base_row.column.content.weeknums = None;
+        layout.base_row.column.content.grid.ordinal_mode = true;
+        layout.base_row.more_columns = 1;
+        let csv = layout.to_delimited(',');
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], "\"November, 2025\"");
+        assert_eq!(lines[2], ",,,,,,305");
+        assert_eq!(lines[8], "");
+        assert_eq!(lines[9], "\"December, 2025\"");
+        let tsv = layout.to_delimited('\t');
+        assert!(tsv.starts_with("November, 2025\nSun\tMon\t"), "{}", tsv);
+
+        assert_eq!(delimited_field("a \"b\"", ','), "\"a \"\"b\"\"\"");
+    }
+}
@@ -0,0 +1,135 @@
+//! Page through the months with single keystrokes (`--interactive`).
+//!
+//! The terminal is only switched out of the line mode with `stty` (Unix) so no TUI framework is
+//! needed. The raw mode is not tested automatically, to try it by hand: `cal -i`, then move with
+//! the arrows, `h`/`l`, `y`/`Y` and `t`, quit with `q` and check that the shell echoes again.
+
+use std::io;
+
+use jcal::date::{CommonDate, Date};
+
+/// What a key does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    PreviousMonth,
+    NextMonth,
+    PreviousYear,
+    NextYear,
+    Today,
+    Quit,
+}
+
+impl Action {
+    /// The action of the bytes of a single key press if any.
+    pub fn from_key(key: &[u8]) -> Option<Self> {
+        match key {
+            b"\x1b[D" | b"\x1bOD" | b"h" => Some(Self::PreviousMonth),
+            b"\x1b[C" | b"\x1bOC" | b"l" => Some(Self::NextMonth),
+            b"Y" => Some(Self::PreviousYear),
+            b"y" => Some(Self::NextYear),
+            b"t" => Some(Self::Today),
+            // Ctrl-C and Ctrl-D since the signals are off in the raw mode
+            b"q" | b"\x03" | b"\x04" => Some(Self::Quit),
+            _ => None,
+        }
+    }
+
+    /// Move the anchor month, returns false to quit.
+    pub fn step(self, anchor: &mut Date, today: &Date) -> bool {
+        match self {
+            Self::PreviousMonth => anchor.set_saturating_months_offset(-1),
+            Self::NextMonth => anchor.set_saturating_months_offset(1),
+            Self::PreviousYear => anchor.set_saturating_months_offset(-12),
+            Self::NextYear => anchor.set_saturating_months_offset(12),
+            Self::Today => *anchor = today.clone(),
+            Self::Quit => return false,
+        }
+        true
+    }
+}
+
+/// Keeps the terminal reading single keys without echo until dropped.
+#[cfg(unix)]
+pub struct RawMode {
+    /// The settings to restore (`stty -g`).
+    saved: String,
+}
+
+#[cfg(unix)]
+impl RawMode {
+    pub fn enter() -> io::Result<Self> {
+        let saved = Self::stty(&["-g"])?.trim().to_owned();
+        Self::stty(&["-icanon", "-echo", "-isig", "min", "1", "time", "0"])?;
+        Ok(Self { saved })
+    }
+
+    /// Run `stty` on the terminal of STDIN.
+    fn stty(args: &[&str]) -> io::Result<String> {
+        let output = std::process::Command::new("stty")
+            .args(args)
+            .stdin(std::process::Stdio::inherit())
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Self::stty(&[&self.saved]);
+    }
+}
+
+#[cfg(not(unix))]
+pub struct RawMode;
+
+#[cfg(not(unix))]
+impl RawMode {
+    pub fn enter() -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "`--interactive` is only available on Unix",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_actions() {
+        assert_eq!(Action::from_key(b"\x1b[D"), Some(Action::PreviousMonth));
+        assert_eq!(Action::from_key(b"l"), Some(Action::NextMonth));
+        assert_eq!(Action::from_key(b"Y"), Some(Action::PreviousYear));
+        assert_eq!(Action::from_key(b"y"), Some(Action::NextYear));
+        assert_eq!(Action::from_key(b"t"), Some(Action::Today));
+        assert_eq!(Action::from_key(b"q"), Some(Action::Quit));
+        assert_eq!(Action::from_key(b"\x03"), Some(Action::Quit));
+        assert_eq!(Action::from_key(b"x"), None);
+        assert_eq!(Action::from_key(b"hl"), None);
+    }
+
+    #[test]
+    fn test_step() {
+        let today = Date::Jalali(jelal::Date::from((1404, 12, 29)));
+        let mut anchor = today.clone();
+
+        assert!(Action::NextMonth.step(&mut anchor, &today));
+        assert_eq!(anchor, Date::Jalali(jelal::Date::from((1405, 1, 1))));
+        assert!(Action::PreviousYear.step(&mut anchor, &today));
+        assert_eq!(anchor, Date::Jalali(jelal::Date::from((1404, 1, 1))));
+        assert!(Action::PreviousMonth.step(&mut anchor, &today));
+        assert_eq!(anchor, Date::Jalali(jelal::Date::from((1403, 12, 1))));
+        assert!(Action::NextYear.step(&mut anchor, &today));
+        assert_eq!(anchor, Date::Jalali(jelal::Date::from((1404, 12, 1))));
+        assert!(Action::Today.step(&mut anchor, &today));
+        assert_eq!(anchor, today);
+        assert!(!Action::Quit.step(&mut anchor, &today));
+    }
+}
@@ -86,7 +86,9 @@
 //! if explictly requested to have common weekdays will be omitted in 7CELLs.
 //!
 //! `GRID_FOOTER`: Is either empty (7 CELLs configuration) or in vertical mode holds 6 CELLs with
-//! week numbers if requested (either this is enabled or `GRID_HEADER`).
+//! week numbers if requested (either this is enabled or `GRID_HEADER`). It is the last line under
+//! each column unless the week numbers come before the grid and its `COMMON_COLUMNS_PREFIX` is
+//! blank.
 //!
 //! `COLUMN_CONTENT_PREFIX`: Only when not vertical (7 CELLs configuration) and with week numbers
 //! requested holds the week number unless requested explictly to have week days per column in
@@ -95,19 +97,28 @@
 //! `GRID`: Basically is the month calendar. Either 6x7 CELLs in vertical mode (6 CELLs
 //! configuration) or 7x6 otherwise (7 CELLs configuration).
 //!
-//! `CELL`: Is either 2 characters in length or 3 if ordinals (Julian) is requested.
+//! `CELL`: Is either 2 characters in length or 3 if ordinals (Julian) is requested, one more for
+//! the moon markers.
 
 #![allow(dead_code)]
 
 use core::array;
 
 use jcal::{
-    WEEKDAYS,
-    date::{CommonDate, Date},
+    clap_helper::StaticMap,
+    date::{
+        CalendarKind, CommonDate, Date, gregorian_month_len, hijri_month_len, jalali_month_len,
+    },
+    names::NameProvider,
+    strftime::date_strftime,
 };
-use jelal::{IYear, UOrdinal, Weekday};
+use jelal::{IDayDiff, IYear, UMonthDay, UOrdinal, Weekday};
 
-use crate::string::{Aligner, ansi_width, highlight};
+use crate::{
+    events::Event,
+    moon::moon_phase,
+    string::{Aligner, Style, StyleConfig, ansi_width, cut_end, dim},
+};
 
 /// How many weeks is in each grid.
 pub const WEEK_COUNT: usize = 6;
@@ -128,56 +139,109 @@ fn join<S: AsRef<str>>(mut v: impl Iterator<Item = S>, delimiter: &str) -> Strin
 }
 
 /// Week numbers in compatible cells with this grid. (const len of 6)
+///
+/// The first and the last rows may belong to the neighbor years so each row is asked for by a day
+/// of its own, never counted on from the first row: the first day of the row (a `base_weekday`,
+/// never in the week 0 of its year) or its Monday since an ISO week starts there whatever the
+/// base of the grid is. The first row of a year is [`carry_over_weeknum`].
 pub fn weeknums(config: &WeekNumConfig, date: &Date, base_weekday: Weekday) -> [usize; WEEK_COUNT] {
-    let date = {
-        // ensure the day is the first day of the month for weeknum calculation
-        let mut new = date.clone();
-        new.set_saturating_day(1);
-        new
-    };
+    let first = first_row_day(config, date, base_weekday);
+    let first_of_year = date.month() == 1;
 
     array::from_fn(|i| {
+        if i == 0 && first_of_year {
+            return carry_over_weeknum(date, base_weekday, config);
+        }
+        let mut row = first.clone();
+        row.set_saturating_days_offset(i as IDayDiff * WEEK_DAYS as IDayDiff);
         match config {
-            // 0 is the 53 of the previous year
-            WeekNumConfig::Iso => date.iso_weeknum() as usize + i,
-            WeekNumConfig::Based => date.weeknum(base_weekday) as usize + i,
+            WeekNumConfig::Iso => row.iso_weeknum() as usize,
+            WeekNumConfig::Based => row.weeknum(base_weekday) as usize,
         }
     })
 }
 
-/// Count 6 weeks from the 1st of the given month, format and optionally highlight.
+/// The day the first row of the month of the given date is counted by (see [`weeknums`]).
+fn first_row_day(config: &WeekNumConfig, date: &Date, base_weekday: Weekday) -> Date {
+    let mut first = date.clone();
+    first.set_saturating_day(1);
+    let before = base_weekday.till_next(&first.weekday()) as IDayDiff;
+    let to_counted = match config {
+        WeekNumConfig::Iso => base_weekday.till_next(&Weekday::MON) as IDayDiff,
+        WeekNumConfig::Based => 0,
+    };
+    first.set_saturating_days_offset(to_counted - before);
+    first
+}
+
+/// The number of the first row of the year of the given date (the replacement of the week 0).
 ///
-/// Since this only count a year's weeks at max, it's output should never exceed 2 in width.
-pub fn format_weeknums(
-    date: &Date,
-    base_weekday: Weekday,
-    config: &WeekNumConfig,
-    highlight_week: Option<usize>,
-) -> [String; WEEK_COUNT] {
-    weeknums(config, date, base_weekday).map(|mut weeknum| {
-        if weeknum == 0 {
-            // set the max weeknum
-            let mut date = date.clone();
-            date.set_saturating_year(date.year().saturating_sub(1));
-            date.set_saturating_ordinal(UOrdinal::MAX);
-            weeknum = date.weeknum(base_weekday) as usize;
-        }
-        let v = Aligner::SPACE.right(&weeknum.to_string(), 2);
-        if Some(weeknum) == highlight_week {
-            highlight(&v)
-        } else {
-            v
+/// The first row of a year that does not start on the base weekday is the same week as the last
+/// row of the previous year so it carries that number over:
+/// - [`WeekNumConfig::Based`]: a week number is the count of the base weekdays so far, the last row
+///   of the previous year is 53 if that year had 53 base weekdays and 52 otherwise.
+/// - [`WeekNumConfig::Iso`]: the ISO week of the Monday of the row (the first day itself on a
+///   Monday based grid), 52 or 53 of the previous ISO year unless that Monday is in the week 1.
+///
+/// Returns 1 if nothing is carried over.
+pub fn carry_over_weeknum(date: &Date, base_weekday: Weekday, config: &WeekNumConfig) -> usize {
+    let mut first = date.clone();
+    first.set_saturating_ordinal(1);
+    match config {
+        WeekNumConfig::Iso => first_row_day(config, &first, base_weekday).iso_weeknum() as usize,
+        WeekNumConfig::Based if first.weekday() == base_weekday => 1,
+        WeekNumConfig::Based => {
+            first.set_saturating_year(first.year().saturating_sub(1));
+            first.weeks_in_year(base_weekday) as usize
         }
-    })
+    }
+}
+
+/// Facts of a displayed month that are calculated once and shared by its grid and week numbers.
+///
+/// Constructing calendar dates is not free (specially the Jalali ones) so asking the date for
+/// each cell is avoided.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthInfo {
+    /// The weekday of the first day of the month.
+    pub first_weekday: Weekday,
+    /// The last day of the month.
+    pub month_end: UMonthDay,
+    /// The ordinal of the first day of the month.
+    pub first_ordinal: UOrdinal,
+    /// The week number of each row of the grid if requested (see [`weeknums`]).
+    ///
+    /// The week 0 never shows since its row is counted in the previous year (see
+    /// [`carry_over_weeknum`]).
+    pub weeknums: Option<[usize; WEEK_COUNT]>,
+}
+
+impl MonthInfo {
+    pub fn new(date: &Date, base_weekday: Weekday, weeknums: Option<&WeekNumConfig>) -> Self {
+        let mut first = date.clone();
+        first.set_saturating_day(1);
+
+        let weeknums = weeknums.map(|config| self::weeknums(config, &first, base_weekday));
+
+        Self {
+            first_weekday: first.weekday(),
+            month_end: match date.kind() {
+                CalendarKind::Jalali => jalali_month_len(date.year(), date.month()),
+                CalendarKind::Gregorian => gregorian_month_len(date.year(), date.month()),
+                CalendarKind::Hijri => hijri_month_len(date.year(), date.month()),
+            },
+            first_ordinal: first.ordinal(),
+            weeknums,
+        }
+    }
 }
 
 /// Collect a column weekdays from the base to the end.
 pub fn weekdays(base_weekday: Weekday) -> [&'static str; WEEK_DAYS] {
-    array::from_fn(|offset| WEEKDAYS[base_weekday.forward(offset).get() as usize])
+    array::from_fn(|offset| NameProvider::DEFAULT.weekday(base_weekday.forward(offset)))
 }
 
 /// How week counting should work.
-#[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WeekNumConfig {
     /// ISO 8601 system of counting (Monday based, the first Thursday in the new year is Week 1).
@@ -186,10 +250,22 @@ pub enum WeekNumConfig {
     Based,
 }
 
+impl WeekNumConfig {
+    pub const PARSER_DEFAULT: &'static str = "based";
+
+    pub const PARSER_MAP: StaticMap<&'static Self> =
+        StaticMap(&[(Self::PARSER_DEFAULT, &Self::Based), ("iso", &Self::Iso)]);
+}
+
 /// What to highlight.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Highlight {
-    Week(usize),
+    /// The week that holds the anchor, its days are highlighted in every month showing them while
+    /// the number is matched by the dates since the numbers restart each year.
+    Week {
+        number: usize,
+        anchor: Date,
+    },
     Day(Date),
 }
 
@@ -197,13 +273,14 @@ impl Highlight {
     pub fn day(&self) -> Option<&Date> {
         match self {
             Self::Day(v) => Some(v),
-            Self::Week(_) => None,
+            Self::Week { .. } => None,
         }
     }
 
-    pub fn week(&self) -> Option<usize> {
+    /// A day of the highlighted week.
+    pub fn week_anchor(&self) -> Option<&Date> {
         match self {
-            Self::Week(v) => Some(*v),
+            Self::Week { anchor, .. } => Some(anchor),
             Self::Day(_) => None,
         }
     }
@@ -218,55 +295,196 @@ pub struct Grid {
     pub ordinal_mode: bool,
     /// The start of the week.
     pub base_weekday: Weekday,
+    /// How the highlighted and weekend cells are painted.
+    pub style: StyleConfig,
+    /// If true, the empty cells show the days of the previous and the next months (dimmed).
+    pub adjacent_days: bool,
+    /// If true, each cell is one wider for a new or full moon marker (see [`moon_phase`]).
+    pub moon: bool,
+    /// If given, each day is followed by the same day in this calendar ("12/4").
+    pub secondary: Option<CalendarKind>,
+    /// The days of the weekend instead of the ones of the calendar (see [`Self::weekend_days`]).
+    pub weekend: Option<Vec<Weekday>>,
 }
 
 impl Grid {
+    /// The given weekend or the one of the calendar of the date (Friday in Jalali and Hijri,
+    /// Saturday and Sunday otherwise).
+    pub fn weekend_days(&self) -> &[Weekday] {
+        if let Some(v) = &self.weekend {
+            return v;
+        }
+        match self.date {
+            Date::Jalali(_) | Date::Hijri(_) => &[Weekday::FRI],
+            Date::Gregorian(_) => &[Weekday::SAT, Weekday::SUN],
+        }
+    }
+
     /// Put a value in a cell size of this grid.
     pub fn format_in_day_cell(&self, s: &str) -> String {
         Aligner::SPACE.right(&s, self.day_cell_width())
     }
+    /// How many characters make a single day of month (or ordinal) in a cell.
+    pub fn value_width(&self) -> usize {
+        if self.ordinal_mode { 3 } else { 2 }
+    }
+
     /// How many characters make a single cell for writing a day of month.
     pub fn day_cell_width(&self) -> usize {
-        if self.ordinal_mode { 3 } else { 2 }
+        let mut width = self.value_width();
+        if self.secondary.is_some() {
+            width = 2 * width + 1;
+        }
+        if self.moon { width + 1 } else { width }
     }
 
-    /// Format a 7x6 grid of weeks with corresponding weekdays as string, optionally a day brighter.
-    pub fn format(&self, highlight_day: Option<&Date>) -> [[String; WEEK_DAYS]; WEEK_COUNT] {
+    /// The value of a cell followed by its [`Self::secondary`] and the moon marker if requested.
+    ///
+    /// The date of the cell is only needed for those, the marker is skipped if `marker` is false.
+    fn cell_text(&self, value: UOrdinal, date: Option<Date>, marker: bool) -> String {
+        let width = self.value_width();
+        let mut text = Aligner::SPACE.right(&value.to_string(), width);
+        if let Some(kind) = self.secondary {
+            // converted per cell so the secondary month may start anywhere in the grid
+            let secondary = date.as_ref().map_or(String::new(), |i| {
+                let i = i.convert_to(kind);
+                if self.ordinal_mode {
+                    i.ordinal().to_string()
+                } else {
+                    i.day().to_string()
+                }
+            });
+            text.push('/');
+            text.push_str(&Aligner::SPACE.left(&secondary, width));
+        }
+        if self.moon {
+            let phase = date.filter(|_| marker).map(|i| moon_phase(i.into()));
+            text.push_str(phase.and_then(|i| i.marker()).unwrap_or(" "));
+        }
+        text
+    }
+
+    /// If the cells need to know their dates (see [`Self::cell_text`]).
+    fn cells_need_date(&self) -> bool {
+        self.moon || self.secondary.is_some()
+    }
+
+    /// The month facts of the date without week numbers (see [`MonthInfo::new`]).
+    pub fn month_info(&self) -> MonthInfo {
+        MonthInfo::new(&self.date, self.base_weekday, None)
+    }
+
+    /// Format a 7x6 grid of weeks with corresponding weekdays as string, the given days brighter.
+    pub fn format(&self, highlight_days: &[&Date]) -> [[String; WEEK_DAYS]; WEEK_COUNT] {
+        self.format_with(&self.month_info(), highlight_days, &[])
+    }
+
+    /// The row of [`Self::new_grid`] that holds the given day if any day of the month is in it.
+    ///
+    /// The row is found by the dates so it is the same week even where the numbers restart.
+    pub fn week_row_with(&self, info: &MonthInfo, day: &Date) -> Option<usize> {
+        let raw = self.new_grid_with(info);
+        (0..WEEK_COUNT).find(|&i| {
+            raw[i].iter().any(|&c| c != 0)
+                && self.adjacent_date_with(info, i, 0) <= *day
+                && *day <= self.adjacent_date_with(info, i, WEEK_DAYS - 1)
+        })
+    }
+
+    /// Same as [`Self::format`] with the month facts already at hand, the days of the given rows
+    /// (see [`Self::week_row_with`]) brighter too.
+    pub fn format_with(
+        &self,
+        info: &MonthInfo,
+        highlight_days: &[&Date],
+        highlight_rows: &[usize],
+    ) -> [[String; WEEK_DAYS]; WEEK_COUNT] {
+        let cells = self.cells_with(info, highlight_days, highlight_rows);
+        array::from_fn(|i| array::from_fn(|j| self.format_cell_with(info, i, j, &cells[i][j])))
+    }
+
+    /// The values [`Self::format_with`] writes, the blanks (even with the adjacent days) are
+    /// [`Cell::Empty`].
+    pub fn cells_with(
+        &self,
+        info: &MonthInfo,
+        highlight_days: &[&Date],
+        highlight_rows: &[usize],
+    ) -> [[Cell; WEEK_DAYS]; WEEK_COUNT] {
         let date = &self.date;
 
-        let is_highlight = |day: UOrdinal| {
-            highlight_day
-                .map(|hday| {
-                    // not the most performant but the most pretty
-                    let mut date = date.clone();
-                    if self.ordinal_mode {
-                        date.set_saturating_ordinal(day);
-                    } else {
-                        date.set_saturating_day(day as u8);
-                    }
-                    *hday == date
-                })
-                .unwrap_or(false)
-        };
+        // the values of the highlighted cells in this month, found once not per cell
+        let highlight_values: Vec<UOrdinal> = highlight_days
+            .iter()
+            .filter(|hday| {
+                hday.kind() == date.kind()
+                    && hday.year() == date.year()
+                    && hday.month() == date.month()
+            })
+            .map(|hday| {
+                if self.ordinal_mode {
+                    hday.ordinal()
+                } else {
+                    hday.day() as UOrdinal
+                }
+            })
+            .collect();
 
-        let raw = self.new_grid();
+        let weekend = self.weekend_days();
+        let raw = self.new_grid_with(info);
         array::from_fn(|i| {
             array::from_fn(|j| {
                 let value = raw[i][j];
                 if value == 0 {
-                    self.format_in_day_cell("")
-                } else {
-                    let s = self.format_in_day_cell(&value.to_string());
-                    if is_highlight(value) {
-                        highlight(&s)
-                    } else {
-                        s
-                    }
+                    return Cell::Empty;
+                }
+                Cell::Day {
+                    value,
+                    highlighted: highlight_values.contains(&value) || highlight_rows.contains(&i),
+                    weekend: weekend.contains(&self.base_weekday.forward(j)),
                 }
             })
         })
     }
 
+    /// The text of a cell of [`Self::cells_with`] in the given row and column of the grid.
+    pub fn format_cell_with(
+        &self,
+        info: &MonthInfo,
+        row: usize,
+        col: usize,
+        cell: &Cell,
+    ) -> String {
+        match *cell {
+            Cell::Day {
+                value,
+                highlighted,
+                weekend,
+            } => {
+                let mut style = Style::PLAIN;
+                if weekend {
+                    style = style.merge(self.style.weekend);
+                }
+                if highlighted {
+                    style = style.merge(self.style.today);
+                }
+                let date = self
+                    .cells_need_date()
+                    .then(|| self.date_of_cell_with(info, row, col))
+                    .flatten();
+                style.paint(&self.format_in_day_cell(&self.cell_text(value, date, true)))
+            }
+            _ if !self.adjacent_days => self.format_in_day_cell(""),
+            // never highlighted nor a weekend, these are not a part of the month
+            _ => {
+                let date = self.adjacent_date_with(info, row, col);
+                let value = self.value_of(&date);
+                let text = self.cell_text(value, Some(date), false);
+                dim(&self.format_in_day_cell(&text))
+            }
+        }
+    }
+
     /// Create a grid in 7 days times 6 weeks formation.
     // maximum of 6 weeks of 7 days.
     //
@@ -276,17 +494,16 @@ impl Grid {
     // of the month) times the number of possible month last days (28..=31 for Gregorian and
     // 29..=31 for Jalali).
     pub fn new_grid(&self) -> [[UOrdinal; WEEK_DAYS]; WEEK_COUNT] {
-        let mut cells = [[0; _]; _];
+        self.new_grid_with(&self.month_info())
+    }
 
-        let start_month = {
-            let mut v = self.date.clone();
-            v.set_saturating_day(1);
-            v
-        };
-        let month_end = self.date.month_end_day();
+    /// Same as [`Self::new_grid`] with the month facts already at hand.
+    pub fn new_grid_with(&self, info: &MonthInfo) -> [[UOrdinal; WEEK_DAYS]; WEEK_COUNT] {
+        let mut cells = [[0; _]; _];
 
+        let month_end = info.month_end;
         let offset = if self.ordinal_mode {
-            start_month.ordinal() - 1
+            info.first_ordinal - 1
         } else {
             0
         };
@@ -304,7 +521,7 @@ impl Grid {
         // ```
         //
         // That is 6.
-        let first_i: usize = self.base_weekday.till_next(&start_month.weekday()) as usize;
+        let first_i: usize = self.base_weekday.till_next(&info.first_weekday) as usize;
 
         let mut row: usize = 0;
         let mut i = first_i;
@@ -322,6 +539,53 @@ impl Grid {
 
         cells
     }
+
+    /// The day (or ordinal) of a neighbor month for an empty cell of [`Self::new_grid`].
+    ///
+    /// The cells before the first day are the tail of the previous month and the ones after the
+    /// end are the head of the next month, ordinals are of their own year.
+    pub fn adjacent_value_with(&self, info: &MonthInfo, row: usize, col: usize) -> UOrdinal {
+        self.value_of(&self.adjacent_date_with(info, row, col))
+    }
+
+    /// The date of any cell of [`Self::new_grid`] counting into the neighbor months.
+    pub fn adjacent_date_with(&self, info: &MonthInfo, row: usize, col: usize) -> Date {
+        let first_i = self.base_weekday.till_next(&info.first_weekday) as IDayDiff;
+        let mut date = self.date.clone();
+        date.set_saturating_day(1);
+        date.set_saturating_days_offset((row * WEEK_DAYS + col) as IDayDiff - first_i);
+        date
+    }
+
+    /// The day (or ordinal in the ordinal mode) a cell shows for the date.
+    fn value_of(&self, date: &Date) -> UOrdinal {
+        if self.ordinal_mode {
+            date.ordinal()
+        } else {
+            date.day() as UOrdinal
+        }
+    }
+
+    /// The date shown in a cell of [`Self::new_grid`] (`None` for the empty cells).
+    pub fn date_of_cell(&self, row: usize, col: usize) -> Option<Date> {
+        self.date_of_cell_with(&self.month_info(), row, col)
+    }
+
+    /// Same as [`Self::date_of_cell`] with the month facts already at hand.
+    pub fn date_of_cell_with(&self, info: &MonthInfo, row: usize, col: usize) -> Option<Date> {
+        if row >= WEEK_COUNT || col >= WEEK_DAYS {
+            return None;
+        }
+        let first_i = self.base_weekday.till_next(&info.first_weekday) as usize;
+        let day = (row * WEEK_DAYS + col).checked_sub(first_i)? + 1;
+        if day > info.month_end as usize {
+            return None;
+        }
+
+        let mut date = self.date.clone();
+        date.set_saturating_day(day as UMonthDay);
+        Some(date)
+    }
 }
 
 impl Default for Grid {
@@ -330,10 +594,32 @@ impl Default for Grid {
             date: Date::default(),
             ordinal_mode: false,
             base_weekday: Weekday::SUN,
+            style: Default::default(),
+            adjacent_days: false,
+            moon: false,
+            secondary: None,
+            weekend: None,
         }
     }
 }
 
+/// A cell of [`ColumnContent::cells`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    /// Not a day of the month (the blanks and the corners).
+    Empty,
+    /// The abbreviation of a weekday.
+    Weekday(String),
+    /// The number of a week.
+    WeekNumber(usize),
+    /// A day (or ordinal) of the month.
+    Day {
+        value: UOrdinal,
+        highlighted: bool,
+        weekend: bool,
+    },
+}
+
 /// Holds a grid in string format.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ColumnContent {
@@ -345,6 +631,13 @@ pub struct ColumnContent {
     pub weekdays: bool,
     /// If true, first (from right to left or top to bottom) week days appears.
     pub weekdays_before_grid: bool,
+    /// If true, the trailing weeks without a day of the month are dropped.
+    pub trim_empty_weeks: bool,
+    /// Weeks to keep regardless of trimming so the months of a [`Row`] line up.
+    pub min_weeks: usize,
+    /// If true, each week is a column and the weekdays are the first column of the month (see
+    /// [`Column::transposed`]).
+    pub transposed: bool,
     pub grid: Grid,
 }
 
@@ -355,8 +648,9 @@ impl ColumnContent {
     ///
     /// This has extra empty fields to adjust its width hence not statically 7 days.
     pub fn format_weekdays_force(&self) -> Vec<String> {
+        // wide cells still get the abbreviation not a cut name ("  Sun" not "Sunda")
         let mut v = weekdays(self.grid.base_weekday)
-            .map(|s| self.grid.format_in_day_cell(s))
+            .map(|s| self.grid.format_in_day_cell(cut_end(s, 3)))
             .to_vec();
         if self.weeknums.is_some() {
             // create an empty cell to shift for the added row
@@ -369,11 +663,52 @@ impl ColumnContent {
         v
     }
 
-    /// How many rows and columns will this formatted value have.
+    /// How many weeks hold a day of the month (the adjacent days do not count).
+    pub fn used_weeks(&self) -> usize {
+        self.used_weeks_with(&self.grid.month_info())
+    }
+
+    /// Same as [`Self::used_weeks`] with the month facts already at hand.
+    pub fn used_weeks_with(&self, info: &MonthInfo) -> usize {
+        let first_i = self.grid.base_weekday.till_next(&info.first_weekday) as usize;
+        (first_i + info.month_end as usize).div_ceil(WEEK_DAYS)
+    }
+
+    /// How many weeks will be formatted (see [`Self::trim_empty_weeks`]).
+    pub fn weeks(&self) -> usize {
+        // the month facts are not needed without trimming
+        if !self.trim_empty_weeks {
+            return WEEK_COUNT;
+        }
+        self.weeks_with(&self.grid.month_info())
+    }
+
+    /// Same as [`Self::weeks`] with the month facts already at hand.
+    pub fn weeks_with(&self, info: &MonthInfo) -> usize {
+        if self.trim_empty_weeks {
+            self.used_weeks_with(info)
+                .max(self.min_weeks)
+                .min(WEEK_COUNT)
+        } else {
+            WEEK_COUNT
+        }
+    }
+
+    /// How many rows and columns will this formatted value have (see [`Self::transposed`]).
     pub fn row_cols(&self) -> (usize, usize) {
-        let rows = WEEK_COUNT + if self.weekdays { 1 } else { 0 };
+        self.row_cols_oriented(self.transposed)
+    }
+
+    /// Same as [`Self::row_cols`] with the weeks as the columns if transposed regardless of
+    /// [`Self::transposed`].
+    pub fn row_cols_oriented(&self, transposed: bool) -> (usize, usize) {
+        let rows = self.weeks() + if self.weekdays { 1 } else { 0 };
         let cols = WEEK_DAYS + if self.weeknums.is_some() { 1 } else { 0 };
-        (rows, cols)
+        if transposed {
+            (cols, rows)
+        } else {
+            (rows, cols)
+        }
     }
 
     /// If printed back to back, what will be the width of each row.
@@ -386,44 +721,82 @@ impl ColumnContent {
             }
     }
 
-    /// This guarantees that every inner vec has the same length.
-    pub fn format(&self, highlight_section: Option<&Highlight>) -> Vec<Vec<String>> {
-        let mut grid = self
+    /// The month facts of the grid with the week numbers if requested.
+    pub fn month_info(&self) -> MonthInfo {
+        MonthInfo::new(
+            &self.grid.date,
+            self.grid.base_weekday,
+            self.weeknums.as_ref(),
+        )
+    }
+
+    /// The rows of the grid with a day of the highlighted weeks.
+    pub fn week_rows(&self, info: &MonthInfo, highlights: &[Highlight]) -> Vec<usize> {
+        highlights
+            .iter()
+            .filter_map(Highlight::week_anchor)
+            .filter_map(|anchor| self.grid.week_row_with(info, anchor))
+            .collect()
+    }
+
+    /// The same cells as [`Self::format`] as values for the outputs that are not aligned text.
+    ///
+    /// Only the days of the month are given, not the adjacent days, markers or the second
+    /// calendar.
+    pub fn cells(&self, highlights: &[Highlight]) -> Vec<Vec<Cell>> {
+        let info = self.month_info();
+        let week_rows = self.week_rows(&info, highlights);
+        self.cells_with(&info, &week_rows, highlights)
+    }
+
+    /// Same as [`Self::cells`] with the month facts and the highlighted rows already at hand.
+    fn cells_with(
+        &self,
+        info: &MonthInfo,
+        week_rows: &[usize],
+        highlights: &[Highlight],
+    ) -> Vec<Vec<Cell>> {
+        let days: Vec<&Date> = highlights.iter().filter_map(Highlight::day).collect();
+        let mut grid: Vec<Vec<Cell>> = self
             .grid
-            .format(highlight_section.as_ref().and_then(|i| i.day()))
+            .cells_with(info, &days, week_rows)
             .into_iter()
             .map(|i| i.to_vec())
-            .collect::<Vec<_>>();
+            .collect();
 
         // regardless of the content, this always inserts a row then adds a column.
         // flags just change the content of the rows and columns.
 
-        let cols = self.weeknums.as_ref().map(|c| {
-            format_weeknums(
-                &self.grid.date,
-                self.grid.base_weekday,
-                c,
-                highlight_section.and_then(|i| i.week()),
-            )
-        });
-
-        if let Some(cols) = cols {
-            for (i, v) in cols.into_iter().enumerate() {
-                let col = if grid[i].iter().all(|c| c.trim_start().is_empty()) {
-                    Self::WEEKNUM_EMPTY.to_owned()
+        if let Some(weeknums) = info.weeknums {
+            for (i, row) in grid.iter_mut().enumerate() {
+                // the days of the neighbor months do not make a week of this month
+                let cell = if row.iter().all(|c| *c == Cell::Empty) {
+                    Cell::Empty
                 } else {
-                    v
+                    Cell::WeekNumber(weeknums[i])
                 };
                 if self.weeknums_before_grid {
-                    grid[i].insert(0, col);
+                    row.insert(0, cell);
                 } else {
-                    grid[i].push(col);
+                    row.push(cell);
                 }
             }
         }
 
+        grid.truncate(self.weeks_with(info));
+
         if self.weekdays {
-            let row = self.format_weekdays_force();
+            let mut row: Vec<Cell> = weekdays(self.grid.base_weekday)
+                .iter()
+                .map(|i| Cell::Weekday(cut_end(i, 3).to_owned()))
+                .collect();
+            if self.weeknums.is_some() {
+                if self.weeknums_before_grid {
+                    row.insert(0, Cell::Empty);
+                } else {
+                    row.push(Cell::Empty);
+                }
+            }
             if self.weekdays_before_grid {
                 grid.insert(0, row)
             } else {
@@ -433,6 +806,65 @@ impl ColumnContent {
 
         grid
     }
+
+    /// The text of each of [`Self::cells`], a column for each week if [`Self::transposed`].
+    ///
+    /// This guarantees that every inner vec has the same length.
+    pub fn format(&self, highlights: &[Highlight]) -> Vec<Vec<String>> {
+        self.format_oriented(highlights, self.transposed)
+    }
+
+    /// Same as [`Self::format`] with the weeks as the columns if transposed regardless of
+    /// [`Self::transposed`].
+    pub fn format_oriented(&self, highlights: &[Highlight], transposed: bool) -> Vec<Vec<String>> {
+        let info = self.month_info();
+        let week_rows = self.week_rows(&info, highlights);
+        // where the grid starts after the weekdays and the week numbers
+        let row_offset = usize::from(self.weekdays && self.weekdays_before_grid);
+        let col_offset = usize::from(self.weeknums.is_some() && self.weeknums_before_grid);
+
+        let rows: Vec<Vec<String>> = self
+            .cells_with(&info, &week_rows, highlights)
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let week = i.wrapping_sub(row_offset);
+                row.iter()
+                    .enumerate()
+                    .map(|(j, cell)| {
+                        let day = j.wrapping_sub(col_offset);
+                        match cell {
+                            Cell::Weekday(name) => self.grid.format_in_day_cell(name),
+                            Cell::WeekNumber(v) => {
+                                let v = Aligner::SPACE.right(&v.to_string(), 2);
+                                if week_rows.contains(&week) {
+                                    self.grid.style.today.paint(&v)
+                                } else {
+                                    v
+                                }
+                            }
+                            // the blanks out of the grid (the week numbers and the corner)
+                            Cell::Empty if week >= WEEK_COUNT || day >= WEEK_DAYS => {
+                                Self::WEEKNUM_EMPTY.to_owned()
+                            }
+                            _ => self.grid.format_cell_with(&info, week, day, cell),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        if !transposed {
+            return rows;
+        }
+        // the week numbers and the weekdays may be narrower than a day
+        (0..rows[0].len())
+            .map(|j| {
+                rows.iter()
+                    .map(|row| self.grid.format_in_day_cell(&row[j]))
+                    .collect()
+            })
+            .collect()
+    }
 }
 
 impl Default for ColumnContent {
@@ -442,6 +874,9 @@ impl Default for ColumnContent {
             weeknums_before_grid: true, // no difference
             weekdays: true,
             weekdays_before_grid: true,
+            trim_empty_weeks: false,
+            min_weeks: 0,
+            transposed: false,
             grid: Default::default(),
         }
     }
@@ -454,8 +889,11 @@ pub struct Column {
     pub delimiter: String,
     /// If true, year will be explicitly written in the column header.
     pub year_in_header: bool,
-    /// If false, each week is a row, else each week is a column (transposed).
+    /// If true, each week is a column (transposed) and the weekdays are moved out of the months
+    /// to the start of the rows like `ncal` (see [`Layout::common_weekday`]).
     pub vertical: bool,
+    /// Replaces the month name (and year) with a date format (see [`date_strftime`]).
+    pub header_format: Option<String>,
 }
 
 impl Column {
@@ -472,64 +910,88 @@ impl Column {
         }
     }
 
-    fn format_header(&self) -> String {
-        // TODO FIXME add tests to make sure this does not produce trimmed values if the produced
-        //            string is smaller than the given width.
+    /// The month name, with the year if asked for.
+    fn default_header_text(&self, with_year: bool) -> String {
         let date = &self.content.grid.date;
-        let month_name = date.month_name();
-        let width = self.width();
-        if self.year_in_header {
-            Aligner::SPACE.center(
-                &(month_name.to_owned() + " " + &Self::year_format(date.year())),
-                width,
-            )
+        let month_name = date.month_name().to_owned();
+        if with_year {
+            month_name + " " + &Self::year_format(date.year())
+        } else {
+            month_name
+        }
+    }
+
+    /// The text of the header before it is centered.
+    ///
+    /// A [`Self::header_format`] that writes nothing of the year (e.g. no `%y` or `%EN`) gets it
+    /// after it where [`Self::year_in_header`] asks for it, otherwise it is printed as is.
+    pub fn header_text(&self) -> String {
+        let date = &self.content.grid.date;
+        // the format is validated when given so the default is only a fallback
+        let Some((format, text)) = self
+            .header_format
+            .as_ref()
+            .and_then(|format| date_strftime(format, date).ok().map(|text| (format, text)))
+        else {
+            return self.default_header_text(self.year_in_header);
+        };
+        if !self.year_in_header {
+            return text;
+        }
+        // the format writes the year if it changes with it
+        let mut other_year = date.clone();
+        other_year.set_saturating_year(date.year().saturating_add(1));
+        if date_strftime(format, &other_year).is_ok_and(|i| i != text) {
+            text
         } else {
-            Aligner::SPACE.center(month_name, width)
+            text + " " + &Self::year_format(date.year())
         }
     }
 
+    /// The [`Self::header_text`] centered, or the first of the month name with the year and alone
+    /// that fits the width (the month name is cut if even that does not).
+    fn format_header(&self) -> String {
+        let width = self.width();
+        let text = [
+            self.header_text(),
+            self.default_header_text(self.year_in_header),
+        ]
+        .into_iter()
+        .find(|i| ansi_width(i) <= width)
+        .unwrap_or_else(|| self.default_header_text(false));
+        Aligner::SPACE.center(&text, width)
+    }
+
     /// Join the given cells with proper delimiter.
     pub fn join_cells<S: AsRef<str>>(&self, v: impl Iterator<Item = S>) -> String {
         join(v, &self.delimiter)
     }
 
+    /// If each week is a column, either in [`Self::vertical`] or just by
+    /// [`ColumnContent::transposed`] which leaves the weekdays in the month.
+    pub fn transposed(&self) -> bool {
+        self.vertical || self.content.transposed
+    }
+
     /// What will be the width of this column.
     pub fn width(&self) -> usize {
         let dw = ansi_width(&self.delimiter);
-        if self.vertical {
-            let c = self.content.row_cols().0;
+        let c = self.content.row_cols_oriented(self.transposed()).1;
+        if self.transposed() {
             // since resize is done using the cell size, we just count that
             c * self.content.grid.day_cell_width() + (c - 1) * dw
         } else {
-            let c = self.content.row_cols().1;
             self.content.row_str_width() + (c - 1) * dw
         }
     }
 
     /// Return a vec row for each line.
-    pub fn format(&self, highlight_section: Option<&Highlight>) -> Vec<String> {
+    pub fn format(&self, highlights: &[Highlight]) -> Vec<String> {
         // merge all the content into rows.
-        let content = self.content.format(highlight_section);
-        let (rows, cols) = if self.vertical {
-            let v = self.content.row_cols();
-            (v.1, v.0)
-        } else {
-            self.content.row_cols()
-        };
-        let mut lines = Vec::with_capacity(rows + 1);
+        let content = self.content.format_oriented(highlights, self.transposed());
+        let mut lines = Vec::with_capacity(content.len() + 1);
         lines.push(self.format_header());
-        for i in 0..rows {
-            let line = self.join_cells((0..cols).map(|j| {
-                if self.vertical {
-                    // adjust weekdays for column size since they may not be.
-                    self.content.grid.format_in_day_cell(&content[j][i])
-                } else {
-                    content[i][j].clone()
-                }
-            }));
-            lines.push(line);
-        }
-
+        lines.extend(content.iter().map(|row| self.join_cells(row.iter())));
         lines
     }
 }
@@ -541,6 +1003,7 @@ impl Default for Column {
             delimiter: DEFAULT_DELIMITER.to_owned(),
             year_in_header: false,
             vertical: false,
+            header_format: None,
         }
     }
 }
@@ -554,6 +1017,12 @@ pub struct Row {
     pub delimiter: String,
     /// Governs the start date and column formatting.
     pub column: Column,
+    /// Write the year only in the columns whose year differs from the previous column.
+    ///
+    /// This overrides [`Column::year_in_header`] and the first formatted column is always labeled.
+    pub year_on_change: bool,
+    /// The year of the last formatted column, kept between rows (see [`Self::year_on_change`]).
+    previous_year: Option<IYear>,
 }
 
 impl Row {
@@ -582,33 +1051,78 @@ impl Row {
         1 + (maximum_width / column_width)
     }
 
-    /// Return a vec row for each line. This moves the column forward.
-    pub fn format_mut(&mut self, highlight_section: Option<&Highlight>) -> Vec<String> {
-        let mut lines = self.column.format(highlight_section);
+    /// Format the current column and move it to the next month.
+    fn format_column_mut(&mut self, highlights: &[Highlight]) -> Vec<String> {
+        if self.year_on_change {
+            let year = self.column.content.grid.date.year();
+            self.column.year_in_header = self.previous_year != Some(year);
+            self.previous_year = Some(year);
+        }
+        let lines = self.column.format(highlights);
         self.column
             .content
             .grid
             .date
             .set_saturating_months_offset(1);
+        lines
+    }
+
+    /// Keep as many weeks in each column as the longest month of this row needs.
+    fn align_weeks(&mut self) {
+        let content = &self.column.content;
+        if !content.trim_empty_weeks {
+            return;
+        }
+        let mut content = content.clone();
+        content.min_weeks = 0;
+        let mut weeks = 0;
+        for _ in 0..=self.more_columns {
+            weeks = weeks.max(content.used_weeks());
+            content.grid.date.set_saturating_months_offset(1);
+        }
+        self.column.content.min_weeks = weeks;
+    }
+
+    /// Return a vec row for each line. This moves the column forward.
+    pub fn format_mut(&mut self, highlights: &[Highlight]) -> Vec<String> {
+        self.align_weeks();
+        let mut width = self.column.width();
+        let mut lines = self.format_column_mut(highlights);
 
         while self.more_columns != 0 {
             self.more_columns -= 1;
 
-            let mut new = self.column.format(highlight_section).into_iter();
-            self.column
-                .content
-                .grid
-                .date
-                .set_saturating_months_offset(1);
-
-            for line in lines.iter_mut() {
-                line.push_str(&self.delimiter);
-                line.push_str(&new.next().unwrap());
-            }
+            let new_width = self.column.width();
+            let new = self.format_column_mut(highlights);
+            self.merge_lines(&mut lines, width, new, new_width);
+            width += ansi_width(&self.delimiter) + new_width;
         }
 
         lines
     }
+
+    /// Append each line of a new column of `new_width` to the lines formatted so far (`width`).
+    ///
+    /// The side with fewer lines is padded with blanks of its width so nothing shifts.
+    pub fn merge_lines(
+        &self,
+        lines: &mut Vec<String>,
+        width: usize,
+        new: Vec<String>,
+        new_width: usize,
+    ) {
+        if lines.len() < new.len() {
+            lines.resize(new.len(), Aligner::SPACE.filler(width));
+        }
+        let mut new = new.into_iter();
+        for line in lines.iter_mut() {
+            line.push_str(&self.delimiter);
+            match new.next() {
+                Some(v) => line.push_str(&v),
+                None => line.push_str(&Aligner::SPACE.filler(new_width)),
+            }
+        }
+    }
 }
 
 impl Default for Row {
@@ -617,6 +1131,8 @@ impl Default for Row {
             more_columns: 0,
             delimiter: DEFAULT_DELIMITER.repeat(3),
             column: Default::default(),
+            year_on_change: false,
+            previous_year: None,
         }
     }
 }
@@ -632,8 +1148,17 @@ pub struct Layout {
     ///
     /// See [`Column::vertical`].
     pub common_weekday: Option<bool>,
-    /// What day to highlight.
-    pub highlight: Option<Highlight>,
+    /// What days and weeks to highlight.
+    pub highlights: Vec<Highlight>,
+    /// If true, the year of the first month is centered above the rows (`CONTENT_HEADER`).
+    pub year_header: bool,
+    /// Replaces the year of [`Self::year_header`] with a date format of the first month (see
+    /// [`date_strftime`]).
+    pub year_header_format: Option<String>,
+    /// Days highlighted along with [`Self::highlights`].
+    pub events: Vec<Event>,
+    /// If true, the events of the printed months are listed under the calendar.
+    pub list_events: bool,
 }
 
 /// Width of the layout elements.
@@ -665,14 +1190,19 @@ impl Layout {
         Column::year_format(year)
     }
 
-    // TODO
-    // /// Returns each line as a string.
-    // pub fn format(mut self) -> impl Iterator<Item = String> {}
+    /// The [`Self::highlights`] with the days of the [`Self::events`].
+    pub fn all_highlights(&self) -> Vec<Highlight> {
+        self.highlights
+            .iter()
+            .cloned()
+            .chain(self.events.iter().map(|i| Highlight::Day(i.date.clone())))
+            .collect()
+    }
 
-    /// Print this value directly to std.
-    pub fn print(mut self) {
-        // TODO print the header banner showing the year when in year mode
-        let mut prefixes = None;
+    /// Returns each line as a string.
+    pub fn format(mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut prefixes: Option<Vec<String>> = None;
         if self.common_weekdays_is_enabled() {
             self.base_row.column.content.weekdays = false;
             let weekdays = std::iter::once("".to_owned())
@@ -689,45 +1219,131 @@ impl Layout {
                 })
                 .collect::<Vec<_>>();
             if self.base_row.column.vertical {
+                let mut weekdays = weekdays;
+                // the footer of week numbers gets the blank of the header too, so each row of
+                // months takes as many prefixes as it has lines
+                if self.base_row.column.content.weeknums.is_some() {
+                    weekdays.push(weekdays[0].clone());
+                }
                 // since a header is in place, skip this
-                prefixes = Some(weekdays.into_iter().cycle());
+                prefixes = Some(weekdays);
             } else {
-                println!("{}", self.base_row.column.join_cells(weekdays.into_iter()));
+                lines.push(self.base_row.column.join_cells(weekdays.into_iter()));
             }
         }
 
         let months_requested = self.base_row.more_columns + 1;
+        let mut first_month = self.base_row.column.content.grid.date.clone();
+        first_month.set_saturating_day(1);
 
-        // if cross year boundaries, add the year number.
-        {
+        // if cross year boundaries, add the year number where it changes (unless always shown or
+        // each year has its own header).
+        if !self.base_row.column.year_in_header && !self.year_header {
             let mut date = self.base_row.column.content.grid.date.clone();
             let initial = date.year();
-            date.set_saturating_months_offset(months_requested.min(i32::MAX as usize) as i32);
+            // the last printed month is one less than the requested months away
+            date.set_saturating_months_offset(
+                IDayDiff::try_from(months_requested - 1).unwrap_or(IDayDiff::MAX),
+            );
             if initial != date.year() {
-                self.base_row.column.year_in_header = true;
+                self.base_row.year_on_change = true;
             }
         }
 
-        // if columns don't fit in a row, update
-        let more_columns_new_value = |printed: usize| {
-            (months_requested - printed)
-                .min(self.next_row_after_column)
-                .saturating_sub(1)
+        // if columns don't fit in a row, update (a year header starts its own rows)
+        let (year_header, next_row_after_column) = (self.year_header, self.next_row_after_column);
+        let more_columns_new_value = |printed: usize, date: &Date| {
+            let mut left = months_requested - printed;
+            if year_header {
+                left = left.min(13 - usize::from(date.month()));
+            }
+            left.min(next_row_after_column).saturating_sub(1)
         };
 
         let mut printed_months = 0;
-        self.base_row.more_columns = more_columns_new_value(printed_months);
+        self.base_row.more_columns =
+            more_columns_new_value(printed_months, &self.base_row.column.content.grid.date);
+
+        let highlights = self.all_highlights();
+        let mut header_year = None;
         while printed_months < months_requested {
+            // CONTENT_HEADER, again at each year
+            let year = self.base_row.column.content.grid.date.year();
+            if self.year_header && header_year != Some(year) {
+                let header = self.format_year_header();
+                if header_year.is_none() {
+                    lines.insert(0, header);
+                } else {
+                    lines.push(String::new());
+                    lines.push(header);
+                }
+                header_year = Some(year);
+            }
+
             printed_months += self.base_row.more_columns + 1;
-            for line in self.base_row.format_mut(self.highlight.as_ref()) {
-                if let Some(prefix) = &mut prefixes {
-                    print!("{}", prefix.next().unwrap());
+            for (i, line) in self
+                .base_row
+                .format_mut(&highlights)
+                .into_iter()
+                .enumerate()
+            {
+                match &prefixes {
+                    // each row starts the prefixes again, any line past them gets the blank of
+                    // the header
+                    Some(prefix) => lines.push(prefix.get(i).unwrap_or(&prefix[0]).clone() + &line),
+                    None => lines.push(line),
                 }
-                println!("{}", line);
             }
             // recharge row for more rows
-            self.base_row.more_columns = more_columns_new_value(printed_months);
+            self.base_row.more_columns =
+                more_columns_new_value(printed_months, &self.base_row.column.content.grid.date);
+        }
+
+        if self.list_events {
+            let mut end = first_month.clone();
+            end.set_saturating_months_offset(
+                IDayDiff::try_from(months_requested).unwrap_or(IDayDiff::MAX),
+            );
+            let mut events: Vec<_> = self
+                .events
+                .iter()
+                .filter(|i| first_month <= i.date && i.date < end)
+                .collect();
+            events.sort_by(|a, b| a.date.cmp(&b.date));
+            if !events.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(events.iter().map(|i| i.line(first_month.kind())));
+        }
+
+        lines
+    }
+
+    /// The centered year (`CONTENT_HEADER`) above the row about to be formatted, the first row of a
+    /// year is the widest.
+    fn format_year_header(&mut self) -> String {
+        // trimmed vertical weeks change the width
+        self.base_row.align_weeks();
+        let date = &self.base_row.column.content.grid.date;
+        // the format is validated when given so the default is only a fallback
+        let year = self
+            .year_header_format
+            .as_ref()
+            .and_then(|format| date_strftime(format, date).ok())
+            .unwrap_or_else(|| self.year_format(date.year()));
+        let width = self.rows_left_offset() + self.base_row.width();
+        Aligner::SPACE.center(&year, width)
+    }
+
+    /// Write each line of [`Self::format`] to the given writer.
+    ///
+    /// Nothing is flushed so a buffered writer (or a locked `stdout`) is written to once.
+    pub fn write_to<W: std::io::Write>(self, mut w: W) -> std::io::Result<()> {
+        for line in self.format() {
+            w.write_all(line.as_bytes())?;
+            w.write_all(b"\n")?;
         }
+        Ok(())
     }
 }
 
@@ -749,7 +1365,11 @@ impl Default for Layout {
             base_row: Default::default(),
             next_row_after_column: 1,
             common_weekday: None,
-            highlight: None,
+            highlights: Vec::new(),
+            year_header: false,
+            year_header_format: None,
+            events: Vec::new(),
+            list_events: false,
         }
     }
 }
@@ -759,29 +1379,34 @@ impl Default for Layout {
 /// Needs to be u16 to fit possible ordinals.
 type RawGrid = [[UOrdinal; 7]; 6];
 
+/// November 2025 in a Sunday based [`Grid::new_grid`], shared by the tests of every output.
+#[cfg(test)]
+pub(crate) const NOV_2025_SUN: RawGrid = [
+    [00, 00, 00, 00, 00, 00, 01],
+    [02, 03, 04, 05, 06, 07, 08],
+    [09, 10, 11, 12, 13, 14, 15],
+    [16, 17, 18, 19, 20, 21, 22],
+    [23, 24, 25, 26, 27, 28, 29],
+    [30, 00, 00, 00, 00, 00, 00],
+];
+
 #[cfg(test)]
 mod tests {
     use jiff::civil;
 
     use super::*;
 
+    use crate::string::set_colors;
+
     #[test]
     fn test_cells_nov_2025_sun() {
-        let nov25_sun = [
-            [00, 00, 00, 00, 00, 00, 01],
-            [02, 03, 04, 05, 06, 07, 08],
-            [09, 10, 11, 12, 13, 14, 15],
-            [16, 17, 18, 19, 20, 21, 22],
-            [23, 24, 25, 26, 27, 28, 29],
-            [30, 00, 00, 00, 00, 00, 00],
-        ];
-
         assert_eq!(
-            nov25_sun,
+            NOV_2025_SUN,
             Grid {
                 date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
                 ordinal_mode: false,
-                base_weekday: Weekday::SUN
+                base_weekday: Weekday::SUN,
+                ..Default::default()
             }
             .new_grid()
         );
@@ -803,7 +1428,8 @@ mod tests {
             Grid {
                 date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
                 ordinal_mode: false,
-                base_weekday: Weekday::SAT
+                base_weekday: Weekday::SAT,
+                ..Default::default()
             }
             .new_grid()
         );
@@ -825,12 +1451,336 @@ mod tests {
             Grid {
                 date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
                 ordinal_mode: false,
-                base_weekday: Weekday::SUN
+                base_weekday: Weekday::SUN,
+                ..Default::default()
             }
-            .format(None)
+            .format(&[])
         );
     }
 
+    #[test]
+    fn test_cells_adjacent_days() {
+        let cell = |s: &str| dim(&Aligner::SPACE.right(s, 2));
+        let grid = Grid {
+            date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
+            base_weekday: Weekday::SUN,
+            adjacent_days: true,
+            ..Default::default()
+        };
+        // the highlight is only for the days of the month
+        let lines = grid.format(&[&Date::Gregorian(civil::Date::constant(2025, 10, 31))]);
+        assert_eq!(
+            lines[0][..6],
+            ["26", "27", "28", "29", "30", "31"].map(cell)
+        );
+        assert_eq!(lines[0][6], " 1");
+        assert_eq!(lines[5][0], "30");
+        assert_eq!(lines[5][1..], ["1", "2", "3", "4", "5", "6"].map(cell));
+
+        // ordinals of the neighbor years (2024 is a leap year)
+        let grid = Grid {
+            date: Date::Gregorian(civil::Date::constant(2025, 1, 1)),
+            ordinal_mode: true,
+            base_weekday: Weekday::SUN,
+            adjacent_days: true,
+            ..Default::default()
+        };
+        let cell = |s: &str| dim(&Aligner::SPACE.right(s, 3));
+        let lines = grid.format(&[]);
+        assert_eq!(lines[0][..3], ["364", "365", "366"].map(cell));
+        assert_eq!(lines[4][6], cell("32"));
+        assert_eq!(
+            lines[5],
+            ["33", "34", "35", "36", "37", "38", "39"].map(cell)
+        );
+
+        // a row of the next month alone gets no week number
+        let content = ColumnContent {
+            weeknums: Some(WeekNumConfig::Based),
+            weekdays: false,
+            grid: Grid {
+                date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
+                base_weekday: Weekday::SAT,
+                adjacent_days: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rows = content.format(&[]);
+        assert_eq!(rows[5][0], ColumnContent::WEEKNUM_EMPTY);
+        assert_eq!(rows[5][1], dim(" 6"));
+    }
+
+    #[test]
+    fn test_trim_empty_weeks() {
+        let content = |date: civil::Date| ColumnContent {
+            weekdays: false,
+            trim_empty_weeks: true,
+            grid: Grid {
+                date: Date::Gregorian(date),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // a non-leap February starting on Sunday fills exactly 4 weeks
+        let february = content(civil::Date::constant(2026, 2, 1));
+        assert_eq!(february.used_weeks(), 4);
+        assert_eq!(february.format(&[]).len(), 4);
+        assert_eq!(february.row_cols(), (4, WEEK_DAYS));
+        let untrimmed = ColumnContent {
+            trim_empty_weeks: false,
+            ..february.clone()
+        };
+        assert_eq!(untrimmed.format(&[]).len(), WEEK_COUNT);
+        assert_eq!(untrimmed.format(&[])[..4], february.format(&[])[..]);
+
+        // a 31-day month starting on Saturday needs all 6
+        let august = content(civil::Date::constant(2026, 8, 1));
+        assert_eq!(august.used_weeks(), 6);
+        assert_eq!(august.format(&[]).len(), 6);
+
+        // the adjacent days do not keep a week
+        let february = ColumnContent {
+            grid: Grid {
+                adjacent_days: true,
+                ..february.grid.clone()
+            },
+            ..february
+        };
+        assert_eq!(february.format(&[]).len(), 4);
+
+        // the months of a row keep the weeks of the longest one (March 2026 has 5)
+        let mut row = Row {
+            more_columns: 1,
+            ..Default::default()
+        };
+        row.column.content.trim_empty_weeks = true;
+        row.column.content.grid.date = Date::Gregorian(civil::Date::constant(2026, 2, 1));
+        let lines = row.format_mut(&[]);
+        assert_eq!(lines.len(), 1 + 1 + 5);
+        assert!(lines.iter().all(|i| ansi_width(i) == ansi_width(&lines[0])));
+        assert!(lines[6].starts_with(&" ".repeat(row.column.width())));
+        assert!(lines[6].trim().starts_with("29 30 31"));
+    }
+
+    #[test]
+    fn test_row_merge_unequal_lines() {
+        let row = Row {
+            delimiter: "|".to_owned(),
+            ..Default::default()
+        };
+        let column = |lines: &[&str]| lines.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+
+        // the new column is shorter
+        let mut lines = column(&["ab", "cd", "ef"]);
+        row.merge_lines(&mut lines, 2, column(&["123"]), 3);
+        assert_eq!(lines, column(&["ab|123", "cd|   ", "ef|   "]));
+
+        // the lines so far are shorter
+        let mut lines = column(&["ab"]);
+        row.merge_lines(&mut lines, 2, column(&["1", "2", "3"]), 1);
+        assert_eq!(lines, column(&["ab|1", "  |2", "  |3"]));
+
+        // a merged row is padded with its whole width
+        row.merge_lines(&mut lines, 4, column(&["x", "y", "z", "w"]), 1);
+        assert_eq!(lines, column(&["ab|1|x", "  |2|y", "  |3|z", "    |w"]));
+
+        let mut lines = Vec::new();
+        row.merge_lines(&mut lines, 0, Vec::new(), 3);
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_column_moon() {
+        let expected = vec![
+            "         November          ",
+            "Sun Mon Tue Wed Thu Fri Sat",
+            "                         1 ",
+            " 2   3   4   5○  6   7   8 ",
+            " 9  10  11  12  13  14  15 ",
+            "16  17  18  19  20● 21  22 ",
+            "23  24  25  26  27  28  29 ",
+            "30                         ",
+        ];
+        let mut column = Column::default();
+        column.content.grid = Grid {
+            date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
+            style: StyleConfig {
+                today: Style::PLAIN,
+                weekend: Style::PLAIN,
+            },
+            moon: true,
+            ..Default::default()
+        };
+        assert_eq!(column.width(), 27);
+        assert_eq!(column.format(&[]), expected);
+
+        // Jalali days are marked on their Gregorian dates, 1404-08-14 is 2025-11-05
+        column.content.grid.date = Date::Jalali(jelal::Date::from((1404, 8, 1)));
+        let lines = column.format(&[]).join("\n");
+        assert!(lines.contains("14○"), "{}", lines);
+        assert!(lines.contains("29●"), "{}", lines);
+        assert_eq!(lines.matches(['○', '●']).count(), 2);
+    }
+
+    #[test]
+    fn test_column_dual_aban_1404() {
+        let expected = vec![
+            "                  Aban                   ",
+            "  Sat   Sun   Mon   Tue   Wed   Thu   Fri",
+            "                               1/23  2/24",
+            " 3/25  4/26  5/27  6/28  7/29  8/30  9/31",
+            "10/1  11/2  12/3  13/4  14/5  15/6  16/7 ",
+            "17/8  18/9  19/10 20/11 21/12 22/13 23/14",
+            "24/15 25/16 26/17 27/18 28/19 29/20 30/21",
+            "                                         ",
+        ];
+        let mut column = Column::default();
+        column.content.grid = Grid {
+            date: Date::Jalali(jelal::Date::from((1404, 8, 1))),
+            base_weekday: Weekday::SAT,
+            style: StyleConfig {
+                today: Style::PLAIN,
+                weekend: Style::PLAIN,
+            },
+            secondary: Some(CalendarKind::Gregorian),
+            ..Default::default()
+        };
+        assert_eq!(column.content.grid.day_cell_width(), 5);
+        assert_eq!(column.content.row_str_width(), 7 * 5);
+        assert_eq!(column.width(), 41);
+        assert_eq!(column.format(&[]), expected);
+
+        // the ordinals of both calendars, 1404-08-14 is the 230th and 2025-11-05 the 309th day
+        column.content.grid.ordinal_mode = true;
+        assert_eq!(column.content.grid.day_cell_width(), 7);
+        let lines = column.format(&[]);
+        assert!(lines[4].contains("230/309"), "{:?}", lines);
+    }
+
+    #[test]
+    fn test_highlight_week_row() {
+        set_colors(Some(true));
+        let gregorian = |y, m, d| Date::Gregorian(civil::Date::constant(y, m, d));
+        let week = |number, anchor| Highlight::Week { number, anchor };
+        let content = |date| ColumnContent {
+            weeknums: Some(WeekNumConfig::Based),
+            weekdays: false,
+            grid: Grid {
+                date,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let today = StyleConfig::DEFAULT.today;
+        let cell = |s: &str| today.paint(s);
+
+        // week 45 of 2025 is 9 to 15 November, the third row
+        let november = content(gregorian(2025, 11, 1));
+        let plain = november.format(&[]);
+        let rows = november.format(&[week(45, gregorian(2025, 11, 9))]);
+        assert_eq!(rows[2][0], cell("45"));
+        assert_eq!(
+            rows[2][1..],
+            [" 9", "10", "11", "12", "13", "14", "15"].map(cell)
+        );
+        for i in [0, 1, 3, 4, 5] {
+            assert_eq!(rows[i], plain[i]);
+        }
+
+        // a week starting in the previous month only brightens the days of this month
+        let highlight = week(43, gregorian(2025, 10, 26));
+        let rows = november.format(&[highlight.clone()]);
+        assert_eq!(rows[0][0], cell("43"));
+        assert_eq!(rows[0][1..7], plain[0][1..7]);
+        assert_eq!(rows[0][7], cell(" 1"));
+        let october = content(gregorian(2025, 10, 1)).format(&[highlight.clone()]);
+        assert_eq!(
+            october[4][1..7],
+            ["26", "27", "28", "29", "30", "31"].map(cell)
+        );
+        assert_eq!(october[4][7], "  ");
+
+        // across the years the dates decide, not the numbers that restart
+        let highlight = week(52, gregorian(2025, 12, 28));
+        let january = content(gregorian(2026, 1, 1));
+        let rows = january.format(&[highlight.clone()]);
+        assert_eq!(rows[0][5..], [" 1", " 2", " 3"].map(cell));
+        assert_eq!(rows[1], january.format(&[])[1]);
+        let highlight = week(1, gregorian(2026, 1, 4));
+        let december = content(gregorian(2025, 12, 1));
+        assert_eq!(december.format(&[highlight.clone()]), december.format(&[]));
+
+        // transposed, each weekday line has a single day of the week
+        let column = Column {
+            content: november,
+            vertical: true,
+            ..Default::default()
+        };
+        let lines = column.format(&[week(45, gregorian(2025, 11, 9))]);
+        for (line, day) in lines[2..].iter().zip(9..=15) {
+            assert!(line.contains(&cell(&format!("{:>2}", day))), "{:?}", line);
+            assert_eq!(line.matches("\x1b[7m").count(), 1, "{:?}", line);
+        }
+        assert!(lines[1].contains(&cell("45")));
+    }
+
+    #[test]
+    fn test_grid_highlight_in_month() {
+        set_colors(Some(true));
+        let grid = Grid {
+            date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
+            ..Default::default()
+        };
+        let plain = grid.format(&[]);
+
+        // another month of the same year, the same month of another year or another calendar
+        for other in [
+            Date::Gregorian(civil::Date::constant(2025, 12, 5)),
+            Date::Gregorian(civil::Date::constant(2024, 11, 5)),
+            Date::Jalali(jelal::Date::from((1404, 8, 14))),
+        ] {
+            assert_eq!(grid.format(&[&other]), plain, "{:?}", other);
+        }
+
+        // Wednesday, 5 November
+        let day = Date::Gregorian(civil::Date::constant(2025, 11, 5));
+        let mut expected = plain;
+        expected[1][3] = grid.style.today.paint(" 5");
+        assert_eq!(grid.format(&[&day]), expected);
+
+        let grid = Grid {
+            ordinal_mode: true,
+            ..grid
+        };
+        let mut expected = grid.format(&[]);
+        expected[1][3] = grid.style.today.paint("309");
+        assert_eq!(grid.format(&[&day]), expected);
+    }
+
+    #[test]
+    fn test_highlight_many_days() {
+        set_colors(Some(true));
+        let content = ColumnContent {
+            grid: Grid {
+                date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let highlights = [3, 12, 27]
+            .map(|i| Highlight::Day(Date::Gregorian(civil::Date::constant(2025, 11, i))));
+        let cells: Vec<_> = content
+            .format(&highlights)
+            .into_iter()
+            .flatten()
+            .filter(|i| i.contains("\x1b[7m"))
+            .collect();
+        let today = |s: &str| content.grid.style.today.paint(s);
+        assert_eq!(cells, [today(" 3"), today("12"), today("27")]);
+    }
+
     #[test]
     fn test_cells_nov_2025_sun_format_ordinal() {
         let nov25_sun = vec![
@@ -850,13 +1800,17 @@ mod tests {
                 weeknums_before_grid: true,
                 weekdays: true,
                 weekdays_before_grid: true,
+                trim_empty_weeks: false,
+                min_weeks: 0,
+                transposed: false,
                 grid: Grid {
                     date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
                     ordinal_mode: true,
-                    base_weekday: Weekday::SUN
+                    base_weekday: Weekday::SUN,
+                    ..Default::default()
                 }
             }
-            .format(None)
+            .format(&[])
         );
 
         assert_eq!(
@@ -866,13 +1820,17 @@ mod tests {
                 weeknums_before_grid: true,
                 weekdays: true,
                 weekdays_before_grid: true,
+                trim_empty_weeks: false,
+                min_weeks: 0,
+                transposed: false,
                 grid: Grid {
                     date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
                     ordinal_mode: true,
                     base_weekday: Weekday::SUN,
+                    ..Default::default()
                 }
             }
-            .format(None)
+            .format(&[])
         );
     }
 
@@ -897,20 +1855,80 @@ mod tests {
                     weeknums_before_grid: true,
                     weekdays: true,
                     weekdays_before_grid: true,
+                    trim_empty_weeks: false,
+                    min_weeks: 0,
+                    transposed: false,
                     grid: Grid {
                         date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
                         ordinal_mode: true,
-                        base_weekday: Weekday::SUN
+                        base_weekday: Weekday::SUN,
+                        ..Default::default()
                     }
                 },
                 delimiter: "|".to_owned(),
                 year_in_header: false,
                 vertical: false,
+                header_format: None,
             }
-            .format(None)
+            .format(&[])
         );
     }
 
+    #[test]
+    fn test_column_nov_2025_sun_transposed() {
+        let nov25_sun = vec![
+            "      November      ".to_owned(),
+            "Su|  | 2| 9|16|23|30".to_owned(),
+            "Mo|  | 3|10|17|24|  ".to_owned(),
+            "Tu|  | 4|11|18|25|  ".to_owned(),
+            "We|  | 5|12|19|26|  ".to_owned(),
+            "Th|  | 6|13|20|27|  ".to_owned(),
+            "Fr|  | 7|14|21|28|  ".to_owned(),
+            "Sa| 1| 8|15|22|29|  ".to_owned(),
+            "  |43|44|45|46|47|48".to_owned(),
+        ];
+
+        let column = Column {
+            content: ColumnContent {
+                weeknums: Some(WeekNumConfig::Based),
+                weeknums_before_grid: false,
+                transposed: true,
+                grid: Grid {
+                    date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
+                    base_weekday: Weekday::SUN,
+                    style: StyleConfig {
+                        today: Style::PLAIN,
+                        weekend: Style::PLAIN,
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            delimiter: "|".to_owned(),
+            ..Default::default()
+        };
+        assert!(column.transposed() && !column.vertical);
+        assert_eq!(column.format(&[]), nov25_sun);
+        assert_eq!(column.width(), 20);
+
+        // the content is transposed on its own
+        assert_eq!(column.content.row_cols(), (WEEK_DAYS + 1, WEEK_COUNT + 1));
+        let content = column.content.format(&[]);
+        assert_eq!(content.len(), WEEK_DAYS + 1);
+        assert_eq!(column.join_cells(content[0].iter()), nov25_sun[1]);
+        assert_eq!(column.join_cells(content[7].iter()), nov25_sun[8]);
+
+        // the weekdays stay in the month, unlike the common ones of the vertical mode
+        let mut layout = Layout::default();
+        layout.base_row.column = column;
+        assert!(!layout.common_weekdays_is_enabled());
+        assert_eq!(layout.rows_left_offset(), 0);
+        assert_eq!(layout.clone().format(), nov25_sun);
+        layout.base_row.column.vertical = true;
+        assert!(layout.common_weekdays_is_enabled());
+        assert_ne!(layout.format(), nov25_sun);
+    }
+
     #[test]
     fn test_column_nov_2025_sun_vertical_ordinal() {
         let nov25_sun = vec![
@@ -933,17 +1951,651 @@ mod tests {
                     weeknums_before_grid: true,
                     weekdays: true,
                     weekdays_before_grid: true,
+                    trim_empty_weeks: false,
+                    min_weeks: 0,
+                    transposed: false,
                     grid: Grid {
                         date: Date::Gregorian(civil::Date::constant(2025, 11, 1)),
                         ordinal_mode: true,
-                        base_weekday: Weekday::SUN
+                        base_weekday: Weekday::SUN,
+                        ..Default::default()
                     }
                 },
                 delimiter: "|".to_owned(),
                 year_in_header: true,
                 vertical: true,
+                header_format: None,
+            }
+            .format(&[])
+        );
+    }
+
+    #[test]
+    fn test_month_info() {
+        let info = MonthInfo::new(
+            &Date::Gregorian(civil::Date::constant(2025, 11, 20)),
+            Weekday::SUN,
+            Some(&WeekNumConfig::Based),
+        );
+        assert_eq!(
+            info,
+            MonthInfo {
+                first_weekday: Weekday::SAT,
+                month_end: 30,
+                first_ordinal: 305,
+                weeknums: Some([43, 44, 45, 46, 47, 48]),
             }
-            .format(None)
         );
+
+        // Esfand of a leap year
+        let info = MonthInfo::new(
+            &Date::Jalali(jelal::Date::from((1403, 12, 10))),
+            Weekday::SAT,
+            None,
+        );
+        assert_eq!(info.month_end, 30);
+        assert_eq!(info.first_ordinal, 337);
+        assert_eq!(info.weeknums, None);
+
+        // ISO weeks of the neighbor years
+        let iso = |date| MonthInfo::new(&date, Weekday::MON, Some(&WeekNumConfig::Iso)).weeknums;
+        assert_eq!(
+            iso(Date::Gregorian(civil::Date::constant(2027, 1, 1))),
+            Some([53, 1, 2, 3, 4, 5])
+        );
+        assert_eq!(
+            iso(Date::Gregorian(civil::Date::constant(2025, 12, 1))),
+            Some([49, 50, 51, 52, 1, 2])
+        );
+        // February 2026 starts on Sunday, in the week of Monday the 2nd when weeks start on Saturday
+        let date = Date::Gregorian(civil::Date::constant(2026, 2, 1));
+        let sat = MonthInfo::new(&date, Weekday::SAT, Some(&WeekNumConfig::Iso)).weeknums;
+        assert_eq!(sat, Some([6, 7, 8, 9, 10, 11]));
+        assert_eq!(iso(date), Some([5, 6, 7, 8, 9, 10]));
+
+        // the week 0 is the last week of the previous year
+        let based = |date, base| MonthInfo::new(&date, base, Some(&WeekNumConfig::Based)).weeknums;
+        assert_eq!(
+            based(
+                Date::Gregorian(civil::Date::constant(2025, 1, 1)),
+                Weekday::SUN
+            ),
+            Some([52, 1, 2, 3, 4, 5])
+        );
+        // and the rows after the last week are the first ones of the next year
+        assert_eq!(
+            based(
+                Date::Gregorian(civil::Date::constant(2025, 12, 1)),
+                Weekday::SUN
+            ),
+            Some([48, 49, 50, 51, 52, 1])
+        );
+        assert_eq!(
+            based(Date::Jalali(jelal::Date::from((1403, 12, 1))), Weekday::SAT),
+            Some([48, 49, 50, 51, 52, 1])
+        );
+    }
+
+    #[test]
+    fn test_layout_write_to() {
+        let mut layout = Layout::default();
+        layout.base_row.column.content.grid.date =
+            Date::Gregorian(civil::Date::constant(2025, 1, 1));
+        layout.base_row.more_columns = 11;
+        layout.next_row_after_column = 3;
+        layout.year_header = true;
+
+        // the same bytes as a `println!` of each line
+        let mut expected = String::new();
+        for line in layout.clone().format() {
+            expected.push_str(&line);
+            expected.push('\n');
+        }
+        let mut v = Vec::new();
+        layout.write_to(&mut v).unwrap();
+        assert_eq!(String::from_utf8(v).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_layout_vertical_weeknum_footer() {
+        let mut layout = Layout::default();
+        let column = &mut layout.base_row.column;
+        column.vertical = true;
+        column.content.grid.date = Date::Gregorian(civil::Date::constant(2025, 11, 1));
+        column.content.weeknums = Some(WeekNumConfig::Based);
+        column.content.weeknums_before_grid = false;
+        // no weekday labels at all
+        column.content.weekdays = false;
+        layout.common_weekday = Some(false);
+
+        // the header, a line for each weekday and the footer
+        let lines = layout.clone().format();
+        assert_eq!(lines.len(), 1 + WEEK_DAYS + 1);
+        assert_eq!(lines[1], "    2  9 16 23 30");
+        assert_eq!(lines[7], " 1  8 15 22 29   ");
+        assert_eq!(lines[8], "43 44 45 46 47 48");
+        assert!(lines.iter().all(|i| ansi_width(i) == 17), "{:?}", lines);
+
+        // the common weekdays leave the prefix of the footer blank
+        layout.common_weekday = Some(true);
+        let lines = layout.clone().format();
+        assert_eq!(lines.len(), 1 + WEEK_DAYS + 1);
+        assert_eq!(lines[1], "Su     2  9 16 23 30");
+        assert_eq!(lines[8], "   43 44 45 46 47 48");
+        assert!(lines.iter().all(|i| ansi_width(i) == 20), "{:?}", lines);
+
+        // a second row of months starts with the same prefixes
+        layout.base_row.more_columns = 1;
+        layout.next_row_after_column = 1;
+        let lines = layout.format();
+        assert_eq!(lines.len(), 2 * (1 + WEEK_DAYS + 1));
+        assert_eq!(lines[9 + 1], "Su     7 14 21 28   ");
+        assert_eq!(lines[9 + 8], "   48 49 50 51 52   ");
+    }
+
+    #[test]
+    fn test_layout_vertical_prefix_per_row() {
+        let mut layout = Layout::default();
+        let column = &mut layout.base_row.column;
+        column.vertical = true;
+        column.content.grid.date = Date::Gregorian(civil::Date::constant(2025, 1, 1));
+        column.content.weeknums = Some(WeekNumConfig::Based);
+        column.content.weeknums_before_grid = false;
+        layout.common_weekday = Some(true);
+        // two rows of three months, each with a footer of week numbers
+        layout.base_row.more_columns = 5;
+        layout.next_row_after_column = 3;
+
+        let lines = layout.format();
+        assert_eq!(lines.len(), 2 * (1 + WEEK_DAYS + 1));
+        for block in lines.chunks(1 + WEEK_DAYS + 1) {
+            assert!(block[0].starts_with("   "), "{:?}", block[0]);
+            assert!(block[0].contains("January") || block[0].contains("April"));
+            assert!(block[1].starts_with("Su "), "{:?}", block[1]);
+            assert!(block[7].starts_with("Sa "), "{:?}", block[7]);
+            assert!(block[8].starts_with("   "), "{:?}", block[8]);
+        }
+    }
+
+    #[test]
+    fn test_carry_over_weeknum() {
+        let carry = |date: &Date, base, config| carry_over_weeknum(date, base, &config);
+
+        // 2021..=2028
+        for (year, sun, mon, iso) in [
+            (2021, 52, 52, 53),
+            (2022, 52, 52, 52),
+            (2023, 1, 52, 52),
+            (2024, 53, 1, 1),
+            (2025, 52, 53, 1),
+            (2026, 52, 52, 1),
+            (2027, 52, 52, 53),
+            (2028, 52, 52, 52),
+        ] {
+            let date = Date::Gregorian(civil::date(year, 1, 1));
+            assert_eq!(
+                carry(&date, Weekday::SUN, WeekNumConfig::Based),
+                sun,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::MON, WeekNumConfig::Based),
+                mon,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::MON, WeekNumConfig::Iso),
+                iso,
+                "{}",
+                year
+            );
+
+            // the first row of January agrees
+            let info = MonthInfo::new(&date, Weekday::SUN, Some(&WeekNumConfig::Based));
+            assert_eq!(info.weeknums.unwrap()[0], sun, "{}", year);
+        }
+
+        // 1400..=1407 (1399 and 1403 are leap years)
+        for (year, sat, sun, mon, iso) in [
+            (1400, 53, 1, 52, 52),
+            (1401, 52, 53, 1, 1),
+            (1402, 52, 52, 53, 1),
+            (1403, 52, 52, 52, 1),
+            (1404, 52, 52, 52, 53),
+            (1405, 1, 52, 52, 52),
+            (1406, 53, 1, 52, 52),
+            (1407, 52, 53, 1, 1),
+        ] {
+            let date = Date::Jalali(jelal::Date::from((year, 1, 1)));
+            assert_eq!(
+                carry(&date, Weekday::SAT, WeekNumConfig::Based),
+                sat,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::SUN, WeekNumConfig::Based),
+                sun,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::MON, WeekNumConfig::Based),
+                mon,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::MON, WeekNumConfig::Iso),
+                iso,
+                "{}",
+                year
+            );
+
+            let info = MonthInfo::new(&date, Weekday::SAT, Some(&WeekNumConfig::Based));
+            assert_eq!(info.weeknums.unwrap()[0], sat, "{}", year);
+        }
+    }
+
+    #[test]
+    fn test_weeknums_first_row_of_year() {
+        // the grid numbers the first row of January by carry_over_weeknum
+        let carry = |date: &Date, base, config| weeknums(&config, date, base)[0];
+
+        // 2021..=2028
+        for (year, sun, mon, iso) in [
+            (2021, 52, 52, 53),
+            (2022, 52, 52, 52),
+            (2023, 1, 52, 52),
+            (2024, 53, 1, 1),
+            (2025, 52, 53, 1),
+            (2026, 52, 52, 1),
+            (2027, 52, 52, 53),
+            (2028, 52, 52, 52),
+        ] {
+            let date = Date::Gregorian(civil::date(year, 1, 1));
+            assert_eq!(
+                carry(&date, Weekday::SUN, WeekNumConfig::Based),
+                sun,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::MON, WeekNumConfig::Based),
+                mon,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::MON, WeekNumConfig::Iso),
+                iso,
+                "{}",
+                year
+            );
+        }
+
+        // 1400..=1407 (1399 and 1403 are leap years)
+        for (year, sat, sun, mon, iso) in [
+            (1400, 53, 1, 52, 52),
+            (1401, 52, 53, 1, 1),
+            (1402, 52, 52, 53, 1),
+            (1403, 52, 52, 52, 1),
+            (1404, 52, 52, 52, 53),
+            (1405, 1, 52, 52, 52),
+            (1406, 53, 1, 52, 52),
+            (1407, 52, 53, 1, 1),
+        ] {
+            let date = Date::Jalali(jelal::Date::from((year, 1, 1)));
+            assert_eq!(
+                carry(&date, Weekday::SAT, WeekNumConfig::Based),
+                sat,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::SUN, WeekNumConfig::Based),
+                sun,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::MON, WeekNumConfig::Based),
+                mon,
+                "{}",
+                year
+            );
+            assert_eq!(
+                carry(&date, Weekday::MON, WeekNumConfig::Iso),
+                iso,
+                "{}",
+                year
+            );
+        }
+    }
+
+    #[test]
+    fn test_grid_invariants() {
+        let months = (1390..=1410)
+            .flat_map(|y| (1..=12).map(move |m| Date::Jalali(jelal::Date::from((y, m, 1)))))
+            .chain((2010..=2030).flat_map(|y| {
+                (1..=12).map(move |m| Date::Gregorian(civil::Date::constant(y, m, 1)))
+            }));
+
+        for date in months {
+            for base in 0..WEEK_DAYS {
+                let grid = Grid {
+                    date: date.clone(),
+                    base_weekday: Weekday::SUN.forward(base),
+                    ..Default::default()
+                };
+                let info = grid.month_info();
+                let cells = grid.new_grid_with(&info);
+
+                // every day once and in order
+                let days: Vec<_> = cells
+                    .iter()
+                    .flatten()
+                    .filter(|i| **i != 0)
+                    .copied()
+                    .collect();
+                let expected: Vec<_> = (1..=info.month_end as UOrdinal).collect();
+                assert_eq!(days, expected, "{:?}", grid);
+
+                for (row, week) in cells.iter().enumerate() {
+                    for (col, &day) in week.iter().enumerate() {
+                        let cell = grid.date_of_cell_with(&info, row, col);
+                        if day == 0 {
+                            assert_eq!(cell, None);
+                            continue;
+                        }
+                        let cell = cell.unwrap();
+                        assert_eq!(cell.day() as UOrdinal, day);
+                        assert_eq!(cell.weekday(), grid.base_weekday.forward(col));
+                    }
+                }
+            }
+        }
+
+        assert_eq!(Grid::default().date_of_cell(6, 0), None);
+        assert_eq!(Grid::default().date_of_cell(0, 7), None);
+    }
+
+    #[test]
+    fn test_full_year_render() {
+        let mut layout = Layout::default();
+        layout.base_row.column.content.grid.date = Date::Jalali(jelal::Date::from((1404, 1, 1)));
+        layout.base_row.column.content.weeknums = Some(WeekNumConfig::Based);
+        layout.base_row.more_columns = 11;
+        layout.next_row_after_column = 3;
+
+        // each month once and in order, in 4 rows of the same width
+        let lines = layout.format();
+        let names: Vec<_> = headers(&lines).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, jcal::JALALI_MONTHS);
+        assert_eq!(lines.len(), 4 * (1 + 1 + WEEK_COUNT));
+        let width = ansi_width(&lines[0]);
+        assert!(lines.iter().all(|i| ansi_width(i) == width), "{:?}", lines);
+
+        // A loose bound to catch regressions to per cell date constructions (see MonthInfo), a
+        // year with week numbers renders in a few milliseconds even in debug builds. The measure
+        // of the whole `cal -J -y` is `bench_full_year_jalali` of the binary.
+        let start = std::time::Instant::now();
+        for _ in 0..10 {
+            assert_eq!(layout.clone().format(), lines);
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed < std::time::Duration::from_secs(5), "{:?}", elapsed);
+    }
+
+    /// Collect the `(month name, year)` of column headers in the order they are printed.
+    fn headers(lines: &[String]) -> Vec<(String, Option<String>)> {
+        let mut v = Vec::new();
+        for line in lines {
+            let mut words = line.split_whitespace().peekable();
+            while let Some(word) = words.next() {
+                if jcal::GREGORIAN_MONTHS.contains(&word) || jcal::JALALI_MONTHS.contains(&word) {
+                    let year = words.next_if(|i| i.parse::<IYear>().is_ok());
+                    v.push((word.to_owned(), year.map(|i| i.to_owned())));
+                }
+            }
+        }
+        v
+    }
+
+    #[test]
+    fn test_layout_multi_row_headers() {
+        for (months, columns) in [(2, 5), (5, 3), (12, 5), (13, 12)] {
+            let start = Date::Gregorian(civil::Date::constant(2025, 11, 1));
+            let mut layout = Layout::default();
+            layout.base_row.column.content.grid.date = start.clone();
+            layout.base_row.more_columns = months - 1;
+            layout.next_row_after_column = columns;
+            let lines = layout.format();
+
+            // the year is shown only if the months cross into 2026 and only where it changes
+            let crosses = months > 2;
+            let mut expected = Vec::new();
+            let mut date = start.clone();
+            for i in 0..months {
+                let year =
+                    (crosses && (i == 0 || date.month() == 1)).then(|| date.year().to_string());
+                expected.push((date.month_name().to_owned(), year));
+                date.set_saturating_months_offset(1);
+            }
+            assert_eq!(
+                headers(&lines),
+                expected,
+                "{} months in {}",
+                months,
+                columns
+            );
+
+            // each row is a header and 6 weeks plus the weekday names
+            let rows = months.div_ceil(columns);
+            assert_eq!(
+                lines.len(),
+                rows * (1 + 1 + WEEK_COUNT),
+                "{} months",
+                months
+            );
+        }
+    }
+
+    #[test]
+    fn test_layout_year_only_when_crossing() {
+        // January to December do not cross a year boundary
+        let mut layout = Layout::default();
+        layout.base_row.column.content.grid.date =
+            Date::Gregorian(civil::Date::constant(2025, 1, 1));
+        layout.base_row.more_columns = 11;
+        layout.next_row_after_column = 3;
+        let headers = headers(&layout.format());
+        assert_eq!(headers.len(), 12);
+        assert!(headers.iter().all(|(_, year)| year.is_none()));
+    }
+
+    #[test]
+    fn test_layout_year_on_change() {
+        let headers_from = |start: Date| {
+            let mut layout = Layout::default();
+            layout.base_row.column.content.grid.date = start;
+            layout.base_row.more_columns = 11;
+            layout.next_row_after_column = 3;
+            headers(&layout.format())
+        };
+        let expected = |names: &[&str], years: &[(usize, &str)]| {
+            names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let year = years.iter().find(|(j, _)| *j == i);
+                    ((*name).to_owned(), year.map(|(_, y)| (*y).to_owned()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let gregorian = |from: usize| {
+            let mut v = jcal::GREGORIAN_MONTHS.to_vec();
+            v.rotate_left(from);
+            v
+        };
+        assert_eq!(
+            headers_from(Date::Gregorian(civil::Date::constant(2025, 1, 1))),
+            expected(&gregorian(0), &[])
+        );
+        assert_eq!(
+            headers_from(Date::Gregorian(civil::Date::constant(2025, 2, 1))),
+            expected(&gregorian(1), &[(0, "2025"), (11, "2026")])
+        );
+
+        let mut jalali = jcal::JALALI_MONTHS.to_vec();
+        jalali.rotate_left(11);
+        assert_eq!(
+            headers_from(Date::Jalali(jelal::Date::from((1403, 12, 1)))),
+            expected(&jalali, &[(0, "1403"), (1, "1404")])
+        );
+
+        // an explicit year is always written
+        let mut layout = Layout::default();
+        layout.base_row.column.content.grid.date =
+            Date::Gregorian(civil::Date::constant(2025, 2, 1));
+        layout.base_row.column.year_in_header = true;
+        layout.base_row.more_columns = 11;
+        assert!(headers(&layout.format()).iter().all(|(_, y)| y.is_some()));
+    }
+
+    #[test]
+    fn test_layout_year_on_change_golden() {
+        set_colors(Some(false));
+        let render = |start: Date| {
+            let mut layout = Layout::default();
+            layout.base_row.column.content.grid.date = start;
+            layout.base_row.more_columns = 11;
+            layout.next_row_after_column = 3;
+            layout.format().join("\n") + "\n"
+        };
+        assert_eq!(
+            render(Date::Gregorian(civil::Date::constant(2025, 1, 1))),
+            include_str!("../testdata/year_on_change_january.txt")
+        );
+        assert_eq!(
+            render(Date::Gregorian(civil::Date::constant(2025, 2, 1))),
+            include_str!("../testdata/year_on_change_february.txt")
+        );
+        assert_eq!(
+            render(Date::Jalali(jelal::Date::from((1403, 12, 1)))),
+            include_str!("../testdata/year_on_change_esfand.txt")
+        );
+    }
+
+    #[test]
+    fn test_layout_year_header() {
+        let mut layout = Layout::default();
+        layout.base_row.column.content.grid.date =
+            Date::Gregorian(civil::Date::constant(2025, 1, 1));
+        layout.base_row.more_columns = 11;
+        layout.next_row_after_column = 3;
+        let without = layout.clone().format();
+        layout.year_header = true;
+
+        // 3 months in a row, the year is centered in the width of a full row
+        let mut row = layout.base_row.clone();
+        row.more_columns = 2;
+        let width = row.width();
+        let lines = layout.clone().format();
+        assert_eq!(lines[0], Aligner::SPACE.center("2025", width));
+        assert_eq!(ansi_width(&lines[0]), width);
+        assert_eq!(lines[0].trim(), "2025");
+        assert_eq!(lines[1..], without[..]);
+
+        // the common weekdays of the vertical mode are a part of the width
+        layout.base_row.column.vertical = true;
+        layout.common_weekday = Some(true);
+        let offset = layout.rows_left_offset();
+        let mut row = layout.base_row.clone();
+        row.more_columns = 2;
+        let lines = layout.format();
+        assert_eq!(
+            lines[0],
+            Aligner::SPACE.center("2025", offset + row.width())
+        );
+    }
+
+    #[test]
+    fn test_layout_year_header_per_year() {
+        let mut layout = Layout::default();
+        layout.base_row.column.content.grid.date =
+            Date::Gregorian(civil::Date::constant(2025, 1, 1));
+        layout.base_row.more_columns = 23;
+        // 12 is not a multiple of 5 so the last row of 2025 is cut short at December
+        layout.next_row_after_column = 5;
+        layout.year_header = true;
+
+        let lines = layout.clone().format();
+        let at = lines.iter().position(|i| i.trim() == "2026").unwrap();
+        assert_eq!(lines[0].trim(), "2025");
+        assert_eq!(lines[at - 1], "");
+        assert!(lines[at + 1].contains("January"));
+        assert!(!lines[at + 1].contains("2026"));
+        assert!(lines[at - 2..at].iter().all(|i| !i.contains("January")));
+
+        let mut row = layout.base_row.clone();
+        row.more_columns = 4;
+        assert_eq!(ansi_width(&lines[at]), row.width());
+    }
+
+    #[test]
+    fn test_column_header_format() {
+        let mut column = Column::default();
+        column.content.grid.date = Date::Gregorian(civil::date(2025, 11, 1));
+        let header = |column: &Column| column.format(&[])[0].trim().to_owned();
+
+        // the year is added only where it is asked for and missing from the format
+        column.header_format = Some("%b".to_owned());
+        assert_eq!(header(&column), "Nov");
+        column.year_in_header = true;
+        assert_eq!(header(&column), "Nov 2025");
+        column.header_format = Some("%b '%y".to_owned());
+        assert_eq!(header(&column), "Nov '25");
+        column.header_format = Some("%B %EN".to_owned());
+        assert_eq!(header(&column), "November 1404");
+
+        // too wide for the 20 columns of the grid
+        column.header_format = Some("%B %B %B".to_owned());
+        assert_eq!(header(&column), "November 2025");
+        column.delimiter = String::new(); // 14 columns
+        assert_eq!(header(&column), "November 2025");
+        column.year_in_header = false;
+        assert_eq!(header(&column), "November");
+        assert!(column.format(&[]).iter().all(|i| ansi_width(i) == 14));
+    }
+
+    #[test]
+    fn test_layout_year_header_format() {
+        let mut layout = Layout {
+            year_header: true,
+            next_row_after_column: 3,
+            ..Default::default()
+        };
+        layout.base_row.column.content.grid.date = Date::Jalali(jelal::Date::from((1404, 1, 1)));
+        layout.base_row.more_columns = 11;
+        let width = 3 * layout.base_row.column.width() + 2 * 3;
+
+        let lines = layout.clone().format();
+        assert_eq!(lines[0], Aligner::SPACE.center("1404", width));
+
+        layout.year_header_format = Some("%Y / %EG".to_owned());
+        let custom = layout.clone().format();
+        assert_eq!(custom[0], Aligner::SPACE.center("1404 / 2025–2026", width));
+        assert_eq!(ansi_width(&custom[0]), width);
+        assert_eq!(custom[1..], lines[1..]);
+
+        // the displayed calendar decides `%Y`
+        layout.base_row.column.content.grid.date =
+            Date::Gregorian(civil::Date::constant(2025, 1, 1));
+        layout.year_header_format = Some("%Y (%y)".to_owned());
+        assert_eq!(layout.format()[0].trim(), "2025 (25)");
     }
 }
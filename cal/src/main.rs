@@ -1,20 +1,330 @@
-use jcal::clap_helper::Parse;
+use std::io::{BufWriter, IsTerminal, Read, Write};
 
-use crate::arg_parser::{Args, ColorMode};
+use clap::error::ErrorKind;
+use jcal::{
+    clap_helper::{CommandFactoryExt, Parse},
+    date::{CommonDate, Date},
+    names::NameProvider,
+};
+use jelal::{UMonth, UMonthDay};
+
+use crate::{
+    arg_parser::{Args, OutputFormat, lint_args, terminal_width},
+    interactive::{Action, RawMode},
+    layout::Layout,
+    string::{Aligner, set_colors},
+};
 
 mod arg_parser;
+mod events;
+mod export;
+mod interactive;
 mod layout;
+mod moon;
 mod string;
 
+/// Move the cursor home and clear the screen.
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// Render the whole output at once so a partially drawn frame is never seen.
+fn frame(layout: Layout, clear: bool) -> Vec<u8> {
+    let mut v = Vec::new();
+    if clear {
+        v.extend_from_slice(CLEAR_SCREEN.as_bytes());
+    }
+    layout
+        .write_to(&mut v)
+        .expect("writing to a vec cannot fail");
+    v
+}
+
+/// The weekday the given month and day falls on in each of `years` years from the year of `from`.
+///
+/// Years without that day (e.g. Esfand 30 or February 29 out of leap years) get a note instead.
+fn weekday_of_table(from: &Date, month: UMonth, day: UMonthDay, years: usize) -> Vec<String> {
+    let names = NameProvider::DEFAULT;
+    let month_name = names.month_name(from.kind(), month);
+    let mut rows = Vec::with_capacity(years);
+    let mut date = from.clone();
+    date.set_saturating_day(1);
+    for year in (from.year()..).take(years) {
+        if date.try_set_year(year).is_err() {
+            break; // out of the range of the calendar
+        }
+        date.set_saturating_month(month);
+        let cell = if day <= date.month_end_day() {
+            date.set_saturating_day(day);
+            let v = names.weekday(date.weekday()).to_owned();
+            date.set_saturating_day(1);
+            v
+        } else {
+            format!("({} {} does not exist)", month_name, day)
+        };
+        rows.push((year.to_string(), cell));
+    }
+
+    let width = rows.iter().map(|(y, _)| y.len()).max().unwrap_or(0);
+    let mut v = vec![format!("{} {}", month_name, day)];
+    v.extend(
+        rows.into_iter()
+            .map(|(year, cell)| format!("{}  {}", Aligner::SPACE.right(&year, width), cell)),
+    );
+    v
+}
+
+/// The whole output of a single run, the calendar (in the `--output` format) or the `--weekday-of`
+/// table, painted as `--color` decides for an output that is a terminal or not.
+fn render(config: &Args, is_terminal: bool) -> Vec<u8> {
+    // pipes and files get no escapes unless asked for
+    set_colors(config.color.resolve(is_terminal));
+
+    let Some((month, day)) = config.weekday_of else {
+        return match config.output {
+            OutputFormat::Text => frame(config.layout.clone(), config.clear),
+            OutputFormat::Json => format!("{}\n", config.layout.to_json()).into_bytes(),
+            OutputFormat::Html => format!("{}\n", config.layout.to_html()).into_bytes(),
+            OutputFormat::Csv => format!("{}\n", config.layout.to_delimited(',')).into_bytes(),
+            OutputFormat::Tsv => format!("{}\n", config.layout.to_delimited('\t')).into_bytes(),
+        };
+    };
+    let mut v = Vec::new();
+    for line in weekday_of_table(&config.now, month, day, config.years) {
+        writeln!(v, "{}", line).expect("writing to a vec cannot fail");
+    }
+    v
+}
+
+/// Print the calendar once.
+fn run(config: &Args) -> std::io::Result<()> {
+    // a locked stdout is line buffered, this flushes once (or when full)
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    stdout.write_all(&render(config, std::io::stdout().is_terminal()))?;
+    stdout.flush()
+}
+
+/// Print the calendar again after each key until quit (see [`interactive`]).
+fn interact(config: &mut Args) -> std::io::Result<()> {
+    let today = Date::Gregorian(jiff::Zoned::now().date()).convert_to(config.now.kind());
+    let mut anchor = config.now.clone();
+    let _raw_mode = RawMode::enter()?;
+    let mut stdin = std::io::stdin().lock();
+    let mut key = [0; 8];
+    loop {
+        run(&config)?;
+        // an escape sequence of a key arrives in a single read
+        let read = stdin.read(&mut key)?;
+        if read == 0 {
+            return Ok(()); // the terminal is gone
+        }
+        let Some(action) = Action::from_key(&key[..read]) else {
+            continue;
+        };
+        if !action.step(&mut anchor, &today) {
+            return Ok(());
+        }
+        config.set_anchor(anchor.clone());
+    }
+}
+
 fn main() {
-    let config = Args::parse();
+    let mut config = Args::parse();
+    config.set_output_terminal(std::io::stdout().is_terminal());
 
-    match config.color {
-        ColorMode::Always => colored::control::set_override(true),
-        ColorMode::Never => colored::control::set_override(false),
-        ColorMode::Auto => colored::control::unset_override(),
+    for lint in lint_args(&config) {
+        if config.strict {
+            Args::error(ErrorKind::ArgumentConflict, lint).exit();
+        }
+        eprintln!("warning: {}", lint);
     }
 
-    // TODO fix this, get an iterator and print each line
-    config.layout.print()
+    if config.interactive && !std::io::stdin().is_terminal() {
+        Args::error(ErrorKind::InvalidValue, "`--interactive` needs a terminal").exit();
+    }
+
+    loop {
+        let result = if config.interactive {
+            interact(&mut config)
+        } else {
+            run(&config)
+        };
+        if let Err(e) = result {
+            // e.g. the reader of a pipe is gone
+            if e.kind() == std::io::ErrorKind::BrokenPipe {
+                return;
+            }
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+
+        // SIGINT is left to its default since nothing is changed on the terminal to be restored
+        let Some(secs) = config.watch else {
+            return;
+        };
+        std::thread::sleep(std::time::Duration::from_secs(secs));
+        // only the clock (for the highlight) and the terminal width change between the prints
+        config.refresh(
+            Date::Gregorian(jiff::Zoned::now().date()),
+            terminal_width(std::env::var("COLUMNS").ok().as_deref()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use clap::{CommandFactory, FromArgMatches};
+
+    #[test]
+    fn test_examples() {
+        for (example, _) in Args::EXAMPLES {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(example.split_whitespace())
+                .unwrap_or_else(|e| panic!("`{}`: {}", example, e));
+            let mut config = Args::default();
+            config
+                .update_from_arg_matches(&matches)
+                .unwrap_or_else(|e| panic!("`{}`: {}", example, e));
+            assert!(lint_args(&config).is_empty(), "`{}`", example);
+            assert!(render(&config, false).ends_with(b"\n"), "`{}`", example);
+        }
+
+        let help = Args::examples_help();
+        assert!(help.starts_with("Examples:\n  cal\n"));
+        assert!(help.contains("  cal -J -y 1404\n      the whole Jalali year 1404\n"));
+    }
+
+    /// The whole `cal -J -y` into a sink, bounded loosely so it runs with the tests. For a
+    /// measure: `cargo test --release -p jcal-cal bench -- --nocapture`.
+    #[test]
+    fn bench_full_year_jalali() {
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["-J", "-y", "1404", "--color", "always"]);
+        let mut config = Args::default();
+        config.update_from_arg_matches(&matches).unwrap();
+
+        const RUNS: u32 = 100;
+        let mut sink = std::io::sink();
+        let start = std::time::Instant::now();
+        for _ in 0..RUNS {
+            sink.write_all(&render(&config, true)).unwrap();
+        }
+        let elapsed = start.elapsed();
+        eprintln!("cal -J -y: {:?} per render", elapsed / RUNS);
+        // a few milliseconds in release, far less than this even in debug builds
+        assert!(
+            elapsed < std::time::Duration::from_secs(10),
+            "{:?}",
+            elapsed
+        );
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let matches = Args::command().no_binary_name(true).get_matches_from([
+            "--no-highlight",
+            "--color",
+            "never",
+            "-y",
+        ]);
+        let mut config = Args::default();
+        config.update_from_arg_matches(&matches).unwrap();
+        // the choice of the last render on this thread does not leak in
+        set_colors(Some(true));
+        let text = render(&config, true);
+        assert!(!text.contains(&b'\x1b'));
+        assert!(config.layout.highlights.is_empty());
+
+        // a pipe gets no escapes, unless asked for
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["-y"]);
+        let mut config = Args::default();
+        config.update_from_arg_matches(&matches).unwrap();
+        set_colors(Some(true));
+        assert!(!render(&config, false).contains(&b'\x1b'));
+        config.color = jcal::config::ColorMode::Always;
+        assert!(render(&config, false).contains(&b'\x1b'));
+    }
+
+    #[test]
+    fn test_help_headings() {
+        let command = Args::command();
+        for arg in command.get_arguments() {
+            let id = arg.get_id().as_str();
+            // the positionals and the flags of every binary (version) keep the default sections
+            if arg.is_positional()
+                || arg
+                    .get_long()
+                    .is_some_and(|i| i == "version" || i == "verbose")
+            {
+                continue;
+            }
+            assert!(arg.get_help_heading().is_some(), "--{} has no section", id);
+        }
+        let help = command.clone().render_help().to_string();
+        for heading in [
+            Args::CALENDAR_HEADING,
+            Args::RANGE_HEADING,
+            Args::LAYOUT_HEADING,
+            Args::WEEK_NUMBERS_HEADING,
+            Args::HIGHLIGHTING_HEADING,
+            Args::OUTPUT_HEADING,
+        ] {
+            assert!(help.contains(&format!("{}:", heading)), "{}", heading);
+        }
+    }
+
+    #[test]
+    fn test_frame_clear_prefix() {
+        let layout = Layout::default();
+        let plain = frame(layout.clone(), false);
+        let cleared = frame(layout, true);
+        assert_eq!(&cleared[..7], b"\x1b[H\x1b[2J");
+        assert_eq!(cleared[7..], plain[..]);
+        assert!(plain.ends_with(b"\n"));
+    }
+
+    #[test]
+    fn test_weekday_of_table() {
+        // 1403 is a leap year, 1404 to 1407 are not
+        let jalali = Date::Jalali(jelal::Date::from((1403, 7, 1)));
+        assert_eq!(
+            weekday_of_table(&jalali, 12, 30, 5),
+            [
+                "Esfand 30",
+                "1403  Thursday",
+                "1404  (Esfand 30 does not exist)",
+                "1405  (Esfand 30 does not exist)",
+                "1406  (Esfand 30 does not exist)",
+                "1407  (Esfand 30 does not exist)",
+            ]
+        );
+        assert_eq!(
+            weekday_of_table(&jalali, 1, 1, 3),
+            [
+                "Farvardin 1",
+                "1403  Wednesday",
+                "1404  Friday",
+                "1405  Saturday"
+            ]
+        );
+
+        let gregorian = Date::Gregorian(jiff::civil::date(2023, 11, 7));
+        assert_eq!(
+            weekday_of_table(&gregorian, 2, 29, 3),
+            [
+                "February 29",
+                "2023  (February 29 does not exist)",
+                "2024  Thursday",
+                "2025  (February 29 does not exist)",
+            ]
+        );
+        assert_eq!(
+            weekday_of_table(&gregorian, 12, 25, 2),
+            ["December 25", "2023  Monday", "2024  Wednesday"]
+        );
+    }
 }
@@ -0,0 +1,115 @@
+//! Phases of the moon for the day markers (`--moon`).
+//!
+//! The age of the moon is measured from a known new moon with the mean synodic month (the same
+//! idea as Conway's mental algorithm, with the Meeus epoch). The true moon runs up to about 14 hours
+//! ahead or behind the mean one so the marked days are only accurate to ±1 day.
+
+use jiff::civil;
+
+/// Mean length of a lunation in days.
+const SYNODIC_MONTH: f64 = 29.530588853;
+
+/// Julian day of the new moon of 2000-01-06 18:14 UT.
+const EPOCH_NEW_MOON: f64 = 2451550.26;
+
+/// The phase of the moon of a whole day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    New,
+    Waxing,
+    Full,
+    Waning,
+}
+
+impl Phase {
+    /// The cell marker of the phase if it is marked at all.
+    pub fn marker(self) -> Option<&'static str> {
+        match self {
+            Self::New => Some("●"),
+            Self::Full => Some("○"),
+            Self::Waxing | Self::Waning => None,
+        }
+    }
+}
+
+/// The Julian day number of a proleptic Gregorian date (the Julian day at its noon).
+fn julian_day_number(date: civil::Date) -> i64 {
+    let (year, month, day) = (
+        i64::from(date.year()),
+        i64::from(date.month()),
+        i64::from(date.day()),
+    );
+    let a = (14 - month) / 12;
+    let y = year + 4800 - a;
+    let m = month + 12 * a - 3;
+    day + (153 * m + 2) / 5 + 365 * y + y / 4 - y / 100 + y / 400 - 32045
+}
+
+/// The phase of the moon at the noon (UT) of the given day.
+///
+/// A day is [`Phase::New`] or [`Phase::Full`] if the mean new or full moon falls in the 24 hours
+/// around its noon, so exactly one day of each lunation gets each of the two.
+pub fn moon_phase(date: civil::Date) -> Phase {
+    let age = (julian_day_number(date) as f64 - EPOCH_NEW_MOON).rem_euclid(SYNODIC_MONTH);
+    let half = SYNODIC_MONTH / 2.0;
+    if age < 0.5 || age >= SYNODIC_MONTH - 0.5 {
+        Phase::New
+    } else if (age - half).abs() < 0.5 {
+        Phase::Full
+    } else if age < half {
+        Phase::Waxing
+    } else {
+        Phase::Waning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// True if the phase is seen within a day of the given date.
+    fn near(date: civil::Date, phase: Phase) -> bool {
+        (-1..=1).any(|i| moon_phase(date.saturating_add(jiff::Span::new().days(i))) == phase)
+    }
+
+    #[test]
+    fn test_julian_day_number() {
+        assert_eq!(julian_day_number(civil::date(2000, 1, 1)), 2451545);
+        assert_eq!(julian_day_number(civil::date(1858, 11, 17)), 2400001);
+    }
+
+    #[test]
+    fn test_known_phases() {
+        for date in [
+            civil::date(2000, 1, 21),
+            civil::date(2024, 1, 25),
+            civil::date(2025, 10, 7),
+            civil::date(2025, 11, 5),
+            civil::date(2025, 12, 4),
+        ] {
+            assert!(near(date, Phase::Full), "{}", date);
+        }
+        for date in [
+            civil::date(2000, 1, 6),
+            civil::date(2024, 1, 11),
+            civil::date(2025, 10, 21),
+            civil::date(2025, 11, 20),
+        ] {
+            assert!(near(date, Phase::New), "{}", date);
+        }
+        assert_eq!(moon_phase(civil::date(2025, 11, 5)), Phase::Full);
+        assert_eq!(moon_phase(civil::date(2025, 11, 12)), Phase::Waning);
+        assert_eq!(moon_phase(civil::date(2025, 11, 27)), Phase::Waxing);
+    }
+
+    #[test]
+    fn test_one_marker_per_lunation() {
+        let start = civil::date(2025, 1, 1);
+        let markers = (0..365)
+            .map(|i| moon_phase(start.saturating_add(jiff::Span::new().days(i))))
+            .filter(|i| i.marker().is_some())
+            .count();
+        // 12.36 lunations in a year, each with a new and a full moon
+        assert!((24..=26).contains(&markers), "{}", markers);
+    }
+}
@@ -2,12 +2,228 @@
 
 #![allow(dead_code)]
 
-use std::cmp::Ordering;
+use std::{cell::Cell, cmp::Ordering};
 
-/// Highlights a value in color depending on the color configuration.
+use jcal::clap_helper::StaticMap;
+
+thread_local! {
+    /// Whether to paint on this thread, before the configuration of [`colored`].
+    static COLORS: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Paint or not on this thread regardless of the process wide configuration of [`colored`]
+/// (`None` leaves it to that), so calendars rendered in parallel cannot change each other.
+pub fn set_colors(v: Option<bool>) {
+    COLORS.set(v);
+}
+
+/// If [`Style::paint`] applies the attributes on this thread (see [`set_colors`]).
+pub fn colors() -> bool {
+    COLORS
+        .get()
+        .unwrap_or_else(|| colored::control::SHOULD_COLORIZE.should_colorize())
+}
+
+/// Highlights a value in color depending on the color configuration (as the default preset).
 pub fn highlight(s: &str) -> String {
-    use colored::Colorize;
-    s.reversed().to_string()
+    StyleConfig::DEFAULT.today.paint(s)
+}
+
+/// Dims a value (the days of the neighbor months) depending on the color configuration.
+pub fn dim(s: &str) -> String {
+    Style {
+        dim: true,
+        ..Style::PLAIN
+    }
+    .paint(s)
+}
+
+/// Attributes of a styled element.
+///
+/// Painting respects [`colors`] so `--color never` is always plain.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Style {
+    pub bold: bool,
+    pub dim: bool,
+    pub underline: bool,
+    pub reverse: bool,
+    /// A foreground color from the 256-color palette.
+    pub foreground: Option<u8>,
+}
+
+impl Style {
+    pub const PLAIN: Self = Self {
+        bold: false,
+        dim: false,
+        underline: false,
+        reverse: false,
+        foreground: None,
+    };
+
+    /// Red, for the weekend of `--weekend` when the preset leaves it plain. Merged under the style
+    /// of today so a highlighted weekend day is both reversed and red.
+    pub const WEEKEND: Self = Self {
+        foreground: Some(160),
+        ..Self::PLAIN
+    };
+
+    /// Parse a comma separated list of attributes or 256-color indices (`bold,underline,33`).
+    ///
+    /// `plain` clears every attribute given before it.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut style = Self::PLAIN;
+        for attribute in s.split(',').map(str::trim) {
+            match attribute {
+                "plain" => style = Self::PLAIN,
+                "bold" => style.bold = true,
+                "dim" => style.dim = true,
+                "underline" => style.underline = true,
+                "reverse" => style.reverse = true,
+                v => {
+                    style.foreground = Some(v.parse().map_err(|_| {
+                        format!("unknown style attribute `{}` (nor a 256-color index)", v)
+                    })?)
+                }
+            }
+        }
+        Ok(style)
+    }
+
+    /// Put the attributes of the other on top of this.
+    pub fn merge(self, over: Self) -> Self {
+        Self {
+            bold: self.bold || over.bold,
+            dim: self.dim || over.dim,
+            underline: self.underline || over.underline,
+            reverse: self.reverse || over.reverse,
+            foreground: over.foreground.or(self.foreground),
+        }
+    }
+
+    /// Apply the attributes to the given string if colors are enabled.
+    pub fn paint(&self, s: &str) -> String {
+        let mut codes = Vec::new();
+        for (set, code) in [
+            (self.bold, "1"),
+            (self.dim, "2"),
+            (self.underline, "4"),
+            (self.reverse, "7"),
+        ] {
+            if set {
+                codes.push(code.to_owned());
+            }
+        }
+        if let Some(v) = self.foreground {
+            codes.push(format!("38;5;{}", v));
+        }
+
+        if codes.is_empty() || !colors() {
+            s.to_owned()
+        } else {
+            format!("\x1b[{}m{}\x1b[0m", codes.join(";"), s)
+        }
+    }
+}
+
+/// Styles of each calendar element.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StyleConfig {
+    /// The highlighted day (today unless set otherwise) or week number.
+    pub today: Style,
+    /// The days of the weekend.
+    pub weekend: Style,
+}
+
+impl StyleConfig {
+    /// Reverse today and leave the rest plain.
+    pub const DEFAULT: Self = Self {
+        today: Style {
+            reverse: true,
+            ..Style::PLAIN
+        },
+        weekend: Style::PLAIN,
+    };
+
+    /// Avoid reverse video which some terminals or eyes do not handle well.
+    pub const HIGH_CONTRAST: Self = Self {
+        today: Style {
+            bold: true,
+            underline: true,
+            ..Style::PLAIN
+        },
+        weekend: Style {
+            bold: true,
+            ..Style::PLAIN
+        },
+    };
+
+    /// Only intensity, no colors.
+    pub const MONO: Self = Self {
+        today: Style {
+            bold: true,
+            ..Style::PLAIN
+        },
+        weekend: Style {
+            dim: true,
+            ..Style::PLAIN
+        },
+    };
+
+    /// Solarized blue for today and red for the weekend.
+    pub const SOLARIZED: Self = Self {
+        today: Style {
+            reverse: true,
+            foreground: Some(33),
+            ..Style::PLAIN
+        },
+        weekend: Style {
+            foreground: Some(160),
+            ..Style::PLAIN
+        },
+    };
+
+    pub const PARSER_DEFAULT: &'static str = "default";
+
+    pub const PRESETS: StaticMap<&'static Self> = StaticMap(&[
+        (Self::PARSER_DEFAULT, &Self::DEFAULT),
+        ("high-contrast", &Self::HIGH_CONTRAST),
+        ("mono", &Self::MONO),
+        ("solarized", &Self::SOLARIZED),
+    ]);
+
+    /// Elements that can be styled on their own.
+    pub const ELEMENTS: &[&str] = &["today", "weekend"];
+
+    /// Parse an `ELEMENT=ATTRIBUTES` override (see [`Style::parse`]).
+    pub fn parse_override(s: &str) -> Result<(String, Style), String> {
+        let Some((element, style)) = s.split_once('=') else {
+            return Err("expected `ELEMENT=ATTRIBUTES`".to_owned());
+        };
+        let element = element.trim();
+        if !Self::ELEMENTS.contains(&element) {
+            return Err(format!(
+                "unknown element `{}` (expected one of: {})",
+                element,
+                Self::ELEMENTS.join(", ")
+            ));
+        }
+        Ok((element.to_owned(), Style::parse(style)?))
+    }
+
+    /// Replace the style of the element (one of [`Self::ELEMENTS`]).
+    pub fn set(&mut self, element: &str, style: Style) {
+        match element {
+            "today" => self.today = style,
+            "weekend" => self.weekend = style,
+            _ => {}
+        }
+    }
+}
+
+impl Default for StyleConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 /// Calculate the "width" so it corresponds to columns in terminal.
@@ -198,6 +414,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_style_parse() {
+        assert_eq!(
+            Style::parse("bold, underline,33"),
+            Ok(Style {
+                bold: true,
+                underline: true,
+                foreground: Some(33),
+                ..Style::PLAIN
+            })
+        );
+        assert_eq!(Style::parse("reverse,plain"), Ok(Style::PLAIN));
+        assert!(Style::parse("blink").is_err());
+        assert!(Style::parse("256").is_err());
+
+        assert!(StyleConfig::parse_override("today=bold").is_ok());
+        assert!(StyleConfig::parse_override("tomorrow=bold").is_err());
+        assert!(StyleConfig::parse_override("bold").is_err());
+    }
+
     #[test]
     fn test_center_uni_adjust() {
         assert_eq!(
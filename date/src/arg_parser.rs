@@ -1,12 +1,21 @@
-use std::{convert::Infallible, path::PathBuf, str::FromStr};
+use std::{convert::Infallible, fs::Metadata, io, path::PathBuf, str::FromStr, time::SystemTime};
 
 use clap::{
-    Arg, ArgAction, ArgGroup, ArgMatches, Command, CommandFactory, FromArgMatches, command,
-    error::ErrorKind, value_parser,
+    Arg, ArgAction, ArgGroup, ArgMatches, Command, CommandFactory, FromArgMatches,
+    builder::PossibleValuesParser, command, error::ErrorKind, value_parser,
 };
-use jiff::{Zoned, civil::Time, tz::TimeZone};
+use jelal::{IYear, UMonth};
+use jiff::{Span, SpanFieldwise, Timestamp, Zoned, civil, tz::TimeZone};
 
-use jcal::{clap_helper::*, parser::*, posix};
+use jcal::{
+    build_info,
+    clap_helper::*,
+    config::{Config, LOCALES},
+    date::{CalendarKind, CommonDate, Date},
+    names::NameProvider,
+    parser::*,
+    posix, strftime,
+};
 
 /// Provides lines each having a date to parse.
 #[derive(Debug, Clone, PartialEq)]
@@ -15,20 +24,175 @@ pub enum Reader {
     Stdin,
 }
 
+/// Which time of a file is read for `--reference`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReferenceField {
+    /// The last modification (default).
+    Modified,
+    /// The last access.
+    Accessed,
+    /// The creation (birth) time, also available on Windows.
+    Born,
+    /// The last status change, only on Unix since Windows has no such time.
+    Changed,
+}
+
+impl ReferenceField {
+    pub const PARSER_DEFAULT: &'static str = "mtime";
+
+    pub const PARSER_MAP: StaticMap<&'static Self> = StaticMap(&[
+        (Self::PARSER_DEFAULT, &Self::Modified),
+        ("atime", &Self::Accessed),
+        ("btime", &Self::Born),
+        ("ctime", &Self::Changed),
+    ]);
+
+    /// Read the time from the metadata of a file.
+    ///
+    /// The filesystem or the platform may not support all the fields, in which case an error of
+    /// [`io::ErrorKind::Unsupported`] is returned.
+    pub fn time_of(&self, metadata: &Metadata) -> io::Result<SystemTime> {
+        match self {
+            Self::Modified => metadata.modified(),
+            Self::Accessed => metadata.accessed(),
+            Self::Born => metadata.created(),
+            Self::Changed => Self::changed(metadata),
+        }
+    }
+
+    #[cfg(unix)]
+    fn changed(metadata: &Metadata) -> io::Result<SystemTime> {
+        use std::{os::unix::fs::MetadataExt, time::Duration};
+
+        let seconds = metadata.ctime();
+        let base = if seconds >= 0 {
+            SystemTime::UNIX_EPOCH + Duration::from_secs(seconds as u64)
+        } else {
+            SystemTime::UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+        };
+        Ok(base + Duration::from_nanos(metadata.ctime_nsec() as u64))
+    }
+
+    #[cfg(not(unix))]
+    fn changed(_metadata: &Metadata) -> io::Result<SystemTime> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the status change time (ctime) is only available on Unix",
+        ))
+    }
+}
+
+/// How `--diff` prints the difference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffUnits {
+    /// An ISO 8601 duration with years and months in the selected calendar (`P2Y3M5DT4H`).
+    Iso,
+    /// The total number of seconds.
+    Seconds,
+}
+
+impl DiffUnits {
+    pub const PARSER_DEFAULT: &'static str = "iso";
+
+    pub const PARSER_MAP: StaticMap<&'static Self> = StaticMap(&[
+        (Self::PARSER_DEFAULT, &Self::Iso),
+        ("seconds", &Self::Seconds),
+    ]);
+}
+
+/// When the `now` of the relative dates is read while following a file (`--follow-now`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FollowNow {
+    /// Once, when the program starts.
+    Start,
+    /// As each line arrives.
+    Line,
+}
+
+impl FollowNow {
+    pub const PARSER_DEFAULT: &'static str = "line";
+
+    pub const PARSER_MAP: StaticMap<&'static Self> =
+        StaticMap(&[("start", &Self::Start), (Self::PARSER_DEFAULT, &Self::Line)]);
+}
+
+/// How `--is-before`, `--is-after` and `--is-same-day` compare the time to their operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Before,
+    After,
+    /// The same civil day in the given zone (a day is the same in either calendar).
+    SameDay,
+}
+
+impl Comparison {
+    /// If `a` compares to `b` this way, the instants are compared except for [`Self::SameDay`].
+    pub fn holds(&self, a: &Zoned, b: &Zoned, timezone: &TimeZone) -> bool {
+        match self {
+            Self::Before => a.timestamp() < b.timestamp(),
+            Self::After => a.timestamp() > b.timestamp(),
+            Self::SameDay => {
+                a.with_time_zone(timezone.clone()).date()
+                    == b.with_time_zone(timezone.clone()).date()
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum When {
     /// Delay the value as far as possible.
     Now,
     /// The content of a file formatted with a string.
     Reader(Reader),
-    /// The edit time of a file as set in `reference` flag
-    Reference(PathBuf),
+    /// A time of a file as set in `reference` and `reference-field` flags.
+    Reference(PathBuf, ReferenceField),
     /// The given time.
     Given(Zoned),
     // /// Do not print the current date and time (for resolution for example)
     // None, // or perhaps Resolution? maybe even Option<When> + check where it came from?
 }
 
+/// A flag that was accepted but is shadowed by another (see [`lint_args`]).
+#[derive(Debug, Clone, PartialEq)]
+// `--also-tz` and `--format-file` do not exist so neither do their lints, `--date` with `--file`
+// is rejected by clap already (see `Args::DATE_SETTERS_GROUP`).
+pub enum Lint {
+    /// The `TZ=` of `--date` decides the zone of the output.
+    UtcWithDateZone,
+    /// The RFC 5322 date has the Gregorian fields only.
+    JalaliWithRfcEmail,
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UtcWithDateZone => {
+                write!(f, "`--utc` has no effect since `--date` sets its own `TZ=`")
+            }
+            Self::JalaliWithRfcEmail => write!(
+                f,
+                "`--rfc-email` with `--jalali` prints Jalali fields which are not an RFC 5322 date"
+            ),
+        }
+    }
+}
+
+/// Find the flags that parse fine but are shadowed by others.
+pub fn lint_args(args: &Args, matches: &ArgMatches) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    let date_zone = matches
+        .get_one::<String>(Args::DATE_LONG)
+        .is_some_and(|i| matches!(posix::parse_timezone(i), Ok((Some(_), _))));
+    if matches.get_flag(Args::UTC_LONG) && date_zone {
+        lints.push(Lint::UtcWithDateZone);
+    }
+    if args.jalali && matches.get_flag(Args::RFC_EMAIL_LONG) {
+        lints.push(Lint::JalaliWithRfcEmail);
+    }
+    lints
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Args {
     pub format: String,
@@ -36,16 +200,65 @@ pub struct Args {
     pub when: When,
     pub debug: bool,
     pub jalali: bool,
+    /// One of [`LOCALES`].
+    pub locale: String,
+    /// The names [`Self::format`] is written in, of the locale with `--human` only.
+    pub names: NameProvider,
+    /// Spans added to the time in order (years and months in the selected calendar).
+    pub adjust: Vec<SpanFieldwise>,
+    /// Print the difference between two times instead.
+    pub diff: Option<(Zoned, Zoned, DiffUnits)>,
+    /// Print the named formats instead (see [`Args::formats_table`]).
+    pub list_formats: bool,
+    /// The only grammar the dates are read in (see [`Args::parse_input`]).
+    pub input: Option<Grammar>,
+    /// Read the named Jalali days (see [`jcal::anchors`]) too, with `-j` or `--input-calendar
+    /// jalali`.
+    pub jalali_input: bool,
+    /// Parse and print nothing.
+    pub parse_only: bool,
+    /// Print each day of the month of this first day instead, with the format if given.
+    pub month_table: Option<(Date, Option<String>)>,
+    /// Keep reading `--file` after its end as lines are appended (like `tail -f`).
+    pub follow: Option<FollowNow>,
+    /// Exit with the result of comparing the time to this one instead of printing it.
+    pub compare: Option<(Comparison, Zoned)>,
+    /// Print the result of [`Self::compare`] too.
+    pub verbose: bool,
 }
 
 impl Args {
     pub const DEBUG_LONG: &str = "debug";
+    pub const QUIET_LONG: &str = "quiet";
+    pub const FOLLOW_LONG: &str = "follow";
+    pub const FOLLOW_NOW_LONG: &str = "follow-now";
     pub const UTC_LONG: &str = "utc";
     pub const DATE_LONG: &str = "date";
     pub const FILE_LONG: &str = "file";
     pub const REFERENCE_LONG: &str = "reference";
+    pub const REFERENCE_FIELD_LONG: &str = "reference-field";
     pub const JALALI_LONG: &str = "jalali";
     pub const GREGORIAN_LONG: &str = "gregorian";
+    pub const NO_OBSOLETE_POSIX_LONG: &str = "no-obsolete-posix";
+    pub const INPUT_CALENDAR_LONG: &str = "input-calendar";
+    pub const ADJUST_LONG: &str = "adjust";
+    pub const DIFF_LONG: &str = "diff";
+    pub const DIFF_UNITS_LONG: &str = "diff-units";
+    pub const LIST_FORMATS_LONG: &str = "list-formats";
+    pub const INPUT_LONG: &str = "input";
+    pub const PARSE_ONLY_LONG: &str = "parse-only";
+    pub const HELP_EXAMPLES_LONG: &str = "help-examples";
+    pub const MONTH_TABLE_LONG: &str = "month-table";
+    pub const IS_BEFORE_LONG: &str = "is-before";
+    pub const IS_AFTER_LONG: &str = "is-after";
+    pub const IS_SAME_DAY_LONG: &str = "is-same-day";
+    pub const COMPARISON_ARGS: &[&str] = &[
+        Self::IS_BEFORE_LONG,
+        Self::IS_AFTER_LONG,
+        Self::IS_SAME_DAY_LONG,
+    ];
+    /// Also the `--verbose` of `--version`.
+    pub const VERBOSE_LONG: &str = build_info::VERBOSE_LONG;
     // pub const RESOLUTION_LONG: & str = "resolution";
     pub const RFC_3339_LONG: &str = "rfc-3339";
     pub const RFC_3339_PAIRS: StaticMap<&'static str> = StaticMap(&[
@@ -54,6 +267,8 @@ impl Args {
         ("ns", "%Y-%m-%d %H:%M:%S.%N%:z"),
     ]);
     pub const RFC_EMAIL_LONG: &str = "rfc-email";
+    pub const HUMAN_LONG: &str = "human";
+    pub const LOCALE_LONG: &str = "locale";
     pub const ISO_8601_LONG: &str = "iso-8601";
     pub const ISO_8601_DEFAULT: &str = "date";
     pub const ISO_8601_PAIRS: StaticMap<&'static str> = StaticMap(&[
@@ -73,6 +288,9 @@ impl Args {
         Self::GREGORIAN_LONG,
     ];
 
+    pub const CALENDAR_INPUTS_GROUP: &str = "calendar-inputs";
+    pub const CALENDAR_INPUTS_ARGS: &[&str] = &[Self::POSITIONAL_ID, Self::MONTH_TABLE_LONG];
+
     pub const FORMAT_SETTERS_GROUP: &str = "formatters";
     pub const FORMAT_SETTERS_ARGS: &[&str] = &[
         Self::ISO_8601_LONG,
@@ -83,31 +301,150 @@ impl Args {
     pub const RFC_EMAIL_FORMAT: &str = "%a, %d %b %Y %H:%M:%S %z";
     pub const DEFAULT_FORMAT: &str = "%a %b %e %H:%M:%S %Z %Y";
 
-    pub fn groups() -> [ArgGroup; 2] {
+    /// The instant every sample of [`Self::formats_table`] shows.
+    pub const SAMPLE_TIME: &str = "2025-11-07T10:30:05.123456789+03:30[+03:30]";
+
+    // the sections of `--help`
+    pub const INPUT_HEADING: &str = "Input";
+    pub const OUTPUT_FORMAT_HEADING: &str = "Output format";
+    pub const CALENDAR_HEADING: &str = "Calendar";
+    pub const BATCH_HEADING: &str = "Batch (-f)";
+    pub const MISC_HEADING: &str = "Misc";
+
+    /// The invocations of `--help-examples` as the STDIN, the arguments (without `date`), what
+    /// they do and their output (which a test checks against the real one).
+    pub const EXAMPLES: &[(&str, &[&str], &str, &str)] = &[
+        (
+            "",
+            &["-j", "-d", "2025-11-04", "+%Y/%m/%d"],
+            "a Gregorian date in Jalali",
+            "1404/08/13\n",
+        ),
+        (
+            "",
+            &["-g", "1404/08/13", "+%F"],
+            "a Jalali date in Gregorian",
+            "2025-11-04\n",
+        ),
+        (
+            "",
+            &["-u", "--rfc-3339=seconds", "110410302025"],
+            "a POSIX `MMDDhhmmCCYY` stamp in UTC as RFC 3339",
+            "2025-11-04 10:30:00+00:00\n",
+        ),
+        (
+            "",
+            &[
+                "--input-calendar",
+                "jalali",
+                "--rfc-3339=date",
+                "081300001404",
+            ],
+            "a POSIX stamp read in Jalali",
+            "2025-11-04\n",
+        ),
+        (
+            "2025-11-04\n2025-11-20\n",
+            &["-j", "-f", "-", "+%Y/%m/%d"],
+            "each line of STDIN in Jalali",
+            "1404/08/13\n1404/08/29\n",
+        ),
+        (
+            "",
+            &["-u", "-d", "2025-11-04 10:30", "--iso-8601=minutes"],
+            "ISO 8601 to the minute",
+            "2025-11-04T10:30+00:00\n",
+        ),
+        (
+            "",
+            &["-u", "-d", "2025-11-04 10:30", "--rfc-email"],
+            "RFC 5322 (email)",
+            "Tue, 04 Nov 2025 10:30:00 +0000\n",
+        ),
+    ];
+
+    pub fn groups() -> [ArgGroup; 3] {
         [
             ArgGroup::new(Self::DATE_SETTERS_GROUP)
                 .multiple(false)
                 .args(Self::DATE_SETTERS_ARGS),
+            ArgGroup::new(Self::CALENDAR_INPUTS_GROUP)
+                .multiple(true)
+                .args(Self::CALENDAR_INPUTS_ARGS),
             ArgGroup::new(Self::FORMAT_SETTERS_GROUP)
                 .multiple(true)
-                .args(Self::FORMAT_SETTERS_ARGS),
+                .args(Self::FORMAT_SETTERS_ARGS)
+                .arg(Self::HUMAN_LONG),
         ]
     }
 
-    pub fn args() -> [Arg; 11] {
+    /// The text of `--help-examples`.
+    pub fn examples_help() -> String {
+        // quoted for the shell only when needed, the examples have no quotes of their own
+        let quote = |s: &str| {
+            if s.contains(|c: char| c.is_whitespace() || c == '\\') {
+                format!("'{}'", s)
+            } else {
+                s.to_owned()
+            }
+        };
+        let mut v = String::from("Examples:\n");
+        for (stdin, args, description, output) in Self::EXAMPLES {
+            let pipe = if stdin.is_empty() {
+                String::new()
+            } else {
+                format!("printf {} | ", quote(&stdin.replace('\n', "\\n")))
+            };
+            let args: Vec<_> = args.iter().map(|i| quote(i)).collect();
+            v += &format!("  {}date {}\n      {}\n", pipe, args.join(" "), description);
+            for line in output.lines() {
+                v += &format!("        {}\n", line);
+            }
+        }
+        v
+    }
+
+    pub fn args() -> [Arg; 31] {
         [
             Arg::new(Self::JALALI_LONG)
                 .long(Self::JALALI_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
                 .short('j')
-                .help("print this date in Jalali")
+                .help(
+                    "print this date in Jalali (a positional POSIX date is read in Jalali too \
+                     unless `--input-calendar` is given)",
+                )
                 .action(ArgAction::SetTrue),
             Arg::new(Self::DEBUG_LONG)
                 .long(Self::DEBUG_LONG)
+                .help_heading(Self::MISC_HEADING)
                 .help("enable minor extra logs in STDERR")
                 .action(ArgAction::SetTrue),
+            Arg::new(Self::QUIET_LONG)
+                .long(Self::QUIET_LONG)
+                .help_heading(Self::MISC_HEADING)
+                .short('q')
+                .help("do not warn about the configuration or flags shadowed by others")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::NO_OBSOLETE_POSIX_LONG)
+                .long(Self::NO_OBSOLETE_POSIX_LONG)
+                .help_heading(Self::INPUT_HEADING)
+                .help("reject the obsolete `MMDDhhmmYY` INPUT (spell the century instead)")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::INPUT_CALENDAR_LONG)
+                .long(Self::INPUT_CALENDAR_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
+                .value_name("CALENDAR")
+                .requires(Self::CALENDAR_INPUTS_GROUP)
+                .value_parser(CalendarKind::PARSER_MAP)
+                .help(
+                    "the calendar of a positional POSIX date, `--month-table` and the named days \
+                     (e.g. `next nowruz`) [default: the output calendar]",
+                ),
             // general flags
             Arg::new(Self::UTC_LONG)
                 .long(Self::UTC_LONG)
+                .help_heading(Self::INPUT_HEADING)
                 .short('u')
                 .visible_alias("uct")
                 .visible_alias("universal")
@@ -115,17 +452,20 @@ impl Args {
                 .action(ArgAction::SetTrue),
             Arg::new(Self::GREGORIAN_LONG)
                 .long(Self::GREGORIAN_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
                 .short('g')
-                .value_name("%Y/%m/%d")
-                .help("print the given Jalali date in Gregorian"),
+                .value_name("%Y/%m/%d [%H:%M[:%S]]")
+                .help("print the given Jalali date (midnight unless a time is given) in Gregorian"),
             Arg::new(Self::DATE_LONG)
                 .long(Self::DATE_LONG)
+                .help_heading(Self::INPUT_HEADING)
                 .short('d')
                 .overrides_with(Self::DATE_LONG)
                 .help("as if `now` is the given (only the last of multiple values takes effect)"),
             // .value_parser should delegate this since the value may need a custom format
             Arg::new(Self::FILE_LONG)
                 .long(Self::FILE_LONG)
+                .help_heading(Self::BATCH_HEADING)
                 .short('f')
                 .help("read a file or STDIN for dates (use '-' for STDIN)")
                 .value_parser(|s: &str| -> Result<Reader, Infallible> {
@@ -135,29 +475,108 @@ impl Args {
                         Reader::File(PathBuf::from_str(s)?)
                     })
                 }),
+            Arg::new(Self::FOLLOW_LONG)
+                .long(Self::FOLLOW_LONG)
+                .help_heading(Self::BATCH_HEADING)
+                .requires(Self::FILE_LONG)
+                .help("keep reading the `--file` for appended lines instead of stopping at its end")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::FOLLOW_NOW_LONG)
+                .long(Self::FOLLOW_NOW_LONG)
+                .help_heading(Self::BATCH_HEADING)
+                .value_name("WHEN")
+                .requires(Self::FOLLOW_LONG)
+                .value_parser(FollowNow::PARSER_MAP)
+                .help(format!(
+                    "when the relative dates of `--follow` are anchored, at the start or as each \
+                     line arrives [default: {}]",
+                    FollowNow::PARSER_DEFAULT
+                )),
             Arg::new(Self::REFERENCE_LONG)
                 .long(Self::REFERENCE_LONG)
+                .help_heading(Self::INPUT_HEADING)
                 .short('r')
                 .help("as if `now` is the modification time of the given file")
                 .value_parser(value_parser!(PathBuf)),
+            Arg::new(Self::REFERENCE_FIELD_LONG)
+                .long(Self::REFERENCE_FIELD_LONG)
+                .help_heading(Self::INPUT_HEADING)
+                .value_name("FIELD")
+                .requires(Self::REFERENCE_LONG)
+                .value_parser(ReferenceField::PARSER_MAP)
+                .help(format!(
+                    "which time of the `--reference` to use (ctime is not available on Windows) \
+                     [default: {}]",
+                    ReferenceField::PARSER_DEFAULT
+                )),
+            Arg::new(Self::ADJUST_LONG)
+                .long(Self::ADJUST_LONG)
+                .help_heading(Self::INPUT_HEADING)
+                .short('v')
+                .value_name("DURATION")
+                .action(ArgAction::Append)
+                .allow_hyphen_values(true)
+                .help(
+                    "add a duration as ISO 8601 (`P1M`, `-PT2H`) or `+1d`/`-2 hours` (repeatable, \
+                     months and years count in the selected calendar)",
+                )
+                .value_parser(|s: &str| s.parse::<Span>().map(|i| i.fieldwise())),
+            Arg::new(Self::DIFF_LONG)
+                .long(Self::DIFF_LONG)
+                .help_heading(Self::MISC_HEADING)
+                .num_args(2)
+                .value_names(["A", "B"])
+                .allow_hyphen_values(true)
+                .conflicts_with_all(Self::DATE_SETTERS_ARGS)
+                .help(
+                    "print the difference from A to B (years and months in the selected calendar)",
+                ),
+            Arg::new(Self::DIFF_UNITS_LONG)
+                .long(Self::DIFF_UNITS_LONG)
+                .help_heading(Self::MISC_HEADING)
+                .value_name("UNITS")
+                .requires(Self::DIFF_LONG)
+                .value_parser(DiffUnits::PARSER_MAP)
+                .help(format!(
+                    "how `--diff` is printed [default: {}]",
+                    DiffUnits::PARSER_DEFAULT
+                )),
             // arg!(RESOLUTION_LONG)
             // "formatters"
             // edit match_format funciton for parsing
             Arg::new(Self::RFC_EMAIL_LONG)
                 .long(Self::RFC_EMAIL_LONG)
+                .help_heading(Self::OUTPUT_FORMAT_HEADING)
                 .alias("rfc-822")
                 .alias("rfc-2822")
                 .overrides_with_all(Self::FORMAT_SETTERS_ARGS)
                 .help("output in the specification of RFC 5322")
                 .action(ArgAction::SetTrue),
+            Arg::new(Self::HUMAN_LONG)
+                .long(Self::HUMAN_LONG)
+                .help_heading(Self::OUTPUT_FORMAT_HEADING)
+                .conflicts_with_all(Self::FORMAT_SETTERS_ARGS)
+                .help(
+                    "output a long phrase in both calendars with the time and offset (the order \
+                     follows `--locale`)",
+                )
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::LOCALE_LONG)
+                .long(Self::LOCALE_LONG)
+                .help_heading(Self::OUTPUT_FORMAT_HEADING)
+                .value_name("LOCALE")
+                .value_parser(PossibleValuesParser::new(LOCALES))
+                .help("the locale of `--human`"),
             Arg::new(Self::RFC_3339_LONG)
                 .long(Self::RFC_3339_LONG)
+                .help_heading(Self::OUTPUT_FORMAT_HEADING)
                 .value_name("SPEC")
                 .overrides_with_all(Self::FORMAT_SETTERS_ARGS)
                 .help("output in a specification of RFC 3339")
                 .value_parser(Self::RFC_3339_PAIRS),
             Arg::new(Self::ISO_8601_LONG)
                 .long(Self::ISO_8601_LONG)
+                .help_heading(Self::OUTPUT_FORMAT_HEADING)
                 .short('I')
                 .value_name("SPEC")
                 .num_args(0..=1) // if not given don't push the default
@@ -168,22 +587,223 @@ impl Args {
                     Self::ISO_8601_DEFAULT,
                 ))
                 .value_parser(Self::ISO_8601_PAIRS),
+            Arg::new(Self::LIST_FORMATS_LONG)
+                .long(Self::LIST_FORMATS_LONG)
+                .help_heading(Self::OUTPUT_FORMAT_HEADING)
+                .help("print the named formats with their directives and a sample, then exit")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::INPUT_LONG)
+                .long(Self::INPUT_LONG)
+                .help_heading(Self::INPUT_HEADING)
+                .value_name("GRAMMAR")
+                .overrides_with(Self::INPUT_LONG)
+                .value_parser(Grammar::PARSER_MAP)
+                .help("read `--date`, `--diff` and `--file` strictly in this grammar only"),
+            Arg::new(Self::MONTH_TABLE_LONG)
+                .long(Self::MONTH_TABLE_LONG)
+                .help_heading(Self::CALENDAR_HEADING)
+                .value_name("Y/M")
+                .conflicts_with_all([Self::DATE_SETTERS_GROUP, Self::DIFF_LONG])
+                .value_parser(|s: &str| -> Result<(IYear, UMonth), String> {
+                    let invalid = || format!("expected YEAR/MONTH, got `{}`", s);
+                    let (year, month) = s.split_once('/').ok_or_else(invalid)?;
+                    Ok((
+                        year.parse().map_err(|_| invalid())?,
+                        month.parse().map_err(|_| invalid())?,
+                    ))
+                })
+                .help(
+                    "print each day of the month in both calendars (a `+FORMAT` replaces the \
+                     line)",
+                ),
+            Arg::new(Self::IS_BEFORE_LONG)
+                .long(Self::IS_BEFORE_LONG)
+                .help_heading(Self::MISC_HEADING)
+                .value_name("DATE")
+                .allow_hyphen_values(true)
+                .overrides_with_all(Self::COMPARISON_ARGS)
+                .conflicts_with_all([Self::FILE_LONG, Self::DIFF_LONG, Self::MONTH_TABLE_LONG])
+                .help("exit with 0 if the time is before DATE, else 1 (prints nothing)"),
+            Arg::new(Self::IS_AFTER_LONG)
+                .long(Self::IS_AFTER_LONG)
+                .help_heading(Self::MISC_HEADING)
+                .value_name("DATE")
+                .allow_hyphen_values(true)
+                .overrides_with_all(Self::COMPARISON_ARGS)
+                .conflicts_with_all([Self::FILE_LONG, Self::DIFF_LONG, Self::MONTH_TABLE_LONG])
+                .help("exit with 0 if the time is after DATE, else 1 (prints nothing)"),
+            Arg::new(Self::IS_SAME_DAY_LONG)
+                .long(Self::IS_SAME_DAY_LONG)
+                .help_heading(Self::MISC_HEADING)
+                .value_name("DATE")
+                .allow_hyphen_values(true)
+                .overrides_with_all(Self::COMPARISON_ARGS)
+                .conflicts_with_all([Self::FILE_LONG, Self::DIFF_LONG, Self::MONTH_TABLE_LONG])
+                .help("exit with 0 if the time is on the day of DATE in the zone, else 1"),
+            Arg::new(Self::VERBOSE_LONG)
+                .long(Self::VERBOSE_LONG)
+                .help_heading(Self::MISC_HEADING)
+                .help(
+                    "print `true` or `false` for `--is-before`, `--is-after` and `--is-same-day` \
+                     (or the build details with `--version`)",
+                )
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::PARSE_ONLY_LONG)
+                .long(Self::PARSE_ONLY_LONG)
+                .help_heading(Self::BATCH_HEADING)
+                .help("only validate the dates, print nothing but the errors")
+                .action(ArgAction::SetTrue),
+            Arg::new(Self::HELP_EXAMPLES_LONG)
+                .long(Self::HELP_EXAMPLES_LONG)
+                .help_heading(Self::MISC_HEADING)
+                .help("print some example invocations with their output and exit")
+                .action(ArgAction::SetTrue),
             // positionals
             Arg::new(Self::POSITIONAL_ID)
                 .value_name("INPUT")
-                .help("`MMDDhhmm[[CC]YY][.ss]` (POSIX) or a `+FORMAT` (without marks)"),
+                .help(format!(
+                    "`[MMDD]hhmm[[CC]YY][.ss]` (POSIX, not with --{}) or a `+FORMAT` (without \
+                     marks)",
+                    Self::DATE_SETTERS_ARGS.join(", --")
+                )),
         ]
     }
 }
 
+impl Args {
+    /// Apply the configuration file values as if they were the built-in defaults.
+    ///
+    /// If `env_tz` is true (`TZ` is set in the environment), the configured timezone is ignored
+    /// since the environment takes precedence over the configuration.
+    pub fn apply_config(&mut self, config: &Config, env_tz: bool) {
+        if let Some(jalali) = config.jalali {
+            self.jalali = jalali;
+        }
+        if let Some(format) = &config.default_format {
+            self.format = format.clone();
+        }
+        if let Some(locale) = &config.locale {
+            self.locale = locale.clone();
+        }
+        if let (Some(timezone), false) = (&config.timezone, env_tz) {
+            self.timezone = timezone.clone();
+        }
+    }
+}
+
+impl Args {
+    /// The formatting notes including the [`strftime::EXTENSIONS`].
+    fn after_help() -> String {
+        let mut v = "The formatter syntax is as standard as it gets.\n\
+                     Consult https://docs.rs/jiff/latest/jiff/fmt/strtime/index.html and other\n\
+                     `date --help` on other implementation.\n\n\
+                     Extensions (in both calendars):"
+            .to_owned();
+        for (directive, description) in strftime::EXTENSIONS {
+            v.push_str(&format!("\n  {}  {}", directive, description));
+        }
+        v
+    }
+}
+
+impl Args {
+    /// Every named format as the flags that select it and the format string.
+    pub fn named_formats() -> Vec<(String, &'static str)> {
+        let mut v = vec![
+            ("(default)".to_owned(), Self::DEFAULT_FORMAT),
+            (
+                format!("--{}", Self::RFC_EMAIL_LONG),
+                Self::RFC_EMAIL_FORMAT,
+            ),
+        ];
+        for (name, format) in Self::RFC_3339_PAIRS.0 {
+            v.push((format!("--{}={}", Self::RFC_3339_LONG, name), *format));
+        }
+        for (name, format) in Self::ISO_8601_PAIRS.0 {
+            v.push((format!("--{}={}", Self::ISO_8601_LONG, name), *format));
+        }
+        for (locale, format) in strftime::HUMAN_FORMATS.0 {
+            let name = format!("--{} --{}={}", Self::HUMAN_LONG, Self::LOCALE_LONG, locale);
+            v.push((name, *format));
+        }
+        v
+    }
+
+    /// A table of [`Self::named_formats`] with a sample of [`Self::SAMPLE_TIME`] in the calendar.
+    pub fn formats_table(jalali: bool) -> Result<String, jiff::Error> {
+        let sample_time: Zoned = Self::SAMPLE_TIME.parse()?;
+        let formats = Self::named_formats();
+        let name_width = formats
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        let format_width = formats.iter().map(|(_, f)| f.len()).max().unwrap_or(0);
+
+        let mut table = String::new();
+        for (name, format) in formats {
+            let sample = strftime::calendar_strftime(format, &sample_time, jalali)?;
+            table.push_str(&format!(
+                "{:name_width$}  {:format_width$}  {}\n",
+                name, format, sample
+            ));
+        }
+        Ok(table)
+    }
+
+    /// The lines of `--month-table`, one per day of the month of the given first day at midnight.
+    ///
+    /// Without a format, a line is the date in the calendar of the table, in the other calendar
+    /// and the weekday. A format is applied to each day as the output of `date` would be.
+    pub fn month_table_lines(
+        first: &Date,
+        format: Option<&str>,
+        timezone: &TimeZone,
+        jalali: bool,
+    ) -> Result<Vec<String>, jiff::Error> {
+        let mut day = first.clone();
+        (1..=first.month_end_day())
+            .map(|i| {
+                day.set_saturating_day(i);
+                let tm = civil::Date::from(day.clone()).to_zoned(timezone.clone())?;
+                if let Some(format) = format {
+                    return strftime::calendar_strftime(format, &tm, jalali);
+                }
+
+                let jalali_date = strftime::jalali_strftime("%Y/%m/%d", &tm)?;
+                let gregorian_date = strftime::gregorian_strftime("%Y-%m-%d", &tm)?;
+                let (a, b) = match first.kind() {
+                    CalendarKind::Jalali => (jalali_date, gregorian_date),
+                    _ => (gregorian_date, jalali_date),
+                };
+                Ok(format!("{}  {}  {}", a, b, tm.strftime("%A")))
+            })
+            .collect()
+    }
+
+    /// Parse a date of `--date`, `--diff` or `--file` in the grammar if given, else loosely (and
+    /// with the named Jalali days if `jalali`).
+    pub fn parse_input(
+        input: Option<Grammar>,
+        jalali: bool,
+        s: &str,
+        now: &Zoned,
+    ) -> Result<Zoned, jiff::Error> {
+        match input {
+            Some(grammar) => parse_with(grammar, s, now),
+            None if jalali => parse_jalali_datetime(s, Some(now.clone())),
+            None => parse_datetime(s, Some(now.clone())),
+        }
+    }
+}
+
 impl CommandFactory for Args {
     fn command() -> Command {
         command!(/* with version, about and author */)
-            .after_help(
-                "The formatter syntax is as standard as it gets.\n\
-                 Consult https://docs.rs/jiff/latest/jiff/fmt/strtime/index.html and other\n\
-                 `date --help` on other implementation.",
-            )
+            .disable_version_flag(true)
+            // `--verbose` is among our own args
+            .arg(build_info::version_arg())
+            .after_help(Self::after_help())
             // TODO add a -c/--calendar that passes to jiff-icu
             .args(Self::args())
             .groups(Self::groups())
@@ -203,26 +823,74 @@ impl Default for Args {
             when: When::Now,
             debug: false,
             jalali: false,
+            locale: LOCALES[0].to_owned(),
+            names: NameProvider::DEFAULT,
+            adjust: Vec::new(),
+            diff: None,
+            list_formats: false,
+            input: None,
+            jalali_input: false,
+            parse_only: false,
+            month_table: None,
+            follow: None,
+            compare: None,
+            verbose: false,
         }
     }
 }
 
 impl FromArgMatches for Args {
     fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        build_info::exit_on_version(&Self::command(), matches);
+        if matches.get_flag(Self::HELP_EXAMPLES_LONG) {
+            print!("{}", Self::examples_help());
+            std::process::exit(0);
+        }
         let mut v = Self::default();
+        let (config, warnings) =
+            Config::load_default().map_err(|e| Self::error(ErrorKind::InvalidValue, e))?;
+        v.apply_config(&config, std::env::var_os("TZ").is_some());
         v.update_from_arg_matches(matches)?;
+
+        for warning in v.warnings(matches, warnings) {
+            eprintln!("warning: {}", warning);
+        }
         Ok(v)
     }
 
     fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        self.update_at(matches, Timestamp::now())
+    }
+}
+
+impl Args {
+    /// The warnings of the configuration file followed by the lints, none with `--quiet`.
+    pub fn warnings(&self, matches: &ArgMatches, config_warnings: Vec<String>) -> Vec<String> {
+        if matches.get_flag(Self::QUIET_LONG) {
+            return Vec::new();
+        }
+        let lints = lint_args(self, matches).into_iter().map(|i| i.to_string());
+        config_warnings.into_iter().chain(lints).collect()
+    }
+
+    /// Update from the matches as of `now` (the anchor of the relative and the partial dates).
+    pub fn update_at(&mut self, matches: &ArgMatches, now: Timestamp) -> Result<(), clap::Error> {
         if matches.get_flag(Self::UTC_LONG) {
             self.timezone = TimeZone::UTC;
         };
 
-        let now = Zoned::now().with_time_zone(self.timezone.clone());
+        let now = now.to_zoned(self.timezone.clone());
 
         self.debug = self.debug || matches.get_flag(Self::DEBUG_LONG);
+        self.list_formats = matches.get_flag(Self::LIST_FORMATS_LONG);
+        self.parse_only = matches.get_flag(Self::PARSE_ONLY_LONG);
+        if let Some(&&grammar) = matches.get_one::<&Grammar>(Self::INPUT_LONG) {
+            self.input = Some(grammar);
+        }
         self.jalali = self.jalali || matches.get_flag(Self::JALALI_LONG);
+        self.jalali_input = matches
+            .get_one::<&CalendarKind>(Self::INPUT_CALENDAR_LONG)
+            .map_or(self.jalali, |&&i| i == CalendarKind::Jalali);
 
         if let Some(v) = matches.get_one::<&'static str>(Self::RFC_3339_LONG) {
             self.format = v.to_string();
@@ -232,21 +900,76 @@ impl FromArgMatches for Args {
             self.format = Self::RFC_EMAIL_FORMAT.to_string();
         }
 
+        if let Some(locale) = matches.get_one::<String>(Self::LOCALE_LONG) {
+            self.locale = locale.clone();
+        }
+        let human = matches.get_flag(Self::HUMAN_LONG);
+        if human {
+            // the locales are validated on parse
+            self.format = strftime::HUMAN_FORMATS
+                .get(&self.locale)
+                .unwrap()
+                .to_string();
+            self.names = NameProvider::get(&self.locale).unwrap();
+        }
+
+        if let Some(v) = matches.get_many::<SpanFieldwise>(Self::ADJUST_LONG) {
+            self.adjust = v.cloned().collect();
+        }
+
+        if let Some(mut v) = matches.get_many::<String>(Self::DIFF_LONG) {
+            let mut parse = || {
+                Self::parse_input(self.input, self.jalali_input, v.next().unwrap(), &now)
+                    .map_err(|e| Self::error(ErrorKind::InvalidValue, e))
+            };
+            let (a, b) = (parse()?, parse()?);
+            let units = matches
+                .get_one::<&DiffUnits>(Self::DIFF_UNITS_LONG)
+                .map_or(DiffUnits::Iso, |&i| *i);
+            self.diff = Some((a, b, units));
+        }
+
+        let comparisons = [
+            (Self::IS_BEFORE_LONG, Comparison::Before),
+            (Self::IS_AFTER_LONG, Comparison::After),
+            (Self::IS_SAME_DAY_LONG, Comparison::SameDay),
+        ];
+        for (id, comparison) in comparisons {
+            if let Some(v) = matches.get_one::<String>(id) {
+                let other = Self::parse_input(self.input, self.jalali_input, v, &now)
+                    .map_err(|e| Self::error(ErrorKind::InvalidValue, e))?;
+                self.compare = Some((comparison, other));
+            }
+        }
+        self.verbose = self.verbose || matches.get_flag(Self::VERBOSE_LONG);
+
         // try date, then gregorian, then file, then reference
         if let Some(v) = matches.get_one::<String>(Self::DATE_LONG) {
-            self.when = match parse_datetime(v, Some(now.clone())) {
+            self.when = match Self::parse_input(self.input, self.jalali_input, v, &now) {
                 Ok(v) => When::Given(v),
                 Err(e) => return Err(Self::error(ErrorKind::InvalidValue, e)),
             };
         } else if let Some(v) = matches.get_one::<String>(Self::GREGORIAN_LONG) {
-            self.when = match parse_ymd_jalali(v).and_then(|i| i.try_into()) {
-                Ok(v) => When::Given(now.with().date(v).time(Time::midnight()).build().unwrap()),
+            let parsed = parse_ymd_hms_jalali(v).and_then(|(date, time)| {
+                civil::Date::try_from(date).map(|date| date.to_datetime(time))
+            });
+            self.when = match parsed.and_then(|i| i.to_zoned(now.time_zone().clone())) {
+                Ok(v) => When::Given(v),
                 Err(e) => return Err(Self::error(ErrorKind::InvalidValue, e)),
             };
         } else if let Some(v) = matches.get_one::<Reader>(Self::FILE_LONG) {
             self.when = When::Reader(v.clone());
+            if matches.get_flag(Self::FOLLOW_LONG) {
+                let now = matches
+                    .get_one::<&FollowNow>(Self::FOLLOW_NOW_LONG)
+                    .map_or(FollowNow::Line, |&&i| i);
+                self.follow = Some(now);
+            }
         } else if let Some(v) = matches.get_one::<PathBuf>(Self::REFERENCE_LONG) {
-            self.when = When::Reference(v.clone());
+            let field = matches
+                .get_one::<&ReferenceField>(Self::REFERENCE_FIELD_LONG)
+                .map_or(ReferenceField::Modified, |&i| *i);
+            self.when = When::Reference(v.clone(), field);
         }
 
         // custom validation for INPUT (POSIX / +FORMAT)
@@ -268,34 +991,99 @@ impl FromArgMatches for Args {
                     ));
                 }
 
+                // in Jalali, the fields are Jalali and the year defaults to the Jalali one
+                let jalali = self.jalali_input;
+                let options = posix::ParseOptions {
+                    prioritize_trailing: true,
+                    reject_obsolete: matches.get_flag(Self::NO_OBSOLETE_POSIX_LONG),
+                    ambiguity: posix::AmbiguityPolicy::Reject,
+                    ..Default::default()
+                };
+                // the short forms (e.g. `touch -t` "hhmm") are read loosely on top of today
+                let digits = input.split_once('.').map_or(input.as_str(), |(i, _)| i);
+                let parsed = if digits.len() < 8 {
+                    let (month, day) = if jalali {
+                        let today = jelal::Date::from(now.date());
+                        (CommonDate::month(&today), CommonDate::day(&today))
+                    } else {
+                        (now.month() as u8, now.day() as u8)
+                    };
+                    posix::DateTime::parse_loose(input, options, month, day)
+                } else {
+                    posix::DateTime::parse(input, options)
+                };
                 self.when = When::Given(
-                    posix::DateTime::parse(input, true)
+                    parsed
+                        .and_then(|tm| tm.validate_in_calendar(jalali).map(|_| tm))
                         .map_err(|e| e.to_string())
                         .and_then(|tm| {
-                            tm.to_datetime(now.year())
-                                .and_then(|i| i.to_zoned(self.timezone.clone()))
-                                .map_err(|e| e.to_string())
+                            if jalali {
+                                let year = jelal::Date::from(now.date()).year().get();
+                                tm.to_jalali_datetime(year as i16)
+                            } else {
+                                tm.to_datetime(now.year())
+                            }
+                            .and_then(|i| i.to_zoned(self.timezone.clone()))
+                            .map_err(|e| e.to_string())
                         })
                         .map_err(|e| Self::error(ErrorKind::InvalidValue, e))?,
                 );
             }
         }
 
+        if let Some(&(year, month)) = matches.get_one::<(IYear, UMonth)>(Self::MONTH_TABLE_LONG) {
+            let calendar = matches
+                .get_one::<&CalendarKind>(Self::INPUT_CALENDAR_LONG)
+                .map_or(
+                    if self.jalali {
+                        CalendarKind::Jalali
+                    } else {
+                        CalendarKind::Gregorian
+                    },
+                    |&&i| i,
+                );
+            let first = Date::from_ymd(calendar, year, month, 1)
+                .map_err(|e| Self::error(ErrorKind::InvalidValue, e))?;
+            let explicit_format = human
+                || matches.is_explicit(Self::FORMAT_SETTERS_GROUP)
+                || matches
+                    .get_one::<String>(Self::POSITIONAL_ID)
+                    .is_some_and(|i| i.starts_with('+'));
+            self.month_table = Some((first, explicit_format.then(|| self.format.clone())));
+        }
+
+        strftime::Formatter::new(&self.format)
+            .check_widths()
+            .map_err(|e| Self::error(ErrorKind::InvalidValue, e))?;
+
+        // after the INPUT which is still read in Jalali, the phrase has both calendars already
+        if human {
+            self.jalali = false;
+        }
+
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use jiff::civil::date;
+    use jiff::civil::{Time, date};
 
     use super::*;
 
     fn call(no_0_args: &[&str]) -> Args {
+        call_with("", false, no_0_args)
+    }
+
+    /// Parse the arguments on top of the given configuration (not the user's file).
+    fn call_with(config: &str, env_tz: bool, no_0_args: &[&str]) -> Args {
         let matches = Args::command()
             .no_binary_name(true)
             .get_matches_from(no_0_args);
-        Args::from_arg_matches(&matches).unwrap()
+        let mut v = Args::default();
+        v.apply_config(&Config::parse(config).unwrap().0, env_tz);
+        v.update_from_arg_matches(&matches).unwrap();
+        v
     }
 
     #[test]
@@ -307,7 +1095,8 @@ mod tests {
                 timezone: TimeZone::system(),
                 when: When::Now,
                 debug: false,
-                jalali: false
+                jalali: false,
+                ..Default::default()
             }
         );
     }
@@ -321,7 +1110,8 @@ mod tests {
                 timezone: TimeZone::system(),
                 when: When::Now,
                 debug: true,
-                jalali: false
+                jalali: false,
+                ..Default::default()
             }
         );
     }
@@ -335,7 +1125,8 @@ mod tests {
                 timezone: TimeZone::system(),
                 when: When::Now,
                 debug: false,
-                jalali: false
+                jalali: false,
+                ..Default::default()
             }
         );
     }
@@ -349,7 +1140,8 @@ mod tests {
                 timezone: TimeZone::system(),
                 when: When::Now,
                 debug: false,
-                jalali: false
+                jalali: false,
+                ..Default::default()
             }
         );
     }
@@ -363,7 +1155,8 @@ mod tests {
                 timezone: TimeZone::system(),
                 when: When::Now,
                 debug: false,
-                jalali: false
+                jalali: false,
+                ..Default::default()
             }
         );
     }
@@ -377,7 +1170,8 @@ mod tests {
                 timezone: TimeZone::system(),
                 when: When::Now,
                 debug: false,
-                jalali: false
+                jalali: false,
+                ..Default::default()
             }
         );
 
@@ -391,7 +1185,8 @@ mod tests {
                 timezone: TimeZone::system(),
                 when: When::Now,
                 debug: false,
-                jalali: false
+                jalali: false,
+                ..Default::default()
             }
         );
     }
@@ -410,15 +1205,112 @@ mod tests {
                         .unwrap()
                 ),
                 debug: false,
-                jalali: false
+                jalali: false,
+                ..Default::default()
             }
         );
     }
 
     #[test]
-    fn test_cli_gregorian_to_jalali() {
+    fn test_cli_jalali_to_gregorian_with_time() {
+        let at = |args: &[&str]| {
+            let When::Given(tm) = call(args).when else {
+                panic!("expected a given time")
+            };
+            tm.datetime()
+        };
+        assert_eq!(
+            at(&["-g", "1404/07/12 14:30"]),
+            date(2025, 10, 4).at(14, 30, 0, 0)
+        );
+        assert_eq!(
+            at(&["-g", "1404/07/12 14:30:45"]),
+            date(2025, 10, 4).at(14, 30, 45, 0)
+        );
+
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["-g", "1404/07/12 14:61"]);
+        assert!(Args::default().update_from_arg_matches(&matches).is_err());
+    }
+
+    #[test]
+    fn test_cli_adjust_and_diff() {
+        let v = call(&["-v", "P1M", "--adjust", "-2 hours", "-v", "+1d"]);
+        let adjust: Vec<_> = v
+            .adjust
+            .iter()
+            .map(|i| jcal::duration::to_iso(&i.0))
+            .collect();
+        assert_eq!(adjust, ["P1M", "-PT2H", "P1D"]);
+
+        let v = call(&[
+            "--diff",
+            "2025-02-19",
+            "2025-03-21",
+            "--diff-units",
+            "seconds",
+        ]);
+        let (a, b, units) = v.diff.unwrap();
+        assert_eq!((a.date(), b.date()), (date(2025, 2, 19), date(2025, 3, 21)));
+        assert_eq!(units, DiffUnits::Seconds);
+        assert_eq!(
+            call(&["--diff", "2025-02-19", "2025-03-21"])
+                .diff
+                .unwrap()
+                .2,
+            DiffUnits::Iso
+        );
+
+        let matches = Args::command()
+            .no_binary_name(true)
+            .try_get_matches_from(["--adjust", "P"]);
+        assert!(matches.is_err());
+    }
+
+    #[test]
+    fn test_cli_human() {
+        let v = call(&["--human", "-j"]);
+        assert_eq!(
+            v.format,
+            strftime::HUMAN_FORMATS.get("en").unwrap().to_string()
+        );
+        assert!(!v.jalali);
+
+        let v = call_with("locale = \"fa\"", false, &["--human"]);
+        assert_eq!(
+            v.format,
+            strftime::HUMAN_FORMATS.get("fa").unwrap().to_string()
+        );
+        assert_eq!(v.names, NameProvider::FA);
+        // a format of the user is never translated
+        let v = call_with("locale = \"fa\"", false, &["+%B"]);
+        assert_eq!(v.names, NameProvider::DEFAULT);
+        let v = call_with("locale = \"fa\"", false, &["--human", "--locale", "en"]);
         assert_eq!(
-            call(&["-j", "1004000025"]), // 2025/10/04
+            v.format,
+            strftime::HUMAN_FORMATS.get("en").unwrap().to_string()
+        );
+
+        let error = |no_0_args: &[&str]| {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(no_0_args);
+            match matches {
+                Ok(matches) => Args::default().update_from_arg_matches(&matches).is_err(),
+                Err(_) => true,
+            }
+        };
+        assert!(error(&["--human", "--rfc-email"]));
+        assert!(error(&["--human", "-I"]));
+        assert!(error(&["--human", "+%Y"]));
+        assert!(error(&["--locale", "de"]));
+    }
+
+    #[test]
+    fn test_cli_jalali_positional() {
+        assert_eq!(
+            call(&["-j", "071200001404"]), // 2025/10/04
             Args {
                 format: Args::DEFAULT_FORMAT.to_owned(),
                 timezone: TimeZone::system(),
@@ -430,7 +1322,517 @@ mod tests {
                 ),
                 debug: false,
                 jalali: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_cli_conversion_matrix() {
+        let given = |jalali| Args {
+            when: When::Given(
+                date(2025, 10, 04)
+                    .at(0, 0, 0, 0)
+                    .to_zoned(TimeZone::system())
+                    .unwrap(),
+            ),
+            jalali,
+            ..Default::default()
+        };
+
+        // (input, output) in (Jalali, Gregorian)
+        for (args, jalali) in [
+            (&["-j", "071200001404"][..], true),
+            (&["--input-calendar", "jalali", "071200001404"][..], false),
+            (&["-g", "1404/07/12"][..], false),
+            (
+                &["-j", "--input-calendar", "gregorian", "100400002025"][..],
+                true,
+            ),
+            (
+                &["--input-calendar", "gregorian", "100400002025"][..],
+                false,
+            ),
+            (&["100400002025"][..], false),
+        ] {
+            assert_eq!(call(args), given(jalali), "{:?}", args);
+        }
+
+        let try_call = |args: &[&str]| -> Result<Args, clap::Error> {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(args)?;
+            let mut v = Args::default();
+            v.update_from_arg_matches(&matches)?;
+            Ok(v)
+        };
+        assert!(try_call(&["-g", "1404/07/12", "-d", "2025-10-04"]).is_err());
+        assert!(try_call(&["-g", "1404/07/12", "100400002025"]).is_err());
+        assert!(try_call(&["--input-calendar", "jalali"]).is_err());
+        assert!(try_call(&["--input-calendar", "julian", "100400002025"]).is_err());
+        assert!(try_call(&["+%0999999999d"]).is_err());
+        assert!(try_call(&["+%255d"]).is_ok());
+    }
+
+    #[test]
+    fn test_cli_comparisons() {
+        let v = call(&["--is-before", "2025-11-08", "-d", "2025-11-07"]);
+        let (comparison, other) = v.compare.unwrap();
+        assert_eq!(comparison, Comparison::Before);
+        assert_eq!(other.date(), date(2025, 11, 8));
+        assert!(matches!(v.when, When::Given(_)));
+        assert!(!v.verbose);
+
+        // the last one wins
+        let v = call(&[
+            "--is-before",
+            "2025-11-08",
+            "--is-same-day",
+            "2025-11-09",
+            "--verbose",
+        ]);
+        assert_eq!(v.compare.unwrap().0, Comparison::SameDay);
+        assert!(v.verbose);
+
+        for args in [
+            &["--is-after", "2025-11-08", "--file", "-"][..],
+            &["--is-after", "2025-11-08", "--month-table", "1404/8"],
+            &["--is-after", "not a date at all"],
+        ] {
+            let result = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(args)
+                .and_then(|matches| Args::default().update_from_arg_matches(&matches));
+            assert!(result.is_err(), "{:?}", args);
+        }
+    }
+
+    /// The rows of the "Conversion matrix" section of the README as (arguments, output).
+    const CONVERSION_MATRIX: &[(&[&str], &str)] = &[
+        // Gregorian input
+        (&["-d", "2025-11-07", "+%F"], "2025-11-07"),
+        (&["-j", "-d", "2025-11-07", "+%F"], "1404-08-16"),
+        (&["-d", "2025-11-07", "+%EF"], "1404-08-16"),
+        (&["-j", "-d", "2025-11-07", "+%EF"], "1404-08-16"),
+        (&["-d", "2025-11-07", "+%F %EF"], "2025-11-07 1404-08-16"),
+        (&["-d", "2025-11-07", "+%EN/%EB/%Ed"], "1404/Aban/16"),
+        (
+            &["-j", "-d", "2025-11-07", "+%Y/%B/%d %EB"],
+            "1404/Aban/16 Aban",
+        ),
+        (&["-d", "2025-11-07", "+%A %EA"], "Friday Jomeh"),
+        (&["-j", "-d", "2025-11-07", "+%A %EA"], "Friday Jomeh"),
+        (&["-d", "2025-11-07", "+%j %EJ"], "311 232"),
+        (&["-j", "-d", "2025-11-07", "+%j %EJ"], "232 232"),
+        // Jalali input
+        (&["-g", "1404/08/16", "+%F"], "2025-11-07"),
+        (&["-j", "-g", "1404/08/16", "+%F"], "1404-08-16"),
+        (&["-g", "1404/08/16", "+%EF"], "1404-08-16"),
+        (&["-g", "1404/08/16", "+%B %EB"], "November Aban"),
+        (&["-j", "-g", "1404/08/16", "+%B %EB"], "Aban Aban"),
+        (&["-g", "1404/08/16", "+%-d %-Ed"], "7 16"),
+        // POSIX input (the positional is either the date or the format)
+        (&["--rfc-3339=date", "110700002025"], "2025-11-07"),
+        (&["-j", "--rfc-3339=date", "081600001404"], "1404-08-16"),
+        (
+            &[
+                "--input-calendar",
+                "jalali",
+                "--rfc-3339=date",
+                "081600001404",
+            ],
+            "2025-11-07",
+        ),
+        (
+            &[
+                "-j",
+                "--input-calendar",
+                "gregorian",
+                "--rfc-3339=date",
+                "110700002025",
+            ],
+            "1404-08-16",
+        ),
+    ];
+
+    #[test]
+    fn test_cli_conversion_matrix_output() {
+        let mut table = "| command | output |\n|---------|--------|\n".to_owned();
+        for (args, expected) in CONVERSION_MATRIX {
+            let v = call(args);
+            let When::Given(tm) = &v.when else {
+                panic!("expected a given time: {:?}", args)
+            };
+            let output = strftime::calendar_strftime(&v.format, tm, v.jalali).unwrap();
+            assert_eq!(output, *expected, "{:?}", args);
+
+            let args: Vec<_> = args
+                .iter()
+                .map(|i| {
+                    if i.contains(' ') {
+                        format!("'{}'", i)
+                    } else {
+                        i.to_string()
+                    }
+                })
+                .collect();
+            table.push_str(&format!("| `date {}` | `{}` |\n", args.join(" "), output));
+        }
+
+        // the section of the README is this table as is
+        let readme = include_str!("../README.md");
+        assert!(readme.contains(&table), "{}", table);
+    }
+
+    #[test]
+    fn test_cli_positional_day_out_of_month() {
+        let error = |no_0_args: &[&str]| {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .get_matches_from(no_0_args);
+            Args::default()
+                .update_from_arg_matches(&matches)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let e = error(&["-j", "123000001404"]);
+        assert!(e.contains("Esfand has 29 days in 1404"), "{}", e);
+        let e = error(&["023100002025"]);
+        assert!(e.contains("February has 28 days in 2025"), "{}", e);
+    }
+
+    #[test]
+    fn test_cli_positional_short_and_ambiguous() {
+        let When::Given(tm) = call(&["-u", "1230.15"]).when else {
+            panic!("expected a given date");
+        };
+        assert_eq!(tm.time(), Time::constant(12, 30, 15, 0));
+
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["81122"]);
+        let e = Args::default()
+            .update_from_arg_matches(&matches)
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("ambiguous"), "{}", e);
+        assert!(e.contains("0008-11-22 00:00:00 as \"YMMDD\""), "{}", e);
+        assert!(e.contains("08:11:22 as \"hmmss\""), "{}", e);
+    }
+
+    #[test]
+    fn test_cli_reference_field() {
+        assert_eq!(
+            call(&["-r", "Cargo.toml"]).when,
+            When::Reference("Cargo.toml".into(), ReferenceField::Modified)
+        );
+        assert_eq!(
+            call(&["-r", "Cargo.toml", "--reference-field", "btime"]).when,
+            When::Reference("Cargo.toml".into(), ReferenceField::Born)
+        );
+        assert!(
+            Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(["--reference-field", "atime"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_reference_field_time_of() {
+        let metadata = std::fs::metadata("Cargo.toml").unwrap();
+        assert_eq!(
+            ReferenceField::Modified.time_of(&metadata).unwrap(),
+            metadata.modified().unwrap()
+        );
+        // not every filesystem keeps the birth time but it must not fail otherwise
+        if let Err(e) = ReferenceField::Born.time_of(&metadata) {
+            assert_eq!(e.kind(), io::ErrorKind::Unsupported);
+        }
+
+        let changed = ReferenceField::Changed.time_of(&metadata);
+        if cfg!(unix) {
+            assert!(changed.unwrap() > SystemTime::UNIX_EPOCH);
+        } else {
+            assert_eq!(changed.unwrap_err().kind(), io::ErrorKind::Unsupported);
+        }
+    }
+
+    #[test]
+    fn test_cli_no_obsolete_posix() {
+        let When::Given(tm) = call(&["-u", "0704192469"]).when else {
+            panic!("expected a given date");
+        };
+        assert_eq!(tm.datetime(), date(1969, 7, 4).at(19, 24, 0, 0));
+
+        let matches = Args::command()
+            .no_binary_name(true)
+            .get_matches_from(["--no-obsolete-posix", "0704192469"]);
+        let e = Args::default()
+            .update_from_arg_matches(&matches)
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("century"), "{}", e);
+    }
+
+    const CONFIG: &str = "calendar = \"jalali\"\n\
+                          default_format = \"%F\"\n\
+                          timezone = \"Asia/Tehran\"";
+
+    #[test]
+    fn test_config_defaults_applied() {
+        assert_eq!(
+            call_with(CONFIG, false, &[]),
+            Args {
+                format: "%F".to_owned(),
+                timezone: TimeZone::get("Asia/Tehran").unwrap(),
+                when: When::Now,
+                debug: false,
+                jalali: true,
+                ..Default::default()
             }
         );
     }
+
+    #[test]
+    fn test_config_precedence() {
+        // environment over config
+        assert_eq!(call_with(CONFIG, true, &[]).timezone, TimeZone::system());
+        // CLI over environment and config
+        let v = call_with(CONFIG, false, &["-u", "-I"]);
+        assert_eq!(v.timezone, TimeZone::UTC);
+        assert_eq!(
+            v.format,
+            Args::ISO_8601_PAIRS
+                .get(Args::ISO_8601_DEFAULT)
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(call_with(CONFIG, false, &["+%T"]).format, "%T".to_owned());
+    }
+
+    #[test]
+    fn test_list_formats() {
+        assert!(call(&["--list-formats"]).list_formats);
+        assert!(!call(&[]).list_formats);
+
+        let formats = Args::named_formats();
+        let count = 2
+            + Args::RFC_3339_PAIRS.keys().count()
+            + Args::ISO_8601_PAIRS.keys().count()
+            + strftime::HUMAN_FORMATS.keys().count();
+        assert_eq!(formats.len(), count);
+        assert!(formats.contains(&("--rfc-3339=seconds".to_owned(), "%Y-%m-%d %H:%M:%S%:z")));
+
+        for jalali in [false, true] {
+            let table = Args::formats_table(jalali).unwrap();
+            let lines: Vec<_> = table.lines().collect();
+            assert_eq!(lines.len(), formats.len());
+            for ((name, format), line) in formats.iter().zip(&lines) {
+                assert!(line.starts_with(name.as_str()), "{}", line);
+                assert!(line.contains(format), "{}", line);
+            }
+        }
+
+        let gregorian = Args::formats_table(false).unwrap();
+        assert!(
+            gregorian.contains("2025-11-07 10:30:05+03:30"),
+            "{}",
+            gregorian
+        );
+        let jalali = Args::formats_table(true).unwrap();
+        assert!(
+            jalali.contains("1404-08-16T10:30:05,123456789+03:30"),
+            "{}",
+            jalali
+        );
+    }
+
+    #[test]
+    fn test_cli_input_grammar() {
+        let at = |args: &[&str]| {
+            let v = call(args);
+            let When::Given(tm) = v.when else {
+                panic!("expected a given time")
+            };
+            (v.input, v.parse_only, tm.timestamp().to_string())
+        };
+        assert_eq!(
+            at(&["--input", "rfc-3339", "-d", "2025-11-07T10:30:05+03:30"]),
+            (
+                Some(Grammar::Rfc3339),
+                false,
+                "2025-11-07T07:00:05Z".to_owned()
+            )
+        );
+        assert_eq!(
+            at(&[
+                "-u",
+                "--input",
+                "iso-week",
+                "-d",
+                "2025-W45-5",
+                "--parse-only"
+            ]),
+            (
+                Some(Grammar::IsoWeek),
+                true,
+                "2025-11-07T00:00:00Z".to_owned()
+            )
+        );
+
+        let try_call = |args: &[&str]| {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(args)?;
+            Args::default().update_from_arg_matches(&matches)
+        };
+        // the heuristics would take these
+        assert!(try_call(&["--input", "rfc-3339", "-d", "next friday"]).is_err());
+        assert!(try_call(&["--input", "rfc-3339", "-d", "2025-11-07T10:30:05Z junk"]).is_err());
+        assert!(try_call(&["--input", "posix", "--diff", "11071030", "tomorrow"]).is_err());
+        assert!(try_call(&["--input", "rfc-2822", "-d", "2025-11-07"]).is_err());
+        assert!(try_call(&["--input", "unknown", "-d", "2025-11-07"]).is_err());
+        assert!(try_call(&["-d", "next friday"]).is_ok());
+    }
+
+    #[test]
+    fn test_cli_jalali_anchors() {
+        let now: Timestamp = "2025-10-04T07:00:00Z".parse().unwrap();
+        let at = |args: &[&str]| {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(args)
+                .unwrap();
+            let mut v = Args::default();
+            v.update_at(&matches, now).map(|()| v.when)
+        };
+        let nowruz = When::Given("2026-03-21T00:00[UTC]".parse().unwrap());
+        assert_eq!(at(&["-u", "-j", "-d", "next nowruz"]).unwrap(), nowruz);
+        let gregorian_output = [
+            "-u",
+            "--input-calendar",
+            "jalali",
+            "-d",
+            "next nowruz",
+            "+%F",
+        ];
+        assert_eq!(at(&gregorian_output).unwrap(), nowruz);
+        let gregorian_input = [
+            "-u",
+            "-j",
+            "--input-calendar",
+            "gregorian",
+            "-d",
+            "next nowruz",
+            "+%F",
+        ];
+        assert!(at(&gregorian_input).is_err());
+        // a Jalali day is not a Gregorian input
+        assert!(at(&["-u", "-d", "next nowruz"]).is_err());
+        assert!(at(&["-u", "--diff", "next nowruz", "today"]).is_err());
+        assert!(at(&["-u", "-j", "--diff", "next nowruz", "today"]).is_ok());
+    }
+
+    #[test]
+    fn test_lints() {
+        let lints = |args: &[&str]| {
+            let matches = Args::command().no_binary_name(true).get_matches_from(args);
+            let mut v = Args::default();
+            v.update_from_arg_matches(&matches).unwrap();
+            lint_args(&v, &matches)
+        };
+
+        assert!(lints(&[]).is_empty());
+        assert!(lints(&["-u", "-d", "2025-11-07"]).is_empty());
+        assert_eq!(
+            lints(&["-u", "-d", "TZ=\"Asia/Tehran\" 2025-11-07"]),
+            [Lint::UtcWithDateZone]
+        );
+
+        assert!(lints(&["--rfc-email"]).is_empty());
+        assert_eq!(lints(&["-j", "--rfc-email"]), [Lint::JalaliWithRfcEmail]);
+
+        // a flag that is shadowed for sure is an error already
+        let date_and_file = Args::command().no_binary_name(true).try_get_matches_from([
+            "-d",
+            "2025-11-07",
+            "-f",
+            "-",
+        ]);
+        assert!(date_and_file.is_err());
+    }
+
+    #[test]
+    fn test_quiet_warnings() {
+        let warnings = |args: &[&str]| {
+            let matches = Args::command().no_binary_name(true).get_matches_from(args);
+            let mut v = Args::default();
+            v.update_from_arg_matches(&matches).unwrap();
+            v.warnings(&matches, vec!["unknown key `x`".to_owned()])
+        };
+        assert_eq!(
+            warnings(&["-j", "--rfc-email"]),
+            [
+                "unknown key `x`".to_owned(),
+                Lint::JalaliWithRfcEmail.to_string()
+            ]
+        );
+        assert!(warnings(&["-q", "-j", "--rfc-email"]).is_empty());
+        assert!(warnings(&["--quiet"]).is_empty());
+    }
+
+    #[test]
+    fn test_cli_follow() {
+        assert_eq!(call(&["-f", "-"]).follow, None);
+        assert_eq!(call(&["-f", "-", "--follow"]).follow, Some(FollowNow::Line));
+        assert_eq!(
+            call(&["-f", "log", "--follow", "--follow-now", "start"]).follow,
+            Some(FollowNow::Start)
+        );
+
+        let matches = |args: &[&str]| {
+            Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(args)
+        };
+        assert!(matches(&["--follow"]).is_err());
+        assert!(matches(&["-f", "-", "--follow-now", "line"]).is_err());
+        assert!(matches(&["-f", "-", "--follow", "--follow-now", "never"]).is_err());
+    }
+
+    #[test]
+    fn test_month_table() {
+        let table = |args: &[&str]| {
+            let (first, format) = call(args).month_table.unwrap();
+            Args::month_table_lines(&first, format.as_deref(), &TimeZone::UTC, false).unwrap()
+        };
+
+        let lines = table(&["--month-table", "1404/01", "-j"]);
+        assert_eq!(lines.len(), 31);
+        assert_eq!(lines[0], "1404/01/01  2025-03-21  Friday");
+        assert_eq!(lines[30], "1404/01/31  2025-04-20  Sunday");
+        assert_eq!(
+            table(&["--month-table", "1404/01", "--input-calendar", "jalali"]),
+            lines
+        );
+
+        let lines = table(&["--month-table", "2024/02"]);
+        assert_eq!(lines.len(), 29);
+        assert_eq!(lines[0], "2024-02-01  1402/11/12  Thursday");
+        assert_eq!(lines[28], "2024-02-29  1402/12/10  Thursday");
+
+        let lines = table(&["--month-table", "2025/02", "+%d %EB"]);
+        assert_eq!(lines.len(), 28);
+        assert_eq!((&*lines[0], &*lines[27]), ("01 Bahman", "28 Esfand"));
+
+        for invalid in ["2025/13", "2025", "x/1"] {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(["--month-table", invalid]);
+            let result = matches.and_then(|m| Args::default().update_from_arg_matches(&m));
+            assert!(result.is_err(), "{}", invalid);
+        }
+    }
 }
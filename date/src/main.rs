@@ -16,88 +16,526 @@
 //! - parsing datetime is done with mostly `parse_datetime` (POSIX support is extended) crate so its
 //!   limitations apply
 
-use std::io::BufRead;
+use std::{
+    io::{BufRead, Write},
+    time::Duration,
+};
 
-use jcal::{clap_helper::Parse, parser::parse_datetime, strftime::jalali_strftime};
+use jcal::{clap_helper::Parse, duration, strftime::localized_strftime};
 
 mod arg_parser;
 
-use arg_parser::{Args, When};
-use jiff::{Timestamp, Zoned, tz::TimeZone};
+use arg_parser::{Args, DiffUnits, FollowNow, When};
+use jiff::{SpanFieldwise, Timestamp, Zoned};
 
 use crate::arg_parser::Reader;
 
+/// How long `--follow` waits at the end of a regular file before reading again.
+const FOLLOW_POLL: Duration = Duration::from_millis(250);
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Args::parse();
+    let timezone = config.timezone.clone();
+    let clock = || Zoned::now().with_time_zone(timezone.clone());
+    let mut stdout = std::io::stdout().lock();
+    let code = run(&config, &mut std::io::stdin().lock(), &mut stdout, &clock)?;
+    if code != 0 {
+        stdout.flush()?;
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// Run the parsed configuration with `clock` as `now`, returns the exit code.
+///
+/// `stdin` is only read for `--file -`.
+fn run(
+    config: &Args,
+    stdin: &mut dyn BufRead,
+    out: &mut dyn Write,
+    clock: &dyn Fn() -> Zoned,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    if config.list_formats {
+        write!(out, "{}", Args::formats_table(config.jalali)?)?;
+        return Ok(0);
+    }
+
+    // everything given is already parsed unless read from a file
+    if config.parse_only && !matches!(config.when, When::Reader(_)) {
+        return Ok(0);
+    }
+
+    if let Some((first, format)) = &config.month_table {
+        let lines =
+            Args::month_table_lines(first, format.as_deref(), &config.timezone, config.jalali)?;
+        for line in lines {
+            writeln!(out, "{}", line)?;
+        }
+        return Ok(0);
+    }
+
+    if let Some((a, b, units)) = &config.diff {
+        match units {
+            DiffUnits::Iso => writeln!(
+                out,
+                "{}",
+                duration::to_iso(&duration::until(a, b, config.jalali)?)
+            )?,
+            DiffUnits::Seconds => writeln!(out, "{}", b.duration_since(a).as_secs())?,
+        }
+        return Ok(0);
+    }
 
     // The rest of the program is the actual logic.
-    let zoned = match config.when {
-        When::Reader(input_path) => {
-            if file_apply(input_path, &config.format, config.timezone, config.jalali) {
-                return Ok(());
-            } else {
-                return Err("failed to parse all lines".into());
-            }
+    let zoned = match &config.when {
+        When::Reader(reader) => {
+            return match file_apply(reader, config, stdin, out, clock) {
+                Ok(true) => Ok(0),
+                Ok(false) => Err("failed to parse all lines".into()),
+                // e.g. piped into `head`
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(0),
+                Err(e) => Err(e.into()),
+            };
         }
-        When::Given(v) => v,
-        When::Now => Zoned::now().with_time_zone(config.timezone),
-        When::Reference(path_buf) => {
-            let time = std::fs::File::open(path_buf)?.metadata()?.modified()?;
-            Timestamp::try_from(time)?.to_zoned(config.timezone)
+        When::Given(v) => v.clone(),
+        When::Now => clock(),
+        When::Reference(path_buf, field) => {
+            let time = field.time_of(&std::fs::File::open(path_buf)?.metadata()?)?;
+            Timestamp::try_from(time)?.to_zoned(config.timezone.clone())
         }
     };
 
+    let zoned = adjust(&zoned, &config.adjust, config.jalali)?;
+
+    if let Some(code) = compare(config, &zoned) {
+        if config.verbose {
+            writeln!(out, "{}", code == 0)?;
+        }
+        return Ok(code);
+    }
+
     if config.debug {
         eprintln!("output format: `{}`", config.format);
         eprintln!("basis: {}", &zoned);
     }
 
-    print_strftime(&config.format, &zoned, config.jalali);
+    match localized_strftime(&config.format, &zoned, config.jalali, config.names) {
+        Ok(v) => writeln!(out, "{}", v)?,
+        // the widths are checked when parsed, not every error of a format is
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return Ok(1);
+        }
+    }
 
-    Ok(())
+    Ok(0)
 }
 
-/// Print time in the given calendar.
-fn print_strftime(format: &str, tm: &Zoned, jalali: bool) {
-    println!(
-        "{}",
-        if jalali {
-            jalali_strftime(format, tm).unwrap()
-        } else {
-            tm.strftime(format).to_string()
-        }
-    )
+/// Add the `--adjust` spans in order in the given calendar.
+fn adjust(tm: &Zoned, spans: &[SpanFieldwise], jalali: bool) -> Result<Zoned, jiff::Error> {
+    spans.iter().try_fold(tm.clone(), |tm, span| {
+        duration::checked_add(&tm, span.0, jalali)
+    })
+}
+
+/// The exit code of the `--is-*` comparison of the time if any (0 if it holds, else 1).
+fn compare(config: &Args, tm: &Zoned) -> Option<i32> {
+    let (comparison, other) = config.compare.as_ref()?;
+    let holds = comparison.holds(tm, other, &config.timezone);
+    Some(if holds { 0 } else { 1 })
 }
 
 /// Parse each line in a stream as with --date and display each resulting time and date.
 ///
-/// If the file or stream fails to open or yield lines panics. Prints warning for each failed to
-/// parse value.
-///
-/// Returns false if any parsing failed.
-// TODO test
-fn file_apply(reader: Reader, format: &str, timezone: TimeZone, jalali: bool) -> bool {
-    // TODO make an enum
-    let read: &mut dyn std::io::Read = match reader {
-        Reader::Stdin => &mut std::io::stdin(),
-        Reader::File(path) => &mut std::fs::File::open(path).expect("cannot open the file"),
+/// Fails if the file cannot be opened. See [`apply_lines`].
+fn file_apply(
+    reader: &Reader,
+    config: &Args,
+    stdin: &mut dyn BufRead,
+    out: &mut dyn Write,
+    clock: &dyn Fn() -> Zoned,
+) -> std::io::Result<bool> {
+    // only a regular file is polled at its end, a pipe blocks until written to or closed
+    let poll = match reader {
+        Reader::File(path) if config.follow.is_some() => {
+            std::fs::metadata(path)?.is_file().then_some(FOLLOW_POLL)
+        }
+        _ => None,
     };
-    let mut buf_reader = std::io::BufReader::new(read);
+    match reader {
+        Reader::Stdin => apply_lines(stdin, out, config, poll, clock),
+        Reader::File(path) => {
+            let file = std::fs::File::open(path)?;
+            apply_lines(std::io::BufReader::new(file), out, config, poll, clock)
+        }
+    }
+}
 
+/// Print each line of the reader as with --date.
+///
+/// Prints warning for each failed to parse value. Without a format, the lines are only validated.
+/// With [`Args::follow`], every record is flushed as soon as it is read and, given a poll, the end
+/// of the reader is waited on instead (the process ends with an interrupt). The `clock` is the
+/// `now` of the relative dates.
+///
+/// Returns false if any parsing failed.
+fn apply_lines<R: BufRead, W: Write>(
+    mut reader: R,
+    mut out: W,
+    config: &Args,
+    poll: Option<Duration>,
+    clock: impl Fn() -> Zoned,
+) -> std::io::Result<bool> {
+    let format = (!config.parse_only).then_some(config.format.as_str());
     let mut ok = true;
     let mut buf = String::new();
-    let now = Zoned::now().with_time_zone(timezone);
-    // 0 is the end of the file
-    while buf_reader.read_line(&mut buf).expect("cannot read line") != 0 {
-        match parse_datetime(&buf, Some(now.clone())) {
-            Ok(tm) => print_strftime(format, &tm, jalali),
+    let mut now = clock();
+    loop {
+        // 0 is the end of the file
+        if reader.read_line(&mut buf)? == 0 {
+            match poll {
+                Some(v) => {
+                    std::thread::sleep(v);
+                    continue;
+                }
+                None => break,
+            }
+        }
+        // the writer of a followed file may be in the middle of a line
+        if poll.is_some() && !buf.ends_with('\n') {
+            continue;
+        }
+        if config.follow == Some(FollowNow::Line) {
+            now = clock();
+        }
+
+        // the line ending is not a part of the date, a strict grammar would reject it
+        let line = buf.trim_end_matches(['\n', '\r']);
+        match Args::parse_input(config.input, config.jalali_input, line, &now)
+            .and_then(|tm| adjust(&tm, &config.adjust, config.jalali))
+        {
+            Ok(tm) => {
+                if let Some(format) = format {
+                    match localized_strftime(format, &tm, config.jalali, config.names) {
+                        Ok(v) => writeln!(out, "{}", v)?,
+                        Err(e) => {
+                            eprintln!("invalid format {}", e);
+                            ok = false;
+                        }
+                    }
+                }
+            }
             Err(e) => {
                 eprintln!("invalid date {}", e);
                 ok = false;
             }
         };
+        if config.follow.is_some() {
+            out.flush()?;
+        }
         buf.clear();
     }
 
-    ok
+    out.flush()?;
+    Ok(ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            mpsc::{Receiver, Sender, channel},
+        },
+    };
+
+    use super::*;
+
+    /// Reads what is sent to it, blocking like a pipe until the sender is gone.
+    struct ChannelReader {
+        rx: Receiver<String>,
+        pending: Vec<u8>,
+    }
+
+    impl Read for ChannelReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.pending.is_empty() {
+                match self.rx.recv() {
+                    Ok(v) => self.pending = v.into_bytes(),
+                    Err(_) => return Ok(0),
+                }
+            }
+            let n = buf.len().min(self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+
+    /// Sends what is written to it only when flushed.
+    struct ChannelWriter {
+        tx: Sender<String>,
+        buf: Vec<u8>,
+    }
+
+    impl Write for ChannelWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.buf.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            if !self.buf.is_empty() {
+                let v = String::from_utf8(std::mem::take(&mut self.buf)).unwrap();
+                let _ = self.tx.send(v);
+            }
+            Ok(())
+        }
+    }
+
+    /// Follow a stream of two `next nowruz` lines with the clock moving forward on each read.
+    fn follow(now: FollowNow) -> [String; 2] {
+        let (input, rx) = channel();
+        let (tx, output) = channel();
+        let config = Args {
+            format: "%F".to_owned(),
+            jalali_input: true,
+            follow: Some(now),
+            ..Default::default()
+        };
+        let handle = std::thread::spawn(move || {
+            let reader = ChannelReader {
+                rx,
+                pending: Vec::new(),
+            };
+            let writer = ChannelWriter {
+                tx,
+                buf: Vec::new(),
+            };
+            let ticks = AtomicUsize::new(0);
+            let clock = || {
+                let clocks = [
+                    "2025-01-01T00:00[UTC]",
+                    "2025-06-01T00:00[UTC]",
+                    "2026-06-01T00:00[UTC]",
+                ];
+                let i = ticks.fetch_add(1, Ordering::SeqCst).min(2);
+                clocks[i].parse().unwrap()
+            };
+            apply_lines(
+                std::io::BufReader::new(reader),
+                writer,
+                &config,
+                None,
+                clock,
+            )
+        });
+
+        // each record is out before the next one is in
+        let timeout = Duration::from_secs(5);
+        input.send("next nowruz\n".to_owned()).unwrap();
+        let first = output.recv_timeout(timeout).unwrap();
+        input.send("next nowruz\n".to_owned()).unwrap();
+        let second = output.recv_timeout(timeout).unwrap();
+
+        drop(input);
+        assert!(handle.join().unwrap().unwrap());
+        [first, second]
+    }
+
+    #[test]
+    fn test_examples() {
+        use clap::CommandFactory;
+
+        // none of the examples depend on the clock but it is pinned anyway
+        let now: Timestamp = "2025-11-07T07:00:05Z".parse().unwrap();
+        for (stdin, args, _, expected) in Args::EXAMPLES {
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(*args)
+                .unwrap_or_else(|e| panic!("{:?}: {}", args, e));
+            let mut config = Args::default();
+            config
+                .update_at(&matches, now)
+                .unwrap_or_else(|e| panic!("{:?}: {}", args, e));
+            assert!(
+                arg_parser::lint_args(&config, &matches).is_empty(),
+                "{:?}",
+                args
+            );
+
+            let clock = || now.to_zoned(config.timezone.clone());
+            let mut out = Vec::new();
+            let code = run(&config, &mut stdin.as_bytes(), &mut out, &clock)
+                .unwrap_or_else(|e| panic!("{:?}: {}", args, e));
+            assert_eq!(code, 0, "{:?}", args);
+            assert_eq!(String::from_utf8(out).unwrap(), *expected, "{:?}", args);
+        }
+
+        let help = Args::examples_help();
+        assert!(help.starts_with("Examples:\n  date -j -d 2025-11-04 +%Y/%m/%d\n"));
+        assert!(help.contains(
+            "  printf '2025-11-04\\n2025-11-20\\n' | date -j -f - +%Y/%m/%d\n      \
+             each line of STDIN in Jalali\n        1404/08/13\n        1404/08/29\n"
+        ));
+        assert!(help.contains("date -u -d '2025-11-04 10:30' --rfc-email\n"));
+    }
+
+    #[test]
+    fn test_help_headings() {
+        use clap::CommandFactory;
+
+        let command = Args::command();
+        for arg in command.get_arguments() {
+            let id = arg.get_id().as_str();
+            // the positional and the flags of every binary (version) keep the default sections
+            if arg.is_positional()
+                || arg
+                    .get_long()
+                    .is_some_and(|i| i == "version" || i == "verbose")
+            {
+                continue;
+            }
+            assert!(arg.get_help_heading().is_some(), "--{} has no section", id);
+        }
+        let help = command.clone().render_help().to_string();
+        for heading in [
+            Args::INPUT_HEADING,
+            Args::OUTPUT_FORMAT_HEADING,
+            Args::CALENDAR_HEADING,
+            Args::BATCH_HEADING,
+            Args::MISC_HEADING,
+        ] {
+            assert!(help.contains(&format!("{}:", heading)), "{}", heading);
+        }
+    }
+
+    #[test]
+    fn test_run_comparison() {
+        let config = Args {
+            when: When::Given("2025-11-07T10:30[UTC]".parse().unwrap()),
+            compare: Some((
+                arg_parser::Comparison::After,
+                "2025-11-08T00:00[UTC]".parse().unwrap(),
+            )),
+            verbose: true,
+            ..Default::default()
+        };
+        let clock = || -> Zoned { unreachable!("the time is given") };
+        let mut out = Vec::new();
+        let code = run(&config, &mut "".as_bytes(), &mut out, &clock).unwrap();
+        assert_eq!(code, 1);
+        assert_eq!(out, b"false\n");
+    }
+
+    #[test]
+    fn test_follow_now() {
+        assert_eq!(follow(FollowNow::Start), ["2025-03-21\n", "2025-03-21\n"]);
+        assert_eq!(follow(FollowNow::Line), ["2026-03-21\n", "2027-03-21\n"]);
+    }
+
+    #[test]
+    fn test_apply_lines() {
+        let config = Args {
+            format: "%F".to_owned(),
+            ..Default::default()
+        };
+        let clock = || "2025-01-01T00:00[UTC]".parse().unwrap();
+        let mut out = Vec::new();
+        // the last line has no ending
+        let input = "2025-11-07\r\nnot a date\n2025-11-08";
+        let ok = apply_lines(input.as_bytes(), &mut out, &config, None, clock).unwrap();
+        assert!(!ok);
+        assert_eq!(String::from_utf8(out).unwrap(), "2025-11-07\n2025-11-08\n");
+    }
+
+    #[test]
+    fn test_format_error() {
+        // wider than the checked widths, as if it slipped past the parsing
+        let format = "%0999999999d".to_owned();
+        let clock = || -> Zoned { "2025-01-01T00:00[UTC]".parse().unwrap() };
+
+        let config = Args {
+            format: format.clone(),
+            when: When::Given("2025-11-07T10:30[UTC]".parse().unwrap()),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let code = run(&config, &mut "".as_bytes(), &mut out, &clock).unwrap();
+        assert_eq!(code, 1);
+        assert!(out.is_empty());
+
+        // each line fails on its own
+        let config = Args {
+            format,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let input = "2025-11-07\n2025-11-08\n";
+        let ok = apply_lines(input.as_bytes(), &mut out, &config, None, clock).unwrap();
+        assert!(!ok);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_file_apply_missing() {
+        let path = std::env::temp_dir().join("jcal-test-missing-dir/dates.txt");
+        let config = Args {
+            format: "%F".to_owned(),
+            ..Default::default()
+        };
+        let clock = || -> Zoned { unreachable!("nothing is read") };
+        let mut out = Vec::new();
+        let e = file_apply(
+            &Reader::File(path),
+            &config,
+            &mut "".as_bytes(),
+            &mut out,
+            &clock,
+        )
+        .unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_compare() {
+        use arg_parser::Comparison::*;
+        use jiff::tz::{Offset, TimeZone};
+
+        let tm = |s: &str| -> Zoned { s.parse().unwrap() };
+        let tehran = TimeZone::fixed(Offset::from_seconds(12600).unwrap());
+        let code = |a: &str, comparison, b: &str, timezone: &TimeZone| {
+            let config = Args {
+                compare: Some((comparison, tm(b))),
+                timezone: timezone.clone(),
+                ..Default::default()
+            };
+            compare(&config, &tm(a))
+        };
+
+        assert_eq!(
+            compare(&Args::default(), &tm("2025-11-07T10:30[UTC]")),
+            None
+        );
+
+        let a = "2025-11-07T10:30+03:30[+03:30]";
+        let later = "2025-11-08T00:00[UTC]";
+        assert_eq!(code(a, Before, later, &tehran), Some(0));
+        assert_eq!(code(a, After, later, &tehran), Some(1));
+        assert_eq!(code(later, After, a, &tehran), Some(0));
+
+        // the same instant in another zone is neither before nor after
+        let same = "2025-11-07T07:00[UTC]";
+        assert_eq!(code(a, Before, same, &tehran), Some(1));
+        assert_eq!(code(a, After, same, &tehran), Some(1));
+        assert_eq!(code(a, SameDay, same, &tehran), Some(0));
+
+        // 01:00 and 23:30 in Tehran are the 6th and the 7th in UTC
+        let a = "2025-11-07T01:00+03:30[+03:30]";
+        let b = "2025-11-07T20:00[UTC]";
+        assert_eq!(code(a, SameDay, b, &tehran), Some(0));
+        assert_eq!(code(a, SameDay, b, &TimeZone::UTC), Some(1));
+    }
 }
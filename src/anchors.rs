@@ -0,0 +1,134 @@
+//! Named days that repeat each Jalali year for relative parsing (e.g. `next nowruz`).
+//!
+//! Adding an anchor is adding an entry to [`ANCHORS`].
+
+use jelal::IYear;
+
+use crate::date::CommonDate;
+
+/// A named day of each Jalali year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    /// Matched ignoring the case.
+    pub keyword: &'static str,
+    pub description: &'static str,
+    /// The day in the given Jalali year.
+    pub date: fn(IYear) -> jelal::Date,
+}
+
+/// Every known anchor.
+pub const ANCHORS: &[Anchor] = &[
+    Anchor {
+        keyword: "nowruz",
+        description: "1 Farvardin, the new year",
+        date: |year| jelal::Date::from((year, 1, 1)),
+    },
+    Anchor {
+        keyword: "sizdah-bedar",
+        description: "13 Farvardin, the last day of the new year holidays",
+        date: |year| jelal::Date::from((year, 1, 13)),
+    },
+    Anchor {
+        keyword: "yalda",
+        description: "30 Azar, the day whose night is the longest (the eve of 1 Dey)",
+        date: |year| jelal::Date::from((year, 9, 30)),
+    },
+];
+
+/// Which occurrence of an anchor is meant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    /// The first one after today.
+    Next,
+    /// The last one before today.
+    Last,
+    /// The one in this year.
+    This,
+}
+
+/// Find an anchor by its keyword.
+pub fn find(keyword: &str) -> Option<&'static Anchor> {
+    ANCHORS
+        .iter()
+        .find(|i| i.keyword.eq_ignore_ascii_case(keyword))
+}
+
+impl Anchor {
+    /// The occurrence of this anchor relative to today.
+    pub fn resolve(&self, direction: Direction, today: &jelal::Date) -> jelal::Date {
+        let year = CommonDate::year(today);
+        let this = (self.date)(year);
+        let ordinal = CommonDate::ordinal(today);
+        match direction {
+            Direction::This => this,
+            Direction::Next if CommonDate::ordinal(&this) > ordinal => this,
+            Direction::Next => (self.date)(year.saturating_add(1)),
+            Direction::Last if CommonDate::ordinal(&this) < ordinal => this,
+            Direction::Last => (self.date)(year.saturating_sub(1)),
+        }
+    }
+}
+
+/// Resolve a `[next|last] KEYWORD` phrase relative to today or `None` if it is not one.
+pub fn parse(phrase: &str, today: &jelal::Date) -> Option<jelal::Date> {
+    let mut words = phrase.split_whitespace();
+    let first = words.next()?;
+    let (direction, keyword) = if first.eq_ignore_ascii_case("next") {
+        (Direction::Next, words.next()?)
+    } else if first.eq_ignore_ascii_case("last") {
+        (Direction::Last, words.next()?)
+    } else {
+        (Direction::This, first)
+    };
+    if words.next().is_some() {
+        return None;
+    }
+    find(keyword).map(|anchor| anchor.resolve(direction, today))
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil;
+
+    use super::*;
+
+    fn gregorian(date: jelal::Date) -> civil::Date {
+        date.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_known_dates() {
+        let date = |keyword, year| gregorian((find(keyword).unwrap().date)(year));
+        assert_eq!(date("nowruz", 1404), civil::date(2025, 3, 21));
+        assert_eq!(date("Nowruz", 1405), civil::date(2026, 3, 21));
+        assert_eq!(date("sizdah-bedar", 1404), civil::date(2025, 4, 2));
+        assert_eq!(date("yalda", 1403), civil::date(2024, 12, 20));
+        assert_eq!(date("yalda", 1404), civil::date(2025, 12, 21));
+        assert!(find("christmas").is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        let today = jelal::Date::from(civil::date(2025, 10, 4)); // 1404/07/12
+        let parse = |s| parse(s, &today).map(gregorian);
+        assert_eq!(parse("next nowruz"), Some(civil::date(2026, 3, 21)));
+        assert_eq!(parse("last nowruz"), Some(civil::date(2025, 3, 21)));
+        assert_eq!(parse("nowruz"), Some(civil::date(2025, 3, 21)));
+        assert_eq!(parse("NEXT  Yalda"), Some(civil::date(2025, 12, 21)));
+        assert_eq!(parse("last yalda"), Some(civil::date(2024, 12, 20)));
+        assert_eq!(parse("next week"), None);
+        assert_eq!(parse("next nowruz please"), None);
+        assert_eq!(parse("next"), None);
+
+        // today is neither the next nor the last
+        let today = jelal::Date::from((1404, 1, 1));
+        assert_eq!(
+            Anchor::resolve(&ANCHORS[0], Direction::Next, &today),
+            jelal::Date::from((1405, 1, 1))
+        );
+        assert_eq!(
+            Anchor::resolve(&ANCHORS[0], Direction::Last, &today),
+            jelal::Date::from((1403, 1, 1))
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Build details for `--version --verbose` shared by both binaries.
+//!
+//! Dependency versions and features are captured by the build script so they describe the binary
+//! and not the current environment. The timezone database is resolved at runtime.
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+/// The `--version` flag which replaces the builtin one of clap.
+pub const VERSION_LONG: &str = "version";
+
+/// The `--verbose` flag which modifies `--version` (see [`version_arg`] to give it more uses).
+pub const VERBOSE_LONG: &str = "verbose";
+
+/// Shown when the build script could not find a value.
+const UNKNOWN: &str = "unknown";
+
+/// The flags to add to a [`Command`] with disabled version flag.
+pub fn args() -> [Arg; 2] {
+    [
+        version_arg(),
+        Arg::new(VERBOSE_LONG)
+            .long(VERBOSE_LONG)
+            .requires(VERSION_LONG)
+            .help("print the dependencies, features and timezone database with `--version`")
+            .action(ArgAction::SetTrue),
+    ]
+}
+
+/// Only the `--version` flag, for a command with its own `SetTrue` flag of [`VERBOSE_LONG`] which
+/// also does something without `--version`.
+pub fn version_arg() -> Arg {
+    Arg::new(VERSION_LONG)
+        .long(VERSION_LONG)
+        .short('V')
+        .help("print version (add `--verbose` for the build details)")
+        .action(ArgAction::SetTrue)
+}
+
+/// The message to print if asked for version (see [`exit_on_version`]).
+pub fn version_message(command: &Command, matches: &ArgMatches) -> Option<String> {
+    if !matches.get_flag(VERSION_LONG) {
+        return None;
+    }
+
+    let version = command.get_version().unwrap_or(UNKNOWN);
+    Some(if matches.get_flag(VERBOSE_LONG) {
+        report(command.get_name(), version)
+    } else {
+        format!("{} {}\n", command.get_name(), version)
+    })
+}
+
+/// Print the version and exit successfully if asked for, just like the builtin flag of clap.
+///
+/// Call this first in `from_arg_matches` of a command that has [`args`].
+pub fn exit_on_version(command: &Command, matches: &ArgMatches) {
+    if let Some(message) = version_message(command, matches) {
+        print!("{}", message);
+        std::process::exit(0);
+    }
+}
+
+/// A `key: value` line for each of the build details.
+pub fn report(name: &str, version: &str) -> String {
+    let features = match env!("JCAL_FEATURES") {
+        "" => "none",
+        v => v,
+    };
+    let tzdb = format!("{:?}", jiff::tz::db());
+    [
+        ("name", name),
+        ("version", version),
+        ("jcal", env!("CARGO_PKG_VERSION")),
+        (
+            "jelal",
+            option_env!("JCAL_JELAL_VERSION").unwrap_or(UNKNOWN),
+        ),
+        ("jiff", option_env!("JCAL_JIFF_VERSION").unwrap_or(UNKNOWN)),
+        ("tzdb", tzdb.as_str()),
+        ("features", features),
+    ]
+    .iter()
+    .map(|(k, v)| format!("{}: {}\n", k, v))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYS: &[&str] = &[
+        "name", "version", "jcal", "jelal", "jiff", "tzdb", "features",
+    ];
+
+    fn keys(s: &str) -> Vec<&str> {
+        s.lines()
+            .filter_map(|i| i.split_once(": "))
+            .map(|(k, _)| k)
+            .collect()
+    }
+
+    fn command() -> Command {
+        Command::new("test")
+            .version("1.2.3")
+            .no_binary_name(true)
+            .disable_version_flag(true)
+            .args(args())
+    }
+
+    #[test]
+    fn test_report_keys() {
+        assert_eq!(keys(&report("test", "1.2.3")), KEYS);
+    }
+
+    #[test]
+    fn test_version_message() {
+        let message = |args: &[&str]| {
+            let command = command();
+            let matches = command.clone().try_get_matches_from(args).unwrap();
+            version_message(&command, &matches)
+        };
+
+        assert_eq!(message(&[]), None);
+        assert_eq!(message(&["--version"]).unwrap(), "test 1.2.3\n");
+        assert_eq!(keys(&message(&["-V", "--verbose"]).unwrap()), KEYS);
+        // verbose alone is meaningless
+        assert!(command().try_get_matches_from(["--verbose"]).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! Loads user defaults shared by `cal` and `date` from a simple configuration file.
+//!
+//! The file lives at `$XDG_CONFIG_HOME/jcal/config.toml` (`~/.config/jcal/config.toml` if unset)
+//! unless overridden by the [`Config::PATH_ENV`] environment variable. Only the tiny subset of TOML
+//! needed for flat `key = value` pairs is understood: double quoted strings (no escapes), booleans
+//! and `#` comments.
+//!
+//! Keys mirror the CLI flags and each binary maps the ones that make sense to it onto its own
+//! arguments as defaults so explicit flags always win. The file format is defined here once.
+
+use std::path::{Path, PathBuf};
+
+use jelal::Weekday;
+use jiff::tz::TimeZone;
+
+use crate::{clap_helper::StaticMap, names::NameProvider, parser::parse_weekday};
+
+/// Known values of the `locale` key, one per [`NameProvider::ALL`] in the same order.
+// Only `date --human` prints in the chosen locale for now. The names of every locale are parsed
+// (see `crate::names`).
+pub const LOCALES: &[&str] = &{
+    const ALL: &[NameProvider] = NameProvider::ALL;
+    let mut locales = [""; ALL.len()];
+    let mut i = 0;
+    while i < ALL.len() {
+        locales[i] = ALL[i].locale;
+        i += 1;
+    }
+    locales
+};
+
+/// Known values of the `digits` key.
+// No digit substitution is applied yet, the key is only validated for now.
+pub const DIGITS: &[&str] = &["latin", "persian"];
+
+/// When to color the output.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub const PARSER_DEFAULT: &'static str = "auto";
+
+    pub const PARSER_MAP: StaticMap<&'static Self> = StaticMap(&[
+        (Self::PARSER_DEFAULT, &Self::Auto),
+        ("always", &Self::Always),
+        ("never", &Self::Never),
+    ]);
+
+    /// Decide the coloring given if the output is a terminal (see [`std::io::IsTerminal`]).
+    ///
+    /// Returns `None` if the decision is left to the environment (e.g. `NO_COLOR`) which only
+    /// happens in `auto` on a terminal. This is portable and needs no real console to be tested.
+    pub fn resolve(&self, is_terminal: bool) -> Option<bool> {
+        match self {
+            Self::Always => Some(true),
+            Self::Never => Some(false),
+            Self::Auto if is_terminal => None,
+            Self::Auto => Some(false),
+        }
+    }
+}
+
+/// Defaults read from a configuration file (`None` means not given).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    /// `calendar = "jalali"` or `"gregorian"`.
+    pub jalali: Option<bool>,
+    /// `weekday = "saturday"` (anything [`parse_weekday`] accepts).
+    pub weekday: Option<Weekday>,
+    /// `color = "auto"` (see [`ColorMode::PARSER_MAP`]).
+    pub color: Option<ColorMode>,
+    /// `week_numbers = true`.
+    pub week_numbers: Option<bool>,
+    /// `vertical = true`.
+    pub vertical: Option<bool>,
+    /// `span = true`.
+    pub span: Option<bool>,
+    /// `locale = "fa"` (see [`LOCALES`]).
+    pub locale: Option<String>,
+    /// `digits = "persian"` (see [`DIGITS`]).
+    pub digits: Option<String>,
+    /// `default_format = "%F %T"` (a `strftime` format).
+    pub default_format: Option<String>,
+    /// `timezone = "Asia/Tehran"` (an IANA name).
+    pub timezone: Option<TimeZone>,
+}
+
+impl Config {
+    /// The environment variable that overrides the configuration path.
+    pub const PATH_ENV: &str = "JCAL_CONFIG";
+
+    /// The keys understood (see [`Self::set`]), the others are only warned about.
+    pub const KEYS: &[&str] = &[
+        "calendar",
+        "weekday",
+        "color",
+        "week_numbers",
+        "vertical",
+        "span",
+        "locale",
+        "digits",
+        "default_format",
+        "timezone",
+    ];
+
+    /// Where the configuration should be read from, if anywhere.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(v) = std::env::var_os(Self::PATH_ENV) {
+            return Some(PathBuf::from(v));
+        }
+
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|i| PathBuf::from(i).join(".config")))?;
+        Some(config_home.join("jcal").join("config.toml"))
+    }
+
+    /// Read and parse the file in [`Self::path`] if any (see [`Self::load`]).
+    pub fn load_default() -> Result<(Self, Vec<String>), String> {
+        match Self::path() {
+            Some(path) => Self::load(&path),
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// Read and parse the given file. A missing file is the same as an empty one.
+    ///
+    /// Returns the configuration and the warnings (unknown keys) on success.
+    pub fn load(path: &Path) -> Result<(Self, Vec<String>), String> {
+        let prefix = |e: String| format!("{}: {}", path.display(), e);
+        match std::fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s)
+                .map(|(config, warnings)| (config, warnings.into_iter().map(prefix).collect()))
+                .map_err(prefix),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Default::default()),
+            Err(e) => Err(prefix(e.to_string())),
+        }
+    }
+
+    /// Parse the content of a configuration file.
+    ///
+    /// Unknown keys produce warnings while invalid values for known keys are errors.
+    pub fn parse(s: &str) -> Result<(Self, Vec<String>), String> {
+        let mut config = Self::default();
+        let mut warnings = Vec::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", line_number));
+            };
+            let key = key.trim();
+            // the value of an unknown key may be of a type not read here (e.g. a number)
+            if !Self::KEYS.contains(&key) {
+                warnings.push(format!("line {}: unknown key `{}`", line_number, key));
+                continue;
+            }
+            let value = parse_value(value)
+                .ok_or_else(|| format!("line {}: invalid value for `{}`", line_number, key))?;
+
+            config
+                .set(key, value)
+                .map_err(|e| format!("line {}: {}", line_number, e))?;
+        }
+
+        Ok((config, warnings))
+    }
+
+    /// Set the given key of [`Self::KEYS`].
+    fn set(&mut self, key: &str, value: Value) -> Result<(), String> {
+        match key {
+            "calendar" => {
+                let v = value.string(key)?;
+                self.jalali = Some(match v.to_lowercase().as_str() {
+                    "jalali" => true,
+                    "gregorian" => false,
+                    _ => return Err(format!("unknown calendar `{}`", v)),
+                });
+            }
+            "weekday" => {
+                self.weekday = Some(parse_weekday(value.string(key)?)?);
+            }
+            "color" => {
+                let v = value.string(key)?;
+                let color = ColorMode::PARSER_MAP
+                    .get_ignore_case(v)
+                    .ok_or_else(|| format!("unknown color mode `{}`", v))?;
+                self.color = Some((*color).clone());
+            }
+            "week_numbers" => {
+                self.week_numbers = Some(value.bool(key)?);
+            }
+            "vertical" => {
+                self.vertical = Some(value.bool(key)?);
+            }
+            "span" => {
+                self.span = Some(value.bool(key)?);
+            }
+            "locale" => {
+                self.locale = Some(one_of(key, value.string(key)?, LOCALES)?);
+            }
+            "digits" => {
+                self.digits = Some(one_of(key, value.string(key)?, DIGITS)?);
+            }
+            "default_format" => {
+                self.default_format = Some(value.string(key)?.to_owned());
+            }
+            "timezone" => {
+                let v = value.string(key)?;
+                self.timezone = Some(TimeZone::get(v).map_err(|e| e.to_string())?);
+            }
+            _ => return Err(format!("unknown key `{}`", key)),
+        }
+        Ok(())
+    }
+}
+
+/// Validate that the value is one of the known values.
+fn one_of(key: &str, value: &str, known: &[&str]) -> Result<String, String> {
+    if known.contains(&value) {
+        Ok(value.to_owned())
+    } else {
+        Err(format!(
+            "unknown {} `{}` (expected one of: {})",
+            key,
+            value,
+            known.join(", ")
+        ))
+    }
+}
+
+/// A value on the right hand side of a `key = value` line.
+#[derive(Debug, Clone, PartialEq)]
+enum Value<'a> {
+    String(&'a str),
+    Bool(bool),
+}
+
+impl<'a> Value<'a> {
+    fn string(self, key: &str) -> Result<&'a str, String> {
+        match self {
+            Self::String(v) => Ok(v),
+            Self::Bool(_) => Err(format!("`{}` must be a string", key)),
+        }
+    }
+
+    fn bool(self, key: &str) -> Result<bool, String> {
+        match self {
+            Self::Bool(v) => Ok(v),
+            Self::String(_) => Err(format!("`{}` must be a boolean", key)),
+        }
+    }
+}
+
+/// Parse a double quoted string (no escapes) or a boolean with an optional trailing comment.
+fn parse_value(s: &str) -> Option<Value<'_>> {
+    let s = s.trim();
+    let (value, rest) = if let Some(quoted) = s.strip_prefix('"') {
+        let (inner, rest) = quoted.split_once('"')?;
+        (Value::String(inner), rest)
+    } else {
+        let end = s
+            .find(|c: char| c.is_whitespace() || c == '#')
+            .unwrap_or(s.len());
+        let value = match &s[..end] {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => return None,
+        };
+        (value, &s[end..])
+    };
+
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL: &str = r#"
+# every known key
+calendar = "jalali"
+weekday = "monday" # trailing comment
+color = "never"
+week_numbers = true
+vertical = false
+span = true
+locale = "fa"
+digits = "persian"
+default_format = "%Y/%m/%d"
+timezone = "Asia/Tehran"
+"#;
+
+    #[test]
+    fn test_parse_every_key() {
+        assert_eq!(
+            Config::parse(FULL).unwrap(),
+            (
+                Config {
+                    jalali: Some(true),
+                    weekday: Some(Weekday::MON),
+                    color: Some(ColorMode::Never),
+                    week_numbers: Some(true),
+                    vertical: Some(false),
+                    span: Some(true),
+                    locale: Some("fa".to_owned()),
+                    digits: Some("persian".to_owned()),
+                    default_format: Some("%Y/%m/%d".to_owned()),
+                    timezone: Some(TimeZone::get("Asia/Tehran").unwrap()),
+                },
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_key_warns() {
+        let (config, warnings) = Config::parse("calendar = \"gregorian\"\nfoo = true\n").unwrap();
+        assert_eq!(config.jalali, Some(false));
+        assert_eq!(warnings, vec!["line 2: unknown key `foo`".to_owned()]);
+
+        // whatever the value, even one of a type not read here
+        let (config, warnings) =
+            Config::parse("future_key = 3\nother = [1, 2]\nspan = true\n").unwrap();
+        assert_eq!(config.span, Some(true));
+        assert_eq!(
+            warnings,
+            vec![
+                "line 1: unknown key `future_key`".to_owned(),
+                "line 2: unknown key `other`".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keys_are_known() {
+        let keys: Vec<&str> = FULL
+            .lines()
+            .filter_map(|i| i.split_once('='))
+            .map(|(key, _)| key.trim())
+            .collect();
+        assert_eq!(keys, Config::KEYS);
+    }
+
+    #[test]
+    fn test_parse_bad_value() {
+        assert!(Config::parse("weekday = \"someday\"").is_err());
+        assert!(Config::parse("week_numbers = \"yes\"").is_err());
+        assert!(Config::parse("calendar = jalali").is_err());
+        assert!(Config::parse("color = \"never\" extra").is_err());
+        assert!(Config::parse("digits = \"roman\"").is_err());
+        assert!(Config::parse("timezone = \"Not/AZone\"").is_err());
+        assert!(Config::parse("no equal sign").is_err());
+    }
+
+    #[test]
+    fn test_color_mode_resolve() {
+        assert_eq!(ColorMode::Always.resolve(false), Some(true));
+        assert_eq!(ColorMode::Never.resolve(true), Some(false));
+        assert_eq!(ColorMode::Auto.resolve(true), None);
+        assert_eq!(ColorMode::Auto.resolve(false), Some(false));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let path = std::env::temp_dir().join("jcal-test-missing-dir/config.toml");
+        assert_eq!(Config::load(&path).unwrap(), Default::default());
+    }
+}
@@ -1,7 +1,7 @@
 //! Holds a generic calendar utilities with predefined and unified calendar relations.
 
 use jelal::{IDayDiff, IYear, MonthDay, Ordinal, UDayDiff, UMonth, UMonthDay, UOrdinal, Weekday};
-use jiff::civil;
+use jiff::{Zoned, civil, tz::Disambiguation};
 
 /// A tuple of 3 values of year, month and day without any checks.
 ///
@@ -10,7 +10,12 @@ use jiff::civil;
 /// valid calendar for this crate.
 pub use jelal::IYmd;
 
-use crate::{GREGORIAN_MONTHS, JALALI_MONTHS};
+use crate::{
+    clap_helper::StaticMap,
+    hijri,
+    names::NameProvider,
+    parser::{parse_ymd_gregorian, parse_ymd_raw},
+};
 
 const JIFF_MIN_YEAR: IYear = -9999;
 const JIFF_MAX_YEAR: IYear = 9999;
@@ -28,13 +33,49 @@ pub trait CommonDate {
     /// Return what year it is (limits to boundaries).
     fn year(&self) -> IYear;
 
+    /// Set the year keeping the month and the day (saturating to the month end).
+    ///
+    /// A year out of the range of the calendar saturates to the closest one (see
+    /// [`Self::try_set_year`]).
     fn set_saturating_year(&mut self, year: IYear);
 
+    /// Set the year like [`Self::set_saturating_year`] only if it is in the range of the calendar.
+    ///
+    /// Otherwise nothing changes and the closest year in the range is returned.
+    fn try_set_year(&mut self, year: IYear) -> Result<(), IYear>
+    where
+        Self: Clone,
+    {
+        let mut new = self.clone();
+        new.set_saturating_year(year);
+        if new.year() != year {
+            return Err(new.year());
+        }
+        *self = new;
+        Ok(())
+    }
+
     /// Return what month it is (1..=12).
     fn month(&self) -> UMonth;
 
     fn set_saturating_month(&mut self, month: UMonth);
 
+    /// What quarter of the year it is (1..=4), Farvardin to Khordad is the first in Jalali.
+    fn quarter(&self) -> u8 {
+        (self.month() - 1) / 3 + 1
+    }
+
+    /// Set the date to the first day of the given quarter (clamped to 1..=4) of this year.
+    fn set_saturating_quarter_start(&mut self, quarter: u8) {
+        self.set_saturating_day(1);
+        self.set_saturating_month((quarter.clamp(1, 4) - 1) * 3 + 1);
+    }
+
+    /// What half of the year it is (1 or 2).
+    fn half_year(&self) -> u8 {
+        (self.quarter() + 1) / 2
+    }
+
     /// Returns what day of the month is it (1..=31).
     fn day(&self) -> UMonthDay;
 
@@ -49,32 +90,76 @@ pub trait CommonDate {
     /// What weekday it is.
     fn weekday(&self) -> Weekday;
 
+    /// What weekday the first day of this year is.
+    ///
+    /// Weekdays repeat every 7 days so this is found from the ordinal without another date, that
+    /// is a calendar conversion less for each week number.
+    fn first_weekday_of_year(&self) -> Weekday {
+        let back = (self.ordinal() as usize - 1) % 7;
+        self.weekday().forward((7 - back) % 7)
+    }
+
     /// What week number it is (0..=53).
-    fn weeknum(&self, base: Weekday) -> u8
-    where
-        Self: Clone,
-    {
-        let mut new_year = self.clone();
-        new_year.set_saturating_ordinal(1);
-        new_year
-            .weekday()
+    ///
+    /// The days before the first `base` weekday of the year are the week 0.
+    fn weeknum(&self, base: Weekday) -> u8 {
+        self.first_weekday_of_year()
             .count_weeks(self.ordinal() as UDayDiff, &base) as u8
     }
 
-    /// Given a number from 0..=53, set the date to the start of that week.
+    /// The number of the last week of this year (see [`Self::weeknum`]).
+    fn weeks_in_year(&self, base: Weekday) -> u8 {
+        self.first_weekday_of_year()
+            .count_weeks(self.year_end_ordinal() as UDayDiff, &base) as u8
+    }
+
+    /// Given a number from 0..=53, set the date to the start of that week (see [`Self::weeknum`]).
     ///
-    /// Given a number larger than the range may cause saturation to the max ordinal.
+    /// The week 0 starts on the first day of the year. Given a number larger than the range may
+    /// cause saturation to the max ordinal.
     fn set_saturating_weeknum(&mut self, weeks: usize, base: Weekday) {
-        let weeks = weeks.clamp(0, 53);
-        let offset = base.till_next(&self.weekday()) as UOrdinal;
-        self.set_saturating_ordinal(weeks as UOrdinal * 7 + offset) // reset back to that week
+        let weeks = weeks.clamp(0, 53) as UOrdinal;
+        // the week 1 starts on the first `base` of the year
+        self.set_saturating_ordinal(1);
+        let week1 = 1 + self.weekday().till_next(&base) as UOrdinal;
+        self.set_saturating_ordinal(match weeks {
+            0 => 1,
+            _ => week1 + (weeks - 1) * 7,
+        });
+    }
+
+    /// Move to the given weekday of the week this date is in (weeks start on `base`).
+    ///
+    /// The week is never left so the date may move backward. The year is not left either so a
+    /// week split between two years saturates to the first or the last day of this year.
+    fn set_saturating_weekday(&mut self, target: Weekday, base: Weekday) {
+        let current = base.till_next(&self.weekday()) as IDayDiff;
+        let target = base.till_next(&target) as IDayDiff;
+        let ordinal = (self.ordinal() as IDayDiff + target - current)
+            .clamp(1, self.year_end_ordinal() as IDayDiff);
+        self.set_saturating_ordinal(ordinal as UOrdinal);
     }
 
     /// What is the maximum day of month (limitations as in [`Self::day`]).
     fn month_end_day(&self) -> UMonthDay;
 
     /// What is the maximum day of year (limitations as in [`Self::ordinal`]).
-    fn year_end_ordinal(&self) -> UOrdinal;
+    fn year_end_ordinal(&self) -> UOrdinal {
+        self.year_end_ordinal_of(self.year())
+    }
+
+    /// What is the maximum day of the given year of this calendar, without a date in that year.
+    fn year_end_ordinal_of(&self, year: IYear) -> UOrdinal;
+
+    /// How many days this year has (same as [`Self::year_end_ordinal`]).
+    fn days_in_year(&self) -> UOrdinal {
+        self.year_end_ordinal()
+    }
+
+    /// If this year has a leap day (Esfand 30 in Jalali or February 29 in Gregorian).
+    fn is_leap_year(&self) -> bool {
+        self.days_in_year() > 365
+    }
 
     /// Add or remove a month to this month cross year boundaries and never panic.
     fn set_saturating_months_offset(&mut self, months: IDayDiff) {
@@ -88,25 +173,155 @@ pub trait CommonDate {
         self.set_saturating_day(CommonDate::day(&new));
     }
 
-    /// Experimental ISO week number.
-    // TODO if iso is defined on other calendars and stuff, move it to commondate
-    fn iso_weeknum(&self) -> u8
+    /// Add or remove days to this date crossing year boundaries and never panic.
+    ///
+    /// Saturates to the first or the last day of the calendar when out of bounds.
+    fn set_saturating_days_offset(&mut self, days: IDayDiff) {
+        let mut ordinal = self.ordinal() as IDayDiff + days;
+        loop {
+            let year = self.year();
+            if ordinal < 1 {
+                self.set_saturating_year(year - 1);
+                if self.year() == year {
+                    ordinal = 1;
+                    break;
+                }
+                ordinal += self.year_end_ordinal() as IDayDiff;
+            } else if ordinal > self.year_end_ordinal() as IDayDiff {
+                ordinal -= self.year_end_ordinal() as IDayDiff;
+                self.set_saturating_year(year + 1);
+                if self.year() == year {
+                    ordinal = self.year_end_ordinal() as IDayDiff;
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+        self.set_saturating_ordinal(ordinal as UOrdinal);
+    }
+
+    /// How many days are left till the other date (negative if it is in the past).
+    ///
+    /// Both dates must be of the same calendar since year lengths are taken from this one.
+    fn days_until(&self, other: &dyn CommonDate) -> IDayDiff
     where
         Self: Clone,
     {
-        let mut v = self.clone();
-        v.set_saturating_ordinal(1);
-        v.weekday().count_iso_weeks(self.ordinal() as UDayDiff) as u8
+        let mut days = other.ordinal() as IDayDiff - self.ordinal() as IDayDiff;
+        let (from, to, sign) = if self.year() <= other.year() {
+            (self.year(), other.year(), 1)
+        } else {
+            (other.year(), self.year(), -1)
+        };
+
+        let mut probe = self.clone();
+        for year in from..to {
+            probe.set_saturating_year(year);
+            days += sign * probe.year_end_ordinal() as IDayDiff;
+        }
+        days
+    }
+
+    /// ISO 8601 week number (1..=53) applied on the years of this calendar.
+    ///
+    /// Weeks start on Monday and belong to the year that has their Thursday so the first or the
+    /// last few days of a year may be in a week of another year (see [`Self::iso_year`]).
+    fn iso_weeknum(&self) -> u8 {
+        iso_week(self).1
+    }
+
+    /// The year that the week of [`Self::iso_weeknum`] belongs to.
+    fn iso_year(&self) -> IYear {
+        iso_week(self).0
+    }
+}
+
+/// Monday is 1 and Sunday is 7.
+fn iso_weekday(weekday: Weekday) -> IDayDiff {
+    (weekday.get() as IDayDiff + 6) % 7 + 1
+}
+
+/// How many ISO weeks a year of the given first ISO weekday and length has (52 or 53).
+fn iso_weeks_in_year(first: IDayDiff, days: UOrdinal) -> u8 {
+    // 53 if the year starts or ends on a Thursday
+    let last = (first - 1 + days as IDayDiff - 1) % 7 + 1;
+    if first == 4 || last == 4 { 53 } else { 52 }
+}
+
+/// The ISO year and week number of this date.
+///
+/// The previous year is only looked at by its length, no date of it is made.
+fn iso_week<T: CommonDate + ?Sized>(date: &T) -> (IYear, u8) {
+    let first = iso_weekday(date.first_weekday_of_year());
+    let week = (date.ordinal() as IDayDiff - iso_weekday(date.weekday()) + 10) / 7;
+    if week < 1 {
+        let year = date.year().saturating_sub(1);
+        let days = date.year_end_ordinal_of(year);
+        // the previous year ends the day before this one starts
+        let previous_first = (first - 1 - days as IDayDiff).rem_euclid(7) + 1;
+        return (year, iso_weeks_in_year(previous_first, days));
+    }
+    if week > iso_weeks_in_year(first, date.year_end_ordinal()) as IDayDiff {
+        return (date.year().saturating_add(1), 1);
+    }
+    (date.year(), week as u8)
+}
+
+/// How many days the given Jalali month has (months are clamped to 1..=12).
+pub fn jalali_month_len(year: IYear, month: UMonth) -> UMonthDay {
+    match month {
+        ..=6 => 31,
+        7..=11 => 30,
+        _ if jalali_is_leap(year) => 30,
+        _ => 29,
+    }
+}
+
+/// How many days the given Gregorian month has (years and months are clamped to the range).
+pub fn gregorian_month_len(year: IYear, month: UMonth) -> UMonthDay {
+    let year = year.clamp(JIFF_MIN_YEAR, JIFF_MAX_YEAR) as i16;
+    civil::date(year, month.clamp(1, 12) as i8, 1).days_in_month() as UMonthDay
+}
+
+/// How many days the given month of the tabular Hijri calendar has (see [`crate::hijri`]).
+pub fn hijri_month_len(year: IYear, month: UMonth) -> UMonthDay {
+    match month {
+        12 if hijri_is_leap(year) => 30,
+        _ if month % 2 == 1 => 30,
+        _ => 29,
     }
 }
 
+/// If the given year of the tabular Hijri calendar has a Dhu al-Hijjah 30.
+///
+/// These are the years 2, 5, 7, 10, 13, 16, 18, 21, 24, 26 and 29 of each 30 years cycle.
+pub fn hijri_is_leap(year: IYear) -> bool {
+    (14 + 11 * year as i64).rem_euclid(30) < 11
+}
+
+/// If the given Jalali year has an Esfand 30 (same as [`CommonDate::is_leap_year`]).
+pub fn jalali_is_leap(year: IYear) -> bool {
+    jelal::Date::from((year, 12, MonthDay::MAX_DAY)).day() == 30
+}
+
+/// The Gregorian years the given Jalali year starts and ends in (1404 is 2025 to 2026).
+///
+/// Saturates like [`Date::to_gregorian`] out of the Gregorian range.
+pub fn gregorian_span_of_jalali_year(year: IYear) -> (IYear, IYear) {
+    let first = Date::Jalali(jelal::Date::from((year, 1, 1)));
+    let last = Date::Jalali(jelal::Date::from((year, 12, jalali_month_len(year, 12))));
+    (first.to_gregorian().year(), last.to_gregorian().year())
+}
+
 impl CommonDate for jelal::Date {
     fn year(&self) -> IYear {
         self.year().get()
     }
 
     fn set_saturating_year(&mut self, year: IYear) {
-        *self = (year, self.ordinal()).into();
+        // the day saturates to the month end (Esfand 30 to 29)
+        *self = (year, CommonDate::month(self), CommonDate::day(self)).into();
     }
 
     fn month(&self) -> UMonth {
@@ -138,13 +353,11 @@ impl CommonDate for jelal::Date {
     }
 
     fn month_end_day(&self) -> UMonthDay {
-        jelal::Date::from((self.year(), self.month(), MonthDay::MAX_DAY)).day()
+        jalali_month_len(self.year(), self.month())
     }
 
-    fn year_end_ordinal(&self) -> UOrdinal {
-        jelal::Date::from((self.year(), Ordinal::MAX))
-            .ordinal()
-            .get()
+    fn year_end_ordinal_of(&self, year: IYear) -> UOrdinal {
+        jelal::Date::from((year, Ordinal::MAX)).ordinal().get()
     }
 }
 
@@ -154,16 +367,10 @@ impl CommonDate for civil::Date {
     }
 
     fn set_saturating_year(&mut self, year: IYear) {
-        let previous_day = self.day() as u8;
-        *self = self
-            .with()
-            .year(year.clamp(JIFF_MIN_YEAR, JIFF_MAX_YEAR) as i16)
-            .month(self.month() as i8)
-            .day(1)
-            .build()
-            .unwrap();
-        // using with_day, prevents overflow and corrects invalid dates
-        self.set_saturating_day(previous_day);
+        let year = year.clamp(JIFF_MIN_YEAR, JIFF_MAX_YEAR) as i16;
+        // the day saturates to the month end (February 29 to 28)
+        let month_end = civil::date(year, self.month(), 1).days_in_month();
+        *self = civil::date(year, self.month(), self.day().min(month_end));
     }
 
     fn month(&self) -> UMonth {
@@ -211,12 +418,95 @@ impl CommonDate for civil::Date {
     }
 
     fn month_end_day(&self) -> UMonthDay {
-        self.clone().last_of_month().day() as UMonthDay
+        self.days_in_month() as UMonthDay
     }
 
-    fn year_end_ordinal(&self) -> UOrdinal {
-        self.clone().last_of_year().day_of_year() as UOrdinal
+    fn year_end_ordinal_of(&self, year: IYear) -> UOrdinal {
+        365 + UOrdinal::from(gregorian_month_len(year, 2) == 29)
+    }
+}
+
+/// Replace the date of a zoned value keeping its time and zone.
+///
+/// A date that lands the time in a gap or a fold is resolved leniently (see
+/// [`Disambiguation::Compatible`]), out of range values are ignored.
+fn with_zoned_date(zoned: &mut Zoned, f: impl FnOnce(&mut civil::Date)) {
+    let mut date = zoned.date();
+    f(&mut date);
+    if let Ok(v) = zoned
+        .with()
+        .date(date)
+        .disambiguation(Disambiguation::Compatible)
+        .build()
+    {
+        *zoned = v;
+    }
+}
+
+/// Delegates to the Gregorian date of the zone.
+impl CommonDate for Zoned {
+    fn year(&self) -> IYear {
+        CommonDate::year(&self.date())
+    }
+
+    fn set_saturating_year(&mut self, year: IYear) {
+        with_zoned_date(self, |date| date.set_saturating_year(year));
+    }
+
+    fn month(&self) -> UMonth {
+        CommonDate::month(&self.date())
+    }
+
+    fn set_saturating_month(&mut self, month: UMonth) {
+        with_zoned_date(self, |date| date.set_saturating_month(month));
+    }
+
+    fn day(&self) -> UMonthDay {
+        CommonDate::day(&self.date())
+    }
+
+    fn set_saturating_day(&mut self, day: UMonthDay) {
+        with_zoned_date(self, |date| date.set_saturating_day(day));
+    }
+
+    fn ordinal(&self) -> UOrdinal {
+        CommonDate::ordinal(&self.date())
     }
+
+    fn set_saturating_ordinal(&mut self, ordinal: UOrdinal) {
+        with_zoned_date(self, |date| date.set_saturating_ordinal(ordinal));
+    }
+
+    fn weekday(&self) -> Weekday {
+        CommonDate::weekday(&self.date())
+    }
+
+    fn month_end_day(&self) -> UMonthDay {
+        CommonDate::month_end_day(&self.date())
+    }
+
+    fn year_end_ordinal_of(&self, year: IYear) -> UOrdinal {
+        CommonDate::year_end_ordinal_of(&self.date(), year)
+    }
+}
+
+/// Which calendar a [`Date`] is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum CalendarKind {
+    Jalali,
+    Gregorian,
+    Hijri,
+}
+
+impl CalendarKind {
+    // Hijri is not offered to the CLIs yet.
+    pub const PARSER_MAP: StaticMap<&'static Self> =
+        StaticMap(&[("jalali", &Self::Jalali), ("gregorian", &Self::Gregorian)]);
 }
 
 /// Holds the calendars that this package concerns.
@@ -224,6 +514,7 @@ impl CommonDate for civil::Date {
 pub enum Date {
     Jalali(jelal::Date),
     Gregorian(civil::Date),
+    Hijri(hijri::Date),
 }
 
 impl Date {
@@ -231,6 +522,7 @@ impl Date {
         match self {
             Date::Jalali(date) => date,
             Date::Gregorian(date) => date,
+            Date::Hijri(date) => date,
         }
     }
 
@@ -238,18 +530,91 @@ impl Date {
         match self {
             Date::Jalali(date) => date,
             Date::Gregorian(date) => date,
+            Date::Hijri(date) => date,
         }
     }
 
-    pub fn month_names(&self) -> &'static [&'static str; 12] {
+    /// A date in the given calendar, failing instead of saturating if any value is out of range.
+    pub fn from_ymd(
+        calendar: CalendarKind,
+        year: IYear,
+        month: UMonth,
+        day: UMonthDay,
+    ) -> Result<Date, jiff::Error> {
+        if !(1..=12).contains(&month) {
+            return Err(jiff::Error::from_args(format_args!(
+                "month {} is not in 1..=12",
+                month
+            )));
+        }
+
+        let mut date = match calendar {
+            CalendarKind::Jalali => Date::Jalali(jelal::Date::from((year, month, 1))),
+            CalendarKind::Gregorian => Date::Gregorian(
+                civil::Date::new(year as i16, month as i8, 1)
+                    .ok()
+                    .filter(|_| (JIFF_MIN_YEAR..=JIFF_MAX_YEAR).contains(&year))
+                    .ok_or_else(|| {
+                        jiff::Error::from_args(format_args!("year is out of the Gregorian range"))
+                    })?,
+            ),
+            CalendarKind::Hijri => Date::Hijri(hijri::Date::from((year, month, 1))),
+        };
+        if date.year() != year {
+            return Err(jiff::Error::from_args(format_args!(
+                "year is out of the calendar range"
+            )));
+        }
+        if !(1..=date.month_end_day()).contains(&day) {
+            return Err(jiff::Error::from_args(format_args!(
+                "day {} is not in this month",
+                day
+            )));
+        }
+        date.set_saturating_day(day);
+        Ok(date)
+    }
+
+    /// Which calendar this date is in.
+    pub fn kind(&self) -> CalendarKind {
         match self {
-            Date::Jalali(_) => &JALALI_MONTHS,
-            Date::Gregorian(_) => &GREGORIAN_MONTHS,
+            Date::Jalali(_) => CalendarKind::Jalali,
+            Date::Gregorian(_) => CalendarKind::Gregorian,
+            Date::Hijri(_) => CalendarKind::Hijri,
         }
     }
 
+    /// The same day in Jalali.
+    pub fn to_jalali(&self) -> Date {
+        Date::Jalali(self.clone().into())
+    }
+
+    /// The same day in Gregorian (saturates to the boundaries of [`civil::Date`]).
+    pub fn to_gregorian(&self) -> Date {
+        Date::Gregorian(self.clone().into())
+    }
+
+    /// The same day in the tabular Hijri calendar (see [`crate::hijri`]).
+    pub fn to_hijri(&self) -> Date {
+        Date::Hijri(self.clone().into())
+    }
+
+    /// The same day in the given calendar.
+    pub fn convert_to(&self, calendar: CalendarKind) -> Date {
+        match calendar {
+            CalendarKind::Jalali => self.to_jalali(),
+            CalendarKind::Gregorian => self.to_gregorian(),
+            CalendarKind::Hijri => self.to_hijri(),
+        }
+    }
+
+    /// The month names of this calendar (see [`NameProvider::DEFAULT`]).
+    pub fn month_names(&self) -> &'static [&'static str; 12] {
+        NameProvider::DEFAULT.month_names(self.kind())
+    }
+
     pub fn month_name(&self) -> &'static str {
-        self.month_names()[self.month() as usize - 1]
+        NameProvider::DEFAULT.month_name(self.kind(), self.month())
     }
 }
 
@@ -294,8 +659,8 @@ impl CommonDate for Date {
         self.common().month_end_day()
     }
 
-    fn year_end_ordinal(&self) -> UOrdinal {
-        self.common().year_end_ordinal()
+    fn year_end_ordinal_of(&self, year: IYear) -> UOrdinal {
+        self.common().year_end_ordinal_of(year)
     }
 }
 
@@ -307,10 +672,48 @@ impl PartialEq for Date {
             (Self::Gregorian(g), Self::Jalali(j)) | (Self::Jalali(j), Self::Gregorian(g)) => {
                 *j == jelal::Date::from(g.clone())
             }
+            (Self::Hijri(h1), Self::Hijri(h2)) => h1 == h2,
+            (Self::Hijri(_), _) | (_, Self::Hijri(_)) => {
+                self.canonical_key() == other.canonical_key()
+            }
         }
     }
 }
 
+impl Eq for Date {}
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Date {
+    /// The Jalali year and ordinal of this day which is the same in every calendar.
+    fn canonical_key(&self) -> (IYear, UOrdinal) {
+        let date = match self {
+            Self::Jalali(j) => j.clone(),
+            Self::Gregorian(g) => jelal::Date::from(*g),
+            Self::Hijri(h) => jelal::Date::from(*h),
+        };
+        (CommonDate::year(&date), CommonDate::ordinal(&date))
+    }
+}
+
+impl Ord for Date {
+    /// Compare as days regardless of the calendar (consistent with [`PartialEq`]).
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.canonical_key().cmp(&other.canonical_key())
+    }
+}
+
+impl std::hash::Hash for Date {
+    /// Hash the day regardless of the calendar (consistent with [`PartialEq`]).
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.canonical_key().hash(state);
+    }
+}
+
 impl From<jelal::Date> for Date {
     fn from(value: jelal::Date) -> Self {
         Date::Jalali(value)
@@ -323,8 +726,883 @@ impl From<civil::Date> for Date {
     }
 }
 
+impl From<hijri::Date> for Date {
+    fn from(value: hijri::Date) -> Self {
+        Date::Hijri(value)
+    }
+}
+
+impl From<Date> for jelal::Date {
+    fn from(value: Date) -> Self {
+        match value {
+            Date::Jalali(v) => v,
+            Date::Gregorian(v) => v.into(),
+            Date::Hijri(v) => v.into(),
+        }
+    }
+}
+
+impl From<Date> for civil::Date {
+    /// Convert saturating to [`civil::Date::MIN`] or [`civil::Date::MAX`] if out of range.
+    fn from(value: Date) -> Self {
+        match value {
+            Date::Gregorian(v) => v,
+            Date::Hijri(v) => v.into(),
+            Date::Jalali(v) => {
+                let negative = CommonDate::year(&v) < 0;
+                v.try_into().unwrap_or(if negative {
+                    civil::Date::MIN
+                } else {
+                    civil::Date::MAX
+                })
+            }
+        }
+    }
+}
+
+impl From<Date> for hijri::Date {
+    /// Convert saturating to [`hijri::MIN_YEAR`] or [`hijri::MAX_YEAR`] if out of range.
+    fn from(value: Date) -> Self {
+        match value {
+            Date::Hijri(v) => v,
+            Date::Gregorian(v) => v.into(),
+            Date::Jalali(v) => v.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Date {
+    /// Write `1404/07/12 (Jalali)`, `1447/04/12 (Hijri)` or `2025-10-04` (see
+    /// [`std::str::FromStr`]).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tag = match self {
+            Date::Jalali(_) => "Jalali",
+            Date::Hijri(_) => "Hijri",
+            Date::Gregorian(_) => "",
+        };
+        match self {
+            Date::Jalali(_) | Date::Hijri(_) => write!(
+                f,
+                "{:04}/{:02}/{:02} ({})",
+                self.year(),
+                self.month(),
+                self.day(),
+                tag
+            ),
+            Date::Gregorian(_) => {
+                write!(
+                    f,
+                    "{:04}-{:02}-{:02}",
+                    self.year(),
+                    self.month(),
+                    self.day()
+                )
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Date {
+    type Err = jiff::Error;
+
+    /// Parse `jalali:1404/07/12`, `hijri:1447/04/12`, `gregorian:2025-10-04` or a bare "%Y/%m/%d"
+    /// in Gregorian.
+    ///
+    /// The tag ignores the case and the output of [`std::fmt::Display`] is accepted as well.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (calendar, s) = match s.split_once(':') {
+            Some((tag, rest)) => match tag.trim().to_lowercase().as_str() {
+                "jalali" => (CalendarKind::Jalali, rest.trim()),
+                "gregorian" => (CalendarKind::Gregorian, rest.trim()),
+                "hijri" => (CalendarKind::Hijri, rest.trim()),
+                _ => {
+                    return Err(jiff::Error::from_args(format_args!(
+                        "unknown calendar `{}` (expected jalali, hijri or gregorian)",
+                        tag
+                    )));
+                }
+            },
+            None => {
+                if let Some(rest) = s.strip_suffix("(Jalali)") {
+                    (CalendarKind::Jalali, rest.trim_end())
+                } else if let Some(rest) = s.strip_suffix("(Hijri)") {
+                    (CalendarKind::Hijri, rest.trim_end())
+                } else {
+                    (CalendarKind::Gregorian, s)
+                }
+            }
+        };
+
+        match calendar {
+            CalendarKind::Jalali | CalendarKind::Hijri => {
+                let (y, m, d) = parse_ymd_raw(s)?;
+                Date::from_ymd(calendar, y as IYear, m as UMonth, d as UMonthDay)
+            }
+            CalendarKind::Gregorian => Ok(Date::Gregorian(parse_ymd_gregorian(s)?)),
+        }
+    }
+}
+
 impl Default for Date {
     fn default() -> Self {
         Self::Gregorian(civil::Date::constant(1, 1, 1))
     }
 }
+
+/// How far each date of a [`DateRange`] is from the previous one (0 is the same as 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Days(UDayDiff),
+    /// Keeps the day of the start saturating to the month end (31, 30, 30, ... from Shahrivar 31).
+    Months(UDayDiff),
+}
+
+/// Every date from the start to the end (inclusive) in the calendar of the start.
+///
+/// Each date is calculated from the start so months do not drift after a short month.
+#[derive(Debug, Clone)]
+pub struct DateRange {
+    start: Date,
+    /// Already in the calendar of the start.
+    end: Date,
+    step: Step,
+    /// The index of the next date.
+    index: usize,
+    /// How many dates are in the range in total.
+    len: usize,
+}
+
+impl DateRange {
+    /// A range of every day.
+    pub fn new(start: Date, end: Date) -> Self {
+        Self::with_step(start, end, Step::Days(1))
+    }
+
+    pub fn with_step(start: Date, end: Date, step: Step) -> Self {
+        let end = end.convert_to(start.kind());
+        let mut v = Self {
+            start,
+            end,
+            step,
+            index: 0,
+            len: 0,
+        };
+        v.len = v.count();
+        v
+    }
+
+    /// The date at the given index regardless of the end.
+    fn nth_date(&self, index: usize) -> Date {
+        let mut date = self.start.clone();
+        match self.step {
+            Step::Days(days) => {
+                let days = index.saturating_mul(days.max(1) as usize);
+                date.set_saturating_days_offset(IDayDiff::try_from(days).unwrap_or(IDayDiff::MAX));
+            }
+            Step::Months(months) => {
+                let months = index.saturating_mul(months.max(1) as usize);
+                date.set_saturating_months_offset(
+                    IDayDiff::try_from(months).unwrap_or(IDayDiff::MAX),
+                );
+                date.set_saturating_day(self.start.day());
+            }
+        }
+        date
+    }
+
+    /// How many dates are between the start and the end.
+    fn count(&self) -> usize {
+        if self.start > self.end {
+            return 0;
+        }
+        let last = match self.step {
+            Step::Days(days) => self.start.days_until(&self.end) as usize / days.max(1) as usize,
+            Step::Months(months) => {
+                let total = (self.end.year() - self.start.year()) as usize * 12
+                    + self.end.month() as usize
+                    - self.start.month() as usize;
+                let last = total / months.max(1) as usize;
+                // the end may be before the day of the start in its month
+                if self.nth_date(last) > self.end {
+                    last - 1
+                } else {
+                    last
+                }
+            }
+        };
+        last + 1
+    }
+}
+
+impl Iterator for DateRange {
+    type Item = Date;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+        let date = self.nth_date(self.index);
+        self.index += 1;
+        Some(date)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.len - self.index;
+        (left, Some(left))
+    }
+}
+
+impl ExactSizeIterator for DateRange {}
+
+/// `Date` as `{"calendar":"jalali","year":1404,"month":7,"day":12}`.
+///
+/// The calendar is read ignoring the case and invalid months or days are rejected.
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Raw {
+        calendar: String,
+        year: IYear,
+        month: UMonth,
+        day: UMonthDay,
+    }
+
+    impl Serialize for Date {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let calendar = match self.kind() {
+                CalendarKind::Jalali => "jalali",
+                CalendarKind::Hijri => "hijri",
+                CalendarKind::Gregorian => "gregorian",
+            };
+            Raw {
+                calendar: calendar.to_owned(),
+                year: self.year(),
+                month: self.month(),
+                day: self.day(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Date {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let raw = Raw::deserialize(deserializer)?;
+            let calendar = match raw.calendar.to_lowercase().as_str() {
+                "jalali" => CalendarKind::Jalali,
+                "hijri" => CalendarKind::Hijri,
+                "gregorian" => CalendarKind::Gregorian,
+                v => {
+                    return Err(D::Error::unknown_variant(
+                        v,
+                        &["jalali", "gregorian", "hijri"],
+                    ));
+                }
+            };
+            Date::from_ymd(calendar, raw.year, raw.month, raw.day).map_err(D::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset<T: CommonDate + Clone>(date: &T, days: IDayDiff) -> T {
+        let mut date = date.clone();
+        date.set_saturating_days_offset(days);
+        date
+    }
+
+    #[test]
+    fn test_month_len() {
+        for year in 1300..=1500 {
+            let mut days = 0;
+            for month in 1..=12 {
+                let len = jalali_month_len(year, month);
+                let date = jelal::Date::from((year, month, 1));
+                assert_eq!(len, CommonDate::month_end_day(&date), "{}/{}", year, month);
+                let end = jelal::Date::from((year, month, MonthDay::MAX_DAY));
+                assert_eq!(len, CommonDate::day(&end), "{}/{}", year, month);
+                days += len as UOrdinal;
+            }
+            let date = jelal::Date::from((year, 1, 1));
+            assert_eq!(
+                jalali_is_leap(year),
+                CommonDate::is_leap_year(&date),
+                "{}",
+                year
+            );
+            assert_eq!(days, CommonDate::days_in_year(&date), "{}", year);
+        }
+        assert!(jalali_is_leap(1403));
+        assert!(!jalali_is_leap(1404));
+        assert_eq!(jalali_month_len(1404, 8), 30);
+
+        for year in 1900..=2100 {
+            for month in 1..=12 {
+                let date = civil::date(year as i16, month as i8, 1);
+                assert_eq!(
+                    gregorian_month_len(year, month),
+                    CommonDate::month_end_day(&date),
+                    "{}-{}",
+                    year,
+                    month
+                );
+            }
+        }
+        assert_eq!(gregorian_month_len(2024, 2), 29);
+        assert_eq!(gregorian_month_len(2100, 2), 28);
+    }
+
+    #[test]
+    fn test_days_offset_jalali() {
+        // 1403 is a leap year so Esfand 30 comes before Farvardin 1
+        let esfand29 = jelal::Date::from((1403, 12, 29));
+        let farvardin1 = jelal::Date::from((1404, 1, 1));
+        assert_eq!(offset(&esfand29, 1), jelal::Date::from((1403, 12, 30)));
+        assert_eq!(offset(&esfand29, 2), farvardin1);
+        assert_eq!(offset(&farvardin1, -2), esfand29);
+        assert_eq!(esfand29.days_until(&farvardin1), 2);
+        assert_eq!(farvardin1.days_until(&esfand29), -2);
+
+        // back through the whole leap year 1403 and into 1402
+        let target = jelal::Date::from((1402, 12, 29));
+        assert_eq!(offset(&farvardin1, -367), target);
+        assert_eq!(farvardin1.days_until(&target), -367);
+    }
+
+    #[test]
+    fn test_days_offset_gregorian() {
+        let dec31 = civil::date(2024, 12, 31);
+        let jan1 = civil::date(2025, 1, 1);
+        assert_eq!(offset(&dec31, 1), jan1);
+        assert_eq!(offset(&jan1, -1), dec31);
+        assert_eq!(dec31.days_until(&jan1), 1);
+
+        // spans both 2024 (a leap year) and 2023
+        let start = civil::date(2025, 1, 10);
+        let target = civil::date(2023, 12, 7);
+        assert_eq!(offset(&start, -400), target);
+        assert_eq!(start.days_until(&target), -400);
+        assert_eq!(target.days_until(&start), 400);
+        assert_eq!(offset(&target, 400), start);
+    }
+
+    #[test]
+    fn test_days_offset_saturates() {
+        let max = civil::date(9999, 12, 31);
+        assert_eq!(offset(&max, 10), max);
+        let min = civil::date(-9999, 1, 1);
+        assert_eq!(offset(&min, -10), min);
+    }
+
+    #[test]
+    fn test_zoned_setters_keep_time_and_zone() {
+        let at = |s: &str| -> Zoned { s.parse().unwrap() };
+
+        // 2025-03-09 02:30 does not exist in New York so it moves past the gap
+        let mut v = at("2025-03-08T02:30[America/New_York]");
+        CommonDate::set_saturating_day(&mut v, 9);
+        assert_eq!(v, at("2025-03-09T03:30-04:00[America/New_York]"));
+        assert_eq!(CommonDate::day(&v), 9);
+
+        // across the fall back, the time of day stays and the offset follows the zone
+        let mut v = at("2025-10-31T12:00-04:00[America/New_York]");
+        CommonDate::set_saturating_months_offset(&mut v, 1);
+        assert_eq!(v, at("2025-11-01T12:00-04:00[America/New_York]"));
+        v.set_saturating_days_offset(2);
+        assert_eq!(v, at("2025-11-03T12:00-05:00[America/New_York]"));
+
+        let mut v = at("2024-02-29T23:59:59[Asia/Tehran]");
+        CommonDate::set_saturating_year(&mut v, 2025);
+        assert_eq!(v, at("2025-02-28T23:59:59[Asia/Tehran]"));
+        assert_eq!(CommonDate::weekday(&v), Weekday::FRI);
+    }
+
+    #[test]
+    fn test_leap_years() {
+        // inherent methods of the dates shadow the trait so it is spelled out
+        let esfand = jelal::Date::from((1403, 12, 1));
+        assert!(CommonDate::is_leap_year(&esfand));
+        assert_eq!(CommonDate::days_in_year(&esfand), 366);
+        assert_eq!(CommonDate::month_end_day(&esfand), 30);
+        assert!(!CommonDate::is_leap_year(&jelal::Date::from((1404, 1, 1))));
+
+        let date = civil::date(1900, 6, 1);
+        assert!(!CommonDate::is_leap_year(&date));
+        assert_eq!(CommonDate::days_in_year(&date), 365);
+        assert!(CommonDate::is_leap_year(&civil::date(2000, 6, 1)));
+        assert!(Date::from(civil::date(2024, 1, 1)).is_leap_year());
+    }
+
+    #[test]
+    fn test_gregorian_span_of_jalali_year() {
+        assert_eq!(gregorian_span_of_jalali_year(1404), (2025, 2026));
+        // a leap year ends on the 20th of March
+        assert_eq!(gregorian_span_of_jalali_year(1403), (2024, 2025));
+        assert_eq!(gregorian_span_of_jalali_year(1), (622, 623));
+        assert_eq!(gregorian_span_of_jalali_year(1399), (2020, 2021));
+    }
+
+    #[test]
+    fn test_ord_across_calendars() {
+        let nowruz = Date::from(jelal::Date::from((1404, 1, 1)));
+        let march21 = Date::from(civil::date(2025, 3, 21));
+        let march20 = Date::from(civil::date(2025, 3, 20));
+        let farvardin2 = Date::from(jelal::Date::from((1404, 1, 2)));
+
+        assert_eq!(nowruz.cmp(&march21), std::cmp::Ordering::Equal);
+        assert!(march20 < nowruz);
+        assert!(farvardin2 > march21);
+        assert!(march20 < farvardin2);
+
+        assert_eq!(march20.clone().min(nowruz.clone()), march20);
+        assert_eq!(farvardin2.clone().max(march21.clone()), farvardin2);
+        assert_eq!(nowruz.clone().max(march21.clone()), march21);
+    }
+
+    #[test]
+    fn test_convert_around_nowruz() {
+        let pairs = [
+            ((2024, 3, 19), (1402, 12, 29)),
+            ((2024, 3, 20), (1403, 1, 1)),
+            ((2025, 3, 20), (1403, 12, 30)),
+            ((2025, 3, 21), (1404, 1, 1)),
+            ((2025, 3, 22), (1404, 1, 2)),
+        ];
+        for ((y, m, d), jalali) in pairs {
+            let gregorian = Date::from(civil::date(y, m, d));
+            let jalali = Date::from(jelal::Date::from(jalali));
+            assert_eq!(gregorian.to_jalali().kind(), CalendarKind::Jalali);
+            assert_eq!(gregorian.to_jalali(), jalali);
+            assert_eq!(jalali.to_gregorian().kind(), CalendarKind::Gregorian);
+            assert_eq!(
+                civil::Date::from(jalali.clone()),
+                civil::date(y, m, d),
+                "{:?}",
+                jalali
+            );
+        }
+
+        // every day of the weeks around both new years survive a round trip
+        for start in [civil::date(2024, 3, 13), civil::date(2025, 3, 14)] {
+            for days in 0..14 {
+                let date = Date::from(offset(&start, days));
+                let back = date.convert_to(CalendarKind::Jalali).to_gregorian();
+                assert_eq!(civil::Date::from(back), offset(&start, days));
+                assert_eq!(date.convert_to(CalendarKind::Gregorian), date);
+                assert_eq!(
+                    jelal::Date::from(date.clone()),
+                    jelal::Date::from(offset(&start, days))
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        for (date, json) in [
+            (
+                Date::from(jelal::Date::from((1404, 7, 12))),
+                r#"{"calendar":"jalali","year":1404,"month":7,"day":12}"#,
+            ),
+            (
+                Date::from(civil::date(2024, 2, 29)),
+                r#"{"calendar":"gregorian","year":2024,"month":2,"day":29}"#,
+            ),
+        ] {
+            assert_eq!(serde_json::to_string(&date).unwrap(), json);
+            let back: Date = serde_json::from_str(json).unwrap();
+            assert_eq!(back.kind(), date.kind());
+            assert_eq!(back, date);
+        }
+
+        let parse = |s: &str| serde_json::from_str::<Date>(s);
+        let date = parse(r#"{"calendar":"Jalali","year":1403,"month":12,"day":30}"#).unwrap();
+        assert_eq!(date, Date::from(jelal::Date::from((1403, 12, 30))));
+        assert!(parse(r#"{"calendar":"GREGORIAN","year":2025,"month":1,"day":1}"#).is_ok());
+
+        assert!(parse(r#"{"calendar":"jalali","year":1404,"month":13,"day":1}"#).is_err());
+        assert!(parse(r#"{"calendar":"gregorian","year":2025,"month":0,"day":1}"#).is_err());
+        assert!(parse(r#"{"calendar":"jalali","year":1404,"month":12,"day":30}"#).is_err());
+        assert!(parse(r#"{"calendar":"julian","year":2025,"month":1,"day":1}"#).is_err());
+    }
+
+    #[test]
+    fn test_hijri_conversions() {
+        // 1 Ramadan 1446 (tabular) is Saturday, 1403/12/11 and 2025-03-01
+        let hijri = Date::from(hijri::Date::new(1446, 9, 1).unwrap());
+        let jalali = Date::from(jelal::Date::from((1403, 12, 11)));
+        let gregorian = Date::from(civil::date(2025, 3, 1));
+        assert_eq!(hijri, jalali);
+        assert_eq!(gregorian, hijri);
+        assert_eq!(hijri.cmp(&jalali), std::cmp::Ordering::Equal);
+        assert!(hijri < Date::from(civil::date(2025, 3, 2)));
+
+        assert_eq!(jalali.to_hijri().kind(), CalendarKind::Hijri);
+        assert_eq!(gregorian.convert_to(CalendarKind::Hijri).day(), 1);
+        assert_eq!(hijri.to_gregorian().to_string(), "2025-03-01");
+        assert_eq!(hijri.to_jalali().to_string(), "1403/12/11 (Jalali)");
+        assert_eq!(hijri.weekday(), Weekday::SAT);
+        assert_eq!(hijri.month_name(), "Ramadan");
+        assert_eq!(hijri.month_names()[11], "Dhu al-Hijjah");
+
+        assert_eq!(hijri.to_string(), "1446/09/01 (Hijri)");
+        assert_eq!(
+            "1446/09/01 (Hijri)".parse::<Date>().unwrap().kind(),
+            CalendarKind::Hijri
+        );
+        assert_eq!("hijri:1446/9/1".parse::<Date>().unwrap(), gregorian);
+        assert!("hijri:1446/12/30".parse::<Date>().is_err());
+        assert!(Date::from_ymd(CalendarKind::Hijri, 1445, 12, 30).is_ok());
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(jalali);
+        assert!(set.contains(&hijri));
+    }
+
+    #[test]
+    fn test_days_offset_enum() {
+        let date = Date::from(civil::date(2024, 12, 31));
+        assert_eq!(offset(&date, 1), Date::from(civil::date(2025, 1, 1)));
+        assert_eq!(date.days_until(&Date::from(civil::date(2025, 1, 1))), 1);
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        let jalali = Date::from(jelal::Date::from((1404, 7, 12)));
+        let gregorian = Date::from(civil::date(2025, 10, 4));
+        assert_eq!(jalali.to_string(), "1404/07/12 (Jalali)");
+        assert_eq!(gregorian.to_string(), "2025-10-04");
+
+        for date in [jalali.clone(), gregorian.clone()] {
+            let back: Date = date.to_string().parse().unwrap();
+            assert_eq!(back.kind(), date.kind());
+            assert_eq!(back, date);
+        }
+
+        let parse = |s: &str| s.parse::<Date>();
+        let date = parse("jalali:1404/07/12").unwrap();
+        assert_eq!(date.kind(), CalendarKind::Jalali);
+        assert_eq!(date, jalali);
+        let date = parse("Gregorian:2025-10-04").unwrap();
+        assert_eq!(date.kind(), CalendarKind::Gregorian);
+        assert_eq!(date, gregorian);
+        let date = parse("2025/10/04").unwrap();
+        assert_eq!(date.kind(), CalendarKind::Gregorian);
+        assert_eq!(date, gregorian);
+
+        assert!(parse("jalali:1403/12/30").is_ok());
+        assert!(parse("jalali:1404/12/30").is_err());
+        assert!(parse("jalali:1404/13/01").is_err());
+        assert!(parse("2025-02-29").is_err());
+        assert!(parse("julian:2025-10-04").is_err());
+        assert!(parse("10/04/2025").is_err());
+    }
+
+    #[test]
+    fn test_iso_week() {
+        let iso = |date: Date| (date.iso_year(), date.iso_weeknum());
+        let gregorian = |y, m, d| Date::from(civil::date(y, m, d));
+        let jalali = |y, m, d| Date::from(jelal::Date::from((y, m, d)));
+
+        assert_eq!(iso(gregorian(2025, 12, 29)), (2026, 1));
+        assert_eq!(iso(gregorian(2025, 12, 28)), (2025, 52));
+        assert_eq!(iso(gregorian(2027, 1, 1)), (2026, 53));
+        assert_eq!(iso(gregorian(2026, 12, 31)), (2026, 53));
+        assert_eq!(iso(gregorian(2025, 11, 20)), (2025, 47));
+
+        // 1402 starts on a Tuesday, 1403 (leap) on a Wednesday and ends on a Thursday
+        assert_eq!(iso(jalali(1402, 12, 29)), (1403, 1));
+        assert_eq!(iso(jalali(1403, 12, 30)), (1403, 53));
+        assert_eq!(iso(jalali(1404, 1, 1)), (1403, 53));
+        assert_eq!(iso(jalali(1405, 1, 1)), (1404, 52));
+
+        // the previous year is found by its length alone
+        let mut date = civil::date(2015, 1, 1);
+        while date.year() < 2031 {
+            let expected = date.iso_week_date();
+            assert_eq!(
+                iso(Date::from(date)),
+                (expected.year() as IYear, expected.week() as u8),
+                "{}",
+                date
+            );
+            date = date.tomorrow().unwrap();
+        }
+        assert_eq!(gregorian(2025, 6, 1).year_end_ordinal_of(2024), 366);
+        assert_eq!(jalali(1404, 6, 1).year_end_ordinal_of(1403), 366);
+
+        let date = gregorian(2025, 6, 1);
+        assert_eq!(date.weeks_in_year(Weekday::SUN), 52);
+        assert_eq!(date.weeknum(Weekday::SUN), 22);
+        assert_eq!(date.first_weekday_of_year(), Weekday::WED);
+        assert_eq!(gregorian(2023, 1, 1).weeks_in_year(Weekday::SUN), 53);
+    }
+
+    #[test]
+    fn test_first_weekday_of_year() {
+        let constructed = |date: &Date| {
+            let mut new_year = date.clone();
+            new_year.set_saturating_ordinal(1);
+            new_year.weekday()
+        };
+        for mut date in [
+            Date::from(civil::date(2024, 1, 1)),
+            Date::from(jelal::Date::from((1403, 1, 1))),
+            Date::Hijri(hijri::Date::from((1446, 1, 1))),
+        ] {
+            // every day of a leap year and the next one
+            for _ in 0..(366 + 365) {
+                assert_eq!(
+                    date.first_weekday_of_year(),
+                    constructed(&date),
+                    "{:?}",
+                    date
+                );
+                date.set_saturating_days_offset(1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_date_range() {
+        let jalali = |y, m, d| Date::from(jelal::Date::from((y, m, d)));
+        let gregorian = |y, m, d| Date::from(civil::date(y, m, d));
+
+        // Esfand 1403 has 30 days
+        let range = DateRange::new(jalali(1403, 12, 28), jalali(1404, 1, 2));
+        assert_eq!(range.len(), 5);
+        assert_eq!(
+            range.map(|i| i.to_string()).collect::<Vec<_>>(),
+            [
+                "1403/12/28 (Jalali)",
+                "1403/12/29 (Jalali)",
+                "1403/12/30 (Jalali)",
+                "1404/01/01 (Jalali)",
+                "1404/01/02 (Jalali)",
+            ]
+        );
+
+        // empty and single
+        let mut range = DateRange::new(jalali(1404, 1, 2), jalali(1404, 1, 1));
+        assert_eq!(range.len(), 0);
+        assert_eq!(range.next(), None);
+        assert_eq!(
+            DateRange::new(jalali(1404, 1, 1), jalali(1404, 1, 1)).len(),
+            1
+        );
+
+        // the calendar of the start is kept
+        let range = DateRange::new(jalali(1404, 1, 1), gregorian(2025, 3, 22));
+        assert_eq!(range.len(), 2);
+        assert!(range.clone().all(|i| i.kind() == CalendarKind::Jalali));
+        assert_eq!(range.last(), Some(jalali(1404, 1, 2)));
+
+        // Fridays of January
+        let mut range =
+            DateRange::with_step(gregorian(2025, 1, 3), gregorian(2025, 1, 31), Step::Days(7));
+        assert_eq!(range.len(), 5);
+        range.next();
+        assert_eq!(range.len(), 4);
+        assert!(range.all(|i| i.weekday() == Weekday::FRI));
+
+        // months keep the day of the start
+        let range = DateRange::with_step(jalali(1403, 6, 31), jalali(1404, 1, 1), Step::Months(1));
+        assert_eq!(
+            range.map(|i| i.day()).collect::<Vec<_>>(),
+            [31, 30, 30, 30, 30, 30, 30]
+        );
+        let range = DateRange::with_step(jalali(1403, 1, 1), jalali(1404, 1, 1), Step::Months(6));
+        assert_eq!(range.len(), 3);
+    }
+
+    #[test]
+    fn test_quarters() {
+        // (month, quarter, half year) at every quarter boundary
+        let boundaries = [
+            (1, 1, 1),
+            (3, 1, 1),
+            (4, 2, 1),
+            (6, 2, 1),
+            (7, 3, 2),
+            (9, 3, 2),
+            (10, 4, 2),
+            (12, 4, 2),
+        ];
+        for calendar in [CalendarKind::Jalali, CalendarKind::Gregorian] {
+            for (month, quarter, half) in boundaries {
+                let mut date = Date::from_ymd(calendar, 1404, month, 20).unwrap();
+                assert_eq!(date.quarter(), quarter, "{}", date);
+                assert_eq!(date.half_year(), half, "{}", date);
+
+                date.set_saturating_quarter_start(quarter);
+                assert_eq!(
+                    (date.month(), date.day()),
+                    ((quarter - 1) * 3 + 1, 1),
+                    "{}",
+                    date
+                );
+            }
+        }
+
+        // Esfand 30 to the start of the year
+        let mut date = jelal::Date::from((1403, 12, 30));
+        assert_eq!(CommonDate::quarter(&date), 4);
+        date.set_saturating_quarter_start(0);
+        assert_eq!(date, jelal::Date::from((1403, 1, 1)));
+        date.set_saturating_quarter_start(9);
+        assert_eq!(date, jelal::Date::from((1403, 10, 1)));
+
+        // the same day is in different quarters
+        let date = Date::from(civil::date(2025, 3, 1));
+        assert_eq!((date.quarter(), date.to_jalali().quarter()), (1, 4));
+    }
+
+    #[test]
+    fn test_set_saturating_weeknum() {
+        fn check<T: CommonDate + Clone + std::fmt::Debug>(date: T) {
+            for base in (0..7).map(|i| Weekday::SUN.forward(i)) {
+                let weeks = date.weeks_in_year(base.clone());
+                let first = if date.first_weekday_of_year() == base {
+                    1
+                } else {
+                    0
+                };
+                for week in first..=weeks {
+                    // the weekday of the current date must not matter
+                    for days in 0..7 {
+                        let mut v = date.clone();
+                        v.set_saturating_days_offset(days);
+                        v.set_saturating_weeknum(week as usize, base.clone());
+                        assert_eq!(v.weeknum(base.clone()), week, "{:?} {:?}", v, base);
+                        assert_eq!(v.year(), date.year());
+                        if week == 0 {
+                            assert_eq!(v.ordinal(), 1);
+                        } else {
+                            assert_eq!(v.weekday(), base);
+                        }
+                    }
+                }
+            }
+        }
+
+        for year in 1400..=1410 {
+            check(jelal::Date::from((year, 6, 10)));
+        }
+        for year in 2020..=2030 {
+            check(civil::date(year as i16, 6, 10));
+        }
+
+        // 2025 starts on Wednesday so the week 22 (Sunday based) starts on June 1
+        let mut date = civil::date(2025, 5, 31);
+        date.set_saturating_weeknum(22, Weekday::SUN);
+        assert_eq!(date, civil::date(2025, 6, 1));
+        date.set_saturating_weeknum(60, Weekday::SUN);
+        assert_eq!(date, civil::date(2025, 12, 31));
+    }
+
+    #[test]
+    fn test_set_saturating_weekday() {
+        let weekday = |mut date: Date, target, base| {
+            date.set_saturating_weekday(target, base);
+            date
+        };
+        let gregorian = |y, m, d| Date::from(civil::date(y, m, d));
+
+        // a Thursday to an earlier and a later day of its week
+        let date = gregorian(2025, 6, 5);
+        assert_eq!(
+            weekday(date.clone(), Weekday::MON, Weekday::SUN),
+            gregorian(2025, 6, 2)
+        );
+        assert_eq!(
+            weekday(date.clone(), Weekday::SAT, Weekday::SUN),
+            gregorian(2025, 6, 7)
+        );
+        assert_eq!(weekday(date.clone(), Weekday::THU, Weekday::SUN), date);
+        // with Friday as the start, the Thursday is the last day of the week
+        assert_eq!(
+            weekday(date.clone(), Weekday::MON, Weekday::FRI),
+            gregorian(2025, 6, 2)
+        );
+        assert_eq!(
+            weekday(date.clone(), Weekday::FRI, Weekday::FRI),
+            gregorian(2025, 5, 30)
+        );
+
+        // weeks split between years
+        let date = gregorian(2025, 12, 30);
+        assert_eq!(
+            weekday(date.clone(), Weekday::SAT, Weekday::SUN),
+            gregorian(2025, 12, 31)
+        );
+        assert_eq!(
+            weekday(date, Weekday::SUN, Weekday::SUN),
+            gregorian(2025, 12, 28)
+        );
+        let date = gregorian(2025, 1, 2);
+        assert_eq!(
+            weekday(date, Weekday::SUN, Weekday::SUN),
+            gregorian(2025, 1, 1)
+        );
+
+        // 1403/12/30 is a Thursday and the last day of the year
+        let date = Date::from(jelal::Date::from((1403, 12, 30)));
+        assert_eq!(weekday(date.clone(), Weekday::FRI, Weekday::SAT), date);
+        assert_eq!(
+            weekday(date, Weekday::SAT, Weekday::SAT),
+            Date::from(jelal::Date::from((1403, 12, 25)))
+        );
+    }
+
+    #[test]
+    fn test_set_year() {
+        let mut v = civil::date(2024, 2, 29);
+        CommonDate::set_saturating_year(&mut v, 1900);
+        assert_eq!(v, civil::date(1900, 2, 28));
+        let mut v = civil::date(2024, 2, 29);
+        CommonDate::set_saturating_year(&mut v, 2028);
+        assert_eq!(v, civil::date(2028, 2, 29));
+
+        let mut v = jelal::Date::from((1403, 12, 30));
+        CommonDate::set_saturating_year(&mut v, 1404);
+        assert_eq!(v, jelal::Date::from((1404, 12, 29)));
+        let mut v = jelal::Date::from((1403, 7, 15));
+        CommonDate::set_saturating_year(&mut v, 1350);
+        assert_eq!(v, jelal::Date::from((1350, 7, 15)));
+
+        // out of range years are reported and change nothing
+        let mut v = Date::from(civil::date(2024, 2, 29));
+        assert_eq!(v.try_set_year(99999), Err(9999));
+        assert_eq!(v, Date::from(civil::date(2024, 2, 29)));
+        assert_eq!(v.try_set_year(2025), Ok(()));
+        assert_eq!(v, Date::from(civil::date(2025, 2, 28)));
+        v.set_saturating_year(-99999);
+        assert_eq!(v.year(), -9999);
+    }
+
+    #[test]
+    fn test_hash_across_calendars() {
+        use std::collections::HashSet;
+
+        let nowruz = Date::from(jelal::Date::from((1404, 1, 1)));
+        let mut set = HashSet::new();
+        assert!(set.insert(nowruz.clone()));
+        assert!(!set.insert(Date::from(civil::date(2025, 3, 21))));
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&Date::from(civil::date(2025, 3, 21))));
+
+        // the days around it are different
+        assert!(!set.contains(&Date::from(civil::date(2025, 3, 20))));
+        assert!(!set.contains(&Date::from(jelal::Date::from((1404, 1, 2)))));
+        assert!(set.insert(Date::from(jelal::Date::from((1403, 12, 30)))));
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,188 @@
+//! ISO 8601 durations and calendar aware differences and adjustments.
+//!
+//! Years and months are the only calendar dependent units so in Jalali they are applied on the
+//! Jalali date and the rest of a [`Span`] is left to [`jiff`].
+
+use jelal::IDayDiff;
+use jiff::{Span, Unit, Zoned, civil, fmt::temporal::SpanParser};
+
+use crate::date::CommonDate;
+
+const SPAN_PARSER: SpanParser = SpanParser::new();
+
+/// Parse an ISO 8601 duration (e.g. `P2Y3M5DT4H` or `-P1W`).
+pub fn parse_iso(s: &str) -> Result<Span, jiff::Error> {
+    SPAN_PARSER.parse_span(s)
+}
+
+/// Serialize to an ISO 8601 duration (e.g. `P2Y3M5DT4H`).
+///
+/// The sign comes before the `P` and zero components are omitted (`PT0S` if all are zero).
+/// Sub-second units are merged into a fraction of the seconds.
+pub fn to_iso(span: &Span) -> String {
+    let negative = span.signum() < 0;
+    let span = span.abs();
+
+    let mut date = String::new();
+    for (value, designator) in [
+        (span.get_years() as i64, 'Y'),
+        (span.get_months() as i64, 'M'),
+        (span.get_weeks() as i64, 'W'),
+        (span.get_days() as i64, 'D'),
+    ] {
+        if value != 0 {
+            date.push_str(&format!("{}{}", value, designator));
+        }
+    }
+
+    let nanoseconds = span.get_milliseconds() as i128 * 1_000_000
+        + span.get_microseconds() as i128 * 1_000
+        + span.get_nanoseconds() as i128;
+    let seconds = span.get_seconds() as i128 + nanoseconds / 1_000_000_000;
+    let fraction = nanoseconds % 1_000_000_000;
+
+    let mut time = String::new();
+    for (value, designator) in [
+        (span.get_hours() as i128, 'H'),
+        (span.get_minutes() as i128, 'M'),
+    ] {
+        if value != 0 {
+            time.push_str(&format!("{}{}", value, designator));
+        }
+    }
+    if fraction != 0 {
+        let fraction = format!("{:09}", fraction);
+        time.push_str(&format!("{}.{}S", seconds, fraction.trim_end_matches('0')));
+    } else if seconds != 0 {
+        time.push_str(&format!("{}S", seconds));
+    }
+
+    if date.is_empty() && time.is_empty() {
+        return "PT0S".to_owned();
+    }
+    let mut v = if negative { "-P" } else { "P" }.to_owned();
+    v.push_str(&date);
+    if !time.is_empty() {
+        v.push('T');
+        v.push_str(&time);
+    }
+    v
+}
+
+/// Move the date by whole months in Jalali keeping the time (the day saturates to the month end).
+fn add_jalali_months(zoned: &Zoned, months: IDayDiff) -> Result<Zoned, jiff::Error> {
+    if months == 0 {
+        return Ok(zoned.clone());
+    }
+    let mut date = jelal::Date::from(zoned.date());
+    let day = CommonDate::day(&date);
+    CommonDate::set_saturating_months_offset(&mut date, months);
+    CommonDate::set_saturating_day(&mut date, day);
+    let date: civil::Date = date.try_into()?;
+    zoned.with().date(date).build()
+}
+
+/// Add the span where years and months are counted in the given calendar.
+pub fn checked_add(zoned: &Zoned, span: Span, jalali: bool) -> Result<Zoned, jiff::Error> {
+    if !jalali {
+        return zoned.checked_add(span);
+    }
+    let months = span.get_years() as IDayDiff * 12 + span.get_months() as IDayDiff;
+    add_jalali_months(zoned, months)?.checked_add(span.years(0).months(0))
+}
+
+/// The span from one time to the other with years and months counted in the given calendar.
+///
+/// The result is negative if `to` is before `from` and `checked_add(from, span)` gives `to`.
+pub fn until(from: &Zoned, to: &Zoned, jalali: bool) -> Result<Span, jiff::Error> {
+    if !jalali {
+        return from.until((Unit::Year, to));
+    }
+
+    let (a, b) = (jelal::Date::from(from.date()), jelal::Date::from(to.date()));
+    let mut months = (CommonDate::year(&b) - CommonDate::year(&a)) as IDayDiff * 12
+        + CommonDate::month(&b) as IDayDiff
+        - CommonDate::month(&a) as IDayDiff;
+    let sign = if to >= from { 1 } else { -1 };
+
+    // days and times are not counted above so this may overshoot `to` by a month at most
+    let mut anchor = add_jalali_months(from, months)?;
+    while months != 0 && ((sign > 0 && anchor > *to) || (sign < 0 && anchor < *to)) {
+        months -= sign;
+        anchor = add_jalali_months(from, months)?;
+    }
+
+    let rest = anchor.until((Unit::Day, to))?;
+    Ok(rest.years(months / 12).months(months % 12))
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::ToSpan;
+
+    use super::*;
+
+    fn at(s: &str) -> Zoned {
+        format!("{}[UTC]", s).parse().unwrap()
+    }
+
+    #[test]
+    fn test_to_iso() {
+        assert_eq!(to_iso(&2.years().months(3).days(5).hours(4)), "P2Y3M5DT4H");
+        assert_eq!(to_iso(&-1.weeks()), "-P1W");
+        assert_eq!(to_iso(&1.minutes()), "PT1M");
+        assert_eq!(to_iso(&1.months()), "P1M");
+        assert_eq!(to_iso(&1.seconds().milliseconds(500)), "PT1.5S");
+        assert_eq!(to_iso(&-1.days().nanoseconds(1)), "-P1DT0.000000001S");
+        assert_eq!(to_iso(&Span::new()), "PT0S");
+    }
+
+    #[test]
+    fn test_parse_iso() {
+        for s in ["P2Y3M5DT4H", "-P1W", "PT1.5S", "PT0S"] {
+            assert_eq!(to_iso(&parse_iso(s).unwrap()), s);
+        }
+        assert!(parse_iso("1 day").is_err());
+        assert!(parse_iso("P").is_err());
+    }
+
+    #[test]
+    fn test_jalali_months_differ() {
+        // Esfand 1403 has 30 days so this is exactly a month in Jalali
+        let from = at("2025-02-19T00:00");
+        let to = at("2025-03-21T00:00");
+        assert_eq!(to_iso(&until(&from, &to, true).unwrap()), "P1M");
+        assert_eq!(to_iso(&until(&from, &to, false).unwrap()), "P1M2D");
+        assert_eq!(to_iso(&until(&to, &from, true).unwrap()), "-P1M");
+        assert_eq!(to_iso(&until(&to, &from, false).unwrap()), "-P1M2D");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let start = at("2025-03-21T00:00"); // 1404/01/01
+        for jalali in [false, true] {
+            for s in ["P2Y3M5DT4H", "P1M", "-P1Y1M", "PT12H", "-P10DT0.5S"] {
+                let span = parse_iso(s).unwrap();
+                let end = checked_add(&start, span, jalali).unwrap();
+                let diff = until(&start, &end, jalali).unwrap();
+                assert_eq!(to_iso(&diff), s, "{} jalali: {}", s, jalali);
+            }
+        }
+
+        // the Jalali months are applied on the Jalali date
+        let end = checked_add(&start, parse_iso("P1M").unwrap(), true).unwrap();
+        assert_eq!(end, at("2025-04-21T00:00")); // 1404/02/01
+        let end = checked_add(&start, parse_iso("P1M").unwrap(), false).unwrap();
+        assert_eq!(end, at("2025-04-21T00:00"));
+        let end = checked_add(&start, parse_iso("P2M").unwrap(), true).unwrap();
+        assert_eq!(end, at("2025-05-22T00:00")); // 1404/03/01
+
+        // the day is kept and saturates to the month end
+        let start = at("2025-09-22T00:00"); // 1404/06/31
+        let end = checked_add(&start, parse_iso("P1M").unwrap(), true).unwrap();
+        assert_eq!(end, at("2025-10-22T00:00")); // 1404/07/30
+        let start = at("2025-05-05T00:00"); // 1404/02/15
+        let end = checked_add(&start, parse_iso("P1M").unwrap(), true).unwrap();
+        assert_eq!(end, at("2025-06-05T00:00")); // 1404/03/15
+    }
+}
@@ -0,0 +1,270 @@
+//! The tabular Islamic calendar (also known as the arithmetic or civil Hijri calendar).
+//!
+//! Months alternate between 30 and 29 days and 11 years of each 30 years cycle are leap with a 30
+//! day Dhu al-Hijjah (see [`hijri_is_leap`]). The observed calendar (and Umm al-Qura) may differ by
+//! a day or two around the start of months since they follow the moon.
+
+use jelal::{IYear, UMonth, UMonthDay, UOrdinal, Weekday};
+use jiff::{Span, Unit, civil};
+
+use crate::date::{CommonDate, hijri_is_leap, hijri_month_len};
+
+/// Days from 1970-01-01 to 1 Muharram 1 (Friday, July 19, 622 in the proleptic Gregorian).
+const EPOCH_DAYS: i64 = -492148;
+
+/// The first year fully in the range of [`civil::Date`].
+pub const MIN_YEAR: IYear = -10946;
+
+/// The last year fully in the range of [`civil::Date`].
+pub const MAX_YEAR: IYear = 9665;
+
+/// A valid date of the tabular Hijri calendar in [`MIN_YEAR`]..=[`MAX_YEAR`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: IYear,
+    month: UMonth,
+    day: UMonthDay,
+}
+
+impl Date {
+    /// A date only if every value is in range.
+    pub fn new(year: IYear, month: UMonth, day: UMonthDay) -> Option<Self> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year)
+            || !(1..=12).contains(&month)
+            || !(1..=hijri_month_len(year, month)).contains(&day)
+        {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    /// Days before the first day of the given month in the year.
+    fn days_before_month(month: UMonth) -> UOrdinal {
+        let month = month as UOrdinal;
+        29 * (month - 1) + month / 2
+    }
+
+    /// Days since 1970-01-01 (negative before it).
+    fn unix_days(&self) -> i64 {
+        let year = self.year as i64;
+        EPOCH_DAYS - 1
+            + (year - 1) * 354
+            + (3 + 11 * year).div_euclid(30)
+            + Self::days_before_month(self.month) as i64
+            + self.day as i64
+    }
+
+    /// The date of the given days since 1970-01-01 saturating to the range.
+    fn from_unix_days(days: i64) -> Self {
+        let year = (30 * (days - EPOCH_DAYS) + 10646).div_euclid(10631);
+        let year = year.clamp(MIN_YEAR as i64, MAX_YEAR as i64) as IYear;
+        let mut date = Self {
+            year,
+            month: 1,
+            day: 1,
+        };
+        let ordinal = days - date.unix_days() + 1;
+        date.set_saturating_ordinal(ordinal.clamp(1, UOrdinal::MAX as i64) as UOrdinal);
+        date
+    }
+
+    fn unix_epoch() -> civil::Date {
+        civil::Date::constant(1970, 1, 1)
+    }
+}
+
+impl Default for Date {
+    /// 1 Muharram 1.
+    fn default() -> Self {
+        Self {
+            year: 1,
+            month: 1,
+            day: 1,
+        }
+    }
+}
+
+impl From<(IYear, UMonth, UMonthDay)> for Date {
+    /// Saturate every value to its range (Dhu al-Hijjah 30 of a common year becomes 29).
+    fn from((year, month, day): (IYear, UMonth, UMonthDay)) -> Self {
+        let mut date = Self::default();
+        date.set_saturating_year(year);
+        date.set_saturating_month(month);
+        date.set_saturating_day(day);
+        date
+    }
+}
+
+impl From<civil::Date> for Date {
+    /// Convert saturating to the first or the last day of the range.
+    fn from(value: civil::Date) -> Self {
+        let days = Self::unix_epoch()
+            .until((Unit::Day, value))
+            .map_or(0, |span| span.get_days() as i64);
+        Self::from_unix_days(days)
+    }
+}
+
+impl From<Date> for civil::Date {
+    fn from(value: Date) -> Self {
+        // the range of the years guarantees a valid date
+        Date::unix_epoch()
+            .checked_add(Span::new().days(value.unix_days()))
+            .unwrap()
+    }
+}
+
+impl From<jelal::Date> for Date {
+    fn from(value: jelal::Date) -> Self {
+        let gregorian: Result<civil::Date, _> = value.clone().try_into();
+        match gregorian {
+            Ok(v) => v.into(),
+            Err(_) if CommonDate::year(&value) < 0 => Self::from((MIN_YEAR, 1, 1)),
+            Err(_) => Self::from((MAX_YEAR, 12, 30)),
+        }
+    }
+}
+
+impl From<Date> for jelal::Date {
+    fn from(value: Date) -> Self {
+        civil::Date::from(value).into()
+    }
+}
+
+impl CommonDate for Date {
+    fn year(&self) -> IYear {
+        self.year
+    }
+
+    fn set_saturating_year(&mut self, year: IYear) {
+        self.year = year.clamp(MIN_YEAR, MAX_YEAR);
+        self.day = self.day.min(self.month_end_day());
+    }
+
+    fn month(&self) -> UMonth {
+        self.month
+    }
+
+    fn set_saturating_month(&mut self, month: UMonth) {
+        self.month = month.clamp(1, 12);
+        self.day = self.day.min(self.month_end_day());
+    }
+
+    fn day(&self) -> UMonthDay {
+        self.day
+    }
+
+    fn set_saturating_day(&mut self, day: UMonthDay) {
+        self.day = day.clamp(1, self.month_end_day());
+    }
+
+    fn ordinal(&self) -> UOrdinal {
+        Self::days_before_month(self.month) + self.day as UOrdinal
+    }
+
+    fn set_saturating_ordinal(&mut self, ordinal: UOrdinal) {
+        let ordinal = ordinal.clamp(1, self.year_end_ordinal());
+        let month = (1..=12)
+            .rev()
+            .find(|m| Self::days_before_month(*m) < ordinal)
+            .unwrap_or(1);
+        self.month = month;
+        self.day = (ordinal - Self::days_before_month(month)) as UMonthDay;
+    }
+
+    fn weekday(&self) -> Weekday {
+        // 1970-01-01 is a Thursday
+        Weekday::THU.forward(self.unix_days().rem_euclid(7) as usize)
+    }
+
+    fn month_end_day(&self) -> UMonthDay {
+        hijri_month_len(self.year, self.month)
+    }
+
+    fn year_end_ordinal_of(&self, year: IYear) -> UOrdinal {
+        if hijri_is_leap(year) { 355 } else { 354 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hijri(year: IYear, month: UMonth, day: UMonthDay) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_anchor_dates() {
+        for (gregorian, expected) in [
+            (civil::date(622, 7, 19), hijri(1, 1, 1)),
+            (civil::date(622, 7, 18), hijri(0, 12, 29)),
+            (civil::date(2025, 3, 1), hijri(1446, 9, 1)),
+            (civil::date(2025, 3, 30), hijri(1446, 9, 30)),
+            (civil::date(2024, 7, 7), hijri(1445, 12, 30)),
+            (civil::date(2025, 10, 16), hijri(1447, 4, 23)),
+        ] {
+            assert_eq!(Date::from(gregorian), expected, "{}", gregorian);
+            assert_eq!(civil::Date::from(expected), gregorian, "{:?}", expected);
+        }
+
+        // 1 Ramadan 1446 is 1403/12/11
+        let jalali = jelal::Date::from((1403, 12, 11));
+        assert_eq!(Date::from(jalali.clone()), hijri(1446, 9, 1));
+        assert_eq!(jelal::Date::from(hijri(1446, 9, 1)), jalali);
+        assert_eq!(hijri(1446, 9, 1).weekday(), Weekday::SAT);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut date = civil::date(1900, 1, 1);
+        let mut previous = Date::from(date);
+        for _ in 0..365 * 250 {
+            date = date.tomorrow().unwrap();
+            let hijri = Date::from(date);
+            assert_eq!(civil::Date::from(hijri), date);
+            assert_eq!(CommonDate::weekday(&hijri), CommonDate::weekday(&date));
+
+            // consecutive days
+            if previous.ordinal() == previous.year_end_ordinal() {
+                assert_eq!((hijri.year(), hijri.ordinal()), (previous.year() + 1, 1));
+            } else {
+                assert_eq!(hijri.ordinal(), previous.ordinal() + 1);
+            }
+            previous = hijri;
+        }
+
+        assert_eq!(Date::from(civil::Date::MIN).year(), MIN_YEAR);
+        assert_eq!(Date::from(civil::Date::MAX).year(), MAX_YEAR);
+    }
+
+    #[test]
+    fn test_common_date() {
+        let mut leaps = 0;
+        for year in 1441..=1470 {
+            let date = hijri(year, 1, 1);
+            let days: UOrdinal = (1..=12).map(|m| hijri_month_len(year, m) as UOrdinal).sum();
+            assert_eq!(days, date.year_end_ordinal());
+            leaps += date.is_leap_year() as usize;
+        }
+        assert_eq!(leaps, 11);
+
+        let mut date = hijri(1445, 12, 30);
+        assert_eq!(date.ordinal(), 355);
+        date.set_saturating_year(1446);
+        assert_eq!(date, hijri(1446, 12, 29));
+        date.set_saturating_ordinal(30);
+        assert_eq!(date, hijri(1446, 1, 30));
+        date.set_saturating_ordinal(31);
+        assert_eq!(date, hijri(1446, 2, 1));
+        date.set_saturating_month(9);
+        date.set_saturating_day(40);
+        assert_eq!(date, hijri(1446, 9, 30));
+        date.set_saturating_months_offset(4);
+        assert_eq!(date, hijri(1447, 1, 1));
+
+        assert_eq!(Date::new(1446, 12, 30), None);
+        assert_eq!(Date::new(1446, 13, 1), None);
+        assert_eq!(Date::from((1446, 12, 30)), hijri(1446, 12, 29));
+    }
+}
@@ -1,6 +1,12 @@
 //! Common utilities for `date` and `cal`.
+pub mod anchors;
+pub mod build_info;
 pub mod clap_helper;
+pub mod config;
 pub mod date;
+pub mod duration;
+pub mod hijri;
+pub mod names;
 pub mod parser;
 pub mod posix;
 pub mod strftime;
@@ -16,6 +22,17 @@ pub const WEEKDAYS: [&str; 7] = [
     "Saturday",
 ];
 
+/// Sunday based weekdays in Persian (transliterated).
+pub const JALALI_WEEKDAYS: [&str; 7] = [
+    "Yekshanbeh",
+    "Doshanbeh",
+    "Seshanbeh",
+    "Chaharshanbeh",
+    "Panjshanbeh",
+    "Jomeh",
+    "Shanbeh",
+];
+
 /// [`WEEKDAYS`] abbreviations to 3 letters.
 pub const WEEKDAYS_ABB: [&str; 7] = abbr_strarr(WEEKDAYS);
 
@@ -59,6 +76,27 @@ pub const JALALI_MONTHS: [&str; 12] = [
 /// [`JALALI_MONTHS`] abbreviations to 3 letters.
 pub const JALALI_MONTHS_ABB: [&str; 12] = abbr_strarr(JALALI_MONTHS);
 
+/// Hijri (lunar) months in English.
+pub const HIJRI_MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi al-Awwal",
+    "Rabi al-Thani",
+    "Jumada al-Awwal",
+    "Jumada al-Thani",
+    "Rajab",
+    "Shaban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qadah",
+    "Dhu al-Hijjah",
+];
+
+/// [`HIJRI_MONTHS`] abbreviations (3 letters would not tell the pairs apart).
+pub const HIJRI_MONTHS_ABB: [&str; 12] = [
+    "Muh", "Saf", "Ra1", "Ra2", "Ju1", "Ju2", "Raj", "Sha", "Ram", "Shw", "DhQ", "DhH",
+];
+
 /// Abbreviate to 3 letters.
 const fn abbr_strarr<const N: usize>(original: [&str; N]) -> [&str; N] {
     const CHARS: usize = 3;
@@ -0,0 +1,248 @@
+//! Names of months and weekdays in each locale (see [`crate::config::LOCALES`]).
+//!
+//! Everything that prints or parses a name asks a [`NameProvider`] so a new locale is one more
+//! table in [`NameProvider::ALL`]. Only [`NameProvider::DEFAULT`] is printed for now while the
+//! parsers accept the names of every locale.
+
+use jelal::{UMonth, Weekday};
+
+use crate::{
+    GREGORIAN_MONTHS, GREGORIAN_MONTHS_ABB, HIJRI_MONTHS, HIJRI_MONTHS_ABB, JALALI_MONTHS,
+    JALALI_MONTHS_ABB, JALALI_WEEKDAYS, WEEKDAYS, WEEKDAYS_ABB, date::CalendarKind,
+};
+
+/// Month and weekday names of a locale.
+///
+/// Weekdays are Sunday based like [`Weekday`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NameProvider {
+    /// One of [`crate::config::LOCALES`].
+    pub locale: &'static str,
+    gregorian_months: &'static [&'static str; 12],
+    gregorian_months_abb: &'static [&'static str; 12],
+    jalali_months: &'static [&'static str; 12],
+    jalali_months_abb: &'static [&'static str; 12],
+    hijri_months: &'static [&'static str; 12],
+    hijri_months_abb: &'static [&'static str; 12],
+    weekdays: &'static [&'static str; 7],
+    weekdays_abb: &'static [&'static str; 7],
+    jalali_weekdays: &'static [&'static str; 7],
+}
+
+impl NameProvider {
+    /// English with the Persian names transliterated.
+    pub const EN: Self = Self {
+        locale: "en",
+        gregorian_months: &GREGORIAN_MONTHS,
+        gregorian_months_abb: &GREGORIAN_MONTHS_ABB,
+        jalali_months: &JALALI_MONTHS,
+        jalali_months_abb: &JALALI_MONTHS_ABB,
+        hijri_months: &HIJRI_MONTHS,
+        hijri_months_abb: &HIJRI_MONTHS_ABB,
+        weekdays: &WEEKDAYS,
+        weekdays_abb: &WEEKDAYS_ABB,
+        jalali_weekdays: &JALALI_WEEKDAYS,
+    };
+
+    /// Persian (month names are not abbreviated).
+    pub const FA: Self = Self {
+        locale: "fa",
+        gregorian_months: Self::FA_GREGORIAN_MONTHS,
+        gregorian_months_abb: Self::FA_GREGORIAN_MONTHS,
+        jalali_months: Self::FA_JALALI_MONTHS,
+        jalali_months_abb: Self::FA_JALALI_MONTHS,
+        hijri_months: Self::FA_HIJRI_MONTHS,
+        hijri_months_abb: Self::FA_HIJRI_MONTHS,
+        weekdays: Self::FA_WEEKDAYS,
+        weekdays_abb: &["ی", "د", "س", "چ", "پ", "ج", "ش"],
+        jalali_weekdays: Self::FA_WEEKDAYS,
+    };
+
+    const FA_GREGORIAN_MONTHS: &[&str; 12] = &[
+        "ژانویه",
+        "فوریه",
+        "مارس",
+        "آوریل",
+        "مه",
+        "ژوئن",
+        "ژوئیه",
+        "اوت",
+        "سپتامبر",
+        "اکتبر",
+        "نوامبر",
+        "دسامبر",
+    ];
+
+    const FA_JALALI_MONTHS: &[&str; 12] = &[
+        "فروردین",
+        "اردیبهشت",
+        "خرداد",
+        "تیر",
+        "مرداد",
+        "شهریور",
+        "مهر",
+        "آبان",
+        "آذر",
+        "دی",
+        "بهمن",
+        "اسفند",
+    ];
+
+    const FA_HIJRI_MONTHS: &[&str; 12] = &[
+        "محرم",
+        "صفر",
+        "ربیع\u{200c}الاول",
+        "ربیع\u{200c}الثانی",
+        "جمادی\u{200c}الاول",
+        "جمادی\u{200c}الثانی",
+        "رجب",
+        "شعبان",
+        "رمضان",
+        "شوال",
+        "ذی\u{200c}القعده",
+        "ذی\u{200c}الحجه",
+    ];
+
+    const FA_WEEKDAYS: &[&str; 7] = &[
+        "یکشنبه",
+        "دوشنبه",
+        "سه\u{200c}شنبه",
+        "چهارشنبه",
+        "پنجشنبه",
+        "جمعه",
+        "شنبه",
+    ];
+
+    /// Every locale (see [`crate::config::LOCALES`] for their keys).
+    pub const ALL: &[Self] = &[Self::EN, Self::FA];
+
+    /// The names that are printed.
+    pub const DEFAULT: Self = Self::EN;
+
+    /// The provider of the given locale if known.
+    pub fn get(locale: &str) -> Option<Self> {
+        Self::ALL.iter().find(|i| i.locale == locale).copied()
+    }
+
+    /// The month names of the given calendar (January or Farvardin first).
+    pub const fn month_names(&self, calendar: CalendarKind) -> &'static [&'static str; 12] {
+        match calendar {
+            CalendarKind::Jalali => self.jalali_months,
+            CalendarKind::Gregorian => self.gregorian_months,
+            CalendarKind::Hijri => self.hijri_months,
+        }
+    }
+
+    /// The abbreviated month names of the given calendar.
+    pub const fn month_abbreviations(&self, calendar: CalendarKind) -> &'static [&'static str; 12] {
+        match calendar {
+            CalendarKind::Jalali => self.jalali_months_abb,
+            CalendarKind::Gregorian => self.gregorian_months_abb,
+            CalendarKind::Hijri => self.hijri_months_abb,
+        }
+    }
+
+    /// The name of a month (1..=12) of the given calendar.
+    pub fn month_name(&self, calendar: CalendarKind, month: UMonth) -> &'static str {
+        self.month_names(calendar)[month as usize - 1]
+    }
+
+    /// The weekday names.
+    pub const fn weekdays(&self) -> &'static [&'static str; 7] {
+        self.weekdays
+    }
+
+    /// The abbreviated weekday names.
+    pub const fn weekdays_abb(&self) -> &'static [&'static str; 7] {
+        self.weekdays_abb
+    }
+
+    /// The Persian weekday names written in this locale (`Jomeh` or `جمعه`).
+    pub const fn jalali_weekdays(&self) -> &'static [&'static str; 7] {
+        self.jalali_weekdays
+    }
+
+    /// The name of a weekday.
+    pub fn weekday(&self, weekday: Weekday) -> &'static str {
+        self.weekdays[weekday.get() as usize]
+    }
+
+    /// Find the index of a full name in the lists of every locale, ignoring the case and the
+    /// spaces and zero width non-joiners (`سه شنبه` is the same as `سه‌شنبه`).
+    pub fn position_in_any<F>(name: &str, list: F) -> Option<usize>
+    where
+        F: Fn(&Self) -> &'static [&'static str],
+    {
+        let normalize = |s: &str| -> String {
+            s.chars()
+                .filter(|c| !c.is_whitespace() && *c != '\u{200c}')
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let name = normalize(name);
+        Self::ALL
+            .iter()
+            .find_map(|provider| list(provider).iter().position(|i| normalize(i) == name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_locale_is_complete() {
+        let locales: Vec<_> = NameProvider::ALL.iter().map(|i| i.locale).collect();
+        assert_eq!(locales, crate::config::LOCALES);
+        assert_eq!(crate::config::LOCALES, ["en", "fa"]);
+
+        for provider in NameProvider::ALL {
+            assert_eq!(NameProvider::get(provider.locale), Some(*provider));
+
+            let mut lists: Vec<&[&str]> = vec![
+                provider.weekdays(),
+                provider.weekdays_abb(),
+                provider.jalali_weekdays(),
+            ];
+            for calendar in [
+                CalendarKind::Jalali,
+                CalendarKind::Gregorian,
+                CalendarKind::Hijri,
+            ] {
+                assert_eq!(provider.month_names(calendar).len(), 12);
+                assert_eq!(provider.month_abbreviations(calendar).len(), 12);
+                lists.push(provider.month_names(calendar));
+                lists.push(provider.month_abbreviations(calendar));
+                assert_eq!(
+                    provider.month_name(calendar, 12),
+                    provider.month_names(calendar)[11]
+                );
+            }
+            for list in lists {
+                assert!(list.len() == 7 || list.len() == 12);
+                for name in list {
+                    assert!(!name.trim().is_empty(), "{}: {:?}", provider.locale, list);
+                }
+            }
+            assert_eq!(provider.weekday(Weekday::SUN), provider.weekdays()[0]);
+        }
+        assert_eq!(NameProvider::get("de"), None);
+    }
+
+    #[test]
+    fn test_position_in_any() {
+        let jalali =
+            |p: &NameProvider| -> &'static [&'static str] { p.month_names(CalendarKind::Jalali) };
+        assert_eq!(NameProvider::position_in_any("فروردین", jalali), Some(0));
+        assert_eq!(NameProvider::position_in_any("ESFAND", jalali), Some(11));
+        assert_eq!(NameProvider::position_in_any("Esf", jalali), None);
+        assert_eq!(NameProvider::position_in_any("ژانویه", jalali), None);
+
+        let weekdays = |p: &NameProvider| -> &'static [&'static str] { p.weekdays() };
+        assert_eq!(NameProvider::position_in_any("سه شنبه", weekdays), Some(2));
+        assert_eq!(
+            NameProvider::position_in_any("سه\u{200c}شنبه", weekdays),
+            Some(2)
+        );
+    }
+}
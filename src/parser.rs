@@ -1,9 +1,13 @@
 //! Holds date and time parsers.
 
 use jelal::{IYear, Month, UMonth, UMonthDay, UWeekday, Weekday};
-use jiff::{Zoned, fmt::strtime::BrokenDownTime};
+use jiff::{
+    Timestamp, Zoned,
+    civil::{self, ISOWeekDate, Time},
+    fmt::{rfc2822, strtime::BrokenDownTime},
+};
 
-use crate::{GREGORIAN_MONTHS, JALALI_MONTHS, WEEKDAYS, posix};
+use crate::{anchors, clap_helper::StaticMap, date::CalendarKind, names::NameProvider, posix};
 
 /// Parse a stirng with multiple strategies to see if one makes sense.
 ///
@@ -14,34 +18,74 @@ use crate::{GREGORIAN_MONTHS, JALALI_MONTHS, WEEKDAYS, posix};
 ///
 /// This is as close as it gets to `parse_datetime`.
 // TODO `now` should be a &Zoned instead of owned
-pub fn parse_datetime(mut s: &str, now: Option<Zoned>) -> Result<Zoned, jiff::Error> {
+pub fn parse_datetime(s: &str, now: Option<Zoned>) -> Result<Zoned, jiff::Error> {
+    parse_datetime_with_anchors(s, now, false)
+}
+
+/// Like [`parse_datetime`] but the named days of [`anchors`] (e.g. `next nowruz`) are tried first.
+///
+/// These are Jalali days so only the Jalali inputs should read them.
+pub fn parse_jalali_datetime(s: &str, now: Option<Zoned>) -> Result<Zoned, jiff::Error> {
+    parse_datetime_with_anchors(s, now, true)
+}
+
+fn parse_datetime_with_anchors(
+    s: &str,
+    now: Option<Zoned>,
+    with_anchors: bool,
+) -> Result<Zoned, jiff::Error> {
     let mut now = now.unwrap_or_else(|| Zoned::now());
 
-    // split the timezone here since posix parser doesn't support it.
+    // split the timezone here (wherever it is) since posix parser doesn't support it.
     // This also relaxes whitespaces inside quotes:
     // https://github.com/uutils/parse_datetime/issues/240
-    if let (Some(tz), rest) = posix::parse_timezone(s) {
-        s = rest;
+    let (tz, rest) = posix::parse_timezone(s)?;
+    if let Some(tz) = tz {
         now = now.with_time_zone(tz);
     }
+    // the lengths below are of the stamp alone
+    let s = rest.as_ref().trim();
+
+    if let Some(date) = with_anchors
+        .then(|| anchors::parse(s, &jelal::Date::from(now.date())))
+        .flatten()
+    {
+        return civil::Date::try_from(date)?
+            .to_datetime(Time::midnight())
+            .to_zoned(now.time_zone().clone());
+    }
 
-    let posix = {
-        posix::DateTime::parse_loose(s, false, now.month() as u8, now.day() as u8)
-            .or_else(|_| posix::DateTime::parse_loose(s, true, now.month() as u8, now.day() as u8))
+    let loose = |prioritize_trailing| {
+        let options = posix::ParseOptions {
+            prioritize_trailing,
+            ..Default::default()
+        };
+        posix::DateTime::parse_loose(s, options, now.month() as u8, now.day() as u8)
+    };
+    // Bare dates (no ".SS"): 6 digits are always "YYMMDD" which loose parsing agrees with. 8 digits
+    // are "MMDDhhmm" first and only if the month is invalid (e.g. "20251104"), "CCYYMMDD".
+    let bare = !s.contains('.');
+    let posix = match s.len() {
+        6 if bare => posix::DateTime::parse_date_only(s),
+        _ => loose(false).or_else(|_| loose(true)),
+    };
+    let posix = match posix {
+        Err(posix::Error::OutOfRange {
+            field: posix::Field::Month,
+            ..
+        }) if bare && s.len() == 8 => posix::DateTime::parse_date_only(s),
+        v => v,
     };
 
     // first try posix and then go for relative, else absolute
     match posix {
-        Ok(tm) => {
-            let second_is_none = tm.second.is_none();
-            tm.to_datetime(now.year()).and_then(|i| {
-                match second_is_none {
-                    // reset the second to what it was before if forcefully was set to 0
-                    true => i.with().second(now.second()).build().unwrap(),
-                    false => i,
-                }
-                .to_zoned(now.time_zone().clone())
-            })
+        Ok(mut tm) => {
+            // keep the second of now if not given (`now` out of the POSIX range has nothing to give)
+            if let Ok(now_tm) = posix::DateTime::try_from(&now) {
+                tm.fill_missing_from(&now_tm);
+            }
+            tm.to_datetime(now.year())
+                .and_then(|i| i.to_zoned(now.time_zone().clone()))
         }
         Err(_) => {
             let tz = now.time_zone().clone();
@@ -53,13 +97,120 @@ pub fn parse_datetime(mut s: &str, now: Option<Zoned>) -> Result<Zoned, jiff::Er
     }
 }
 
+/// A single grammar to parse with instead of the heuristics of [`parse_datetime`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Grammar {
+    /// "2025-11-07T10:30:05+03:30" with an optional fraction and "Z" for UTC.
+    Rfc3339,
+    /// "Fri, 07 Nov 2025 10:30:05 +0330".
+    Rfc2822,
+    /// "[[CC]YY]MMDDhhmm[.SS]" and the other forms of [`posix::DateTime::parse`].
+    Posix,
+    /// "2025-W45-5" (ISO 8601 week date, midnight).
+    IsoWeek,
+}
+
+impl Grammar {
+    pub const PARSER_MAP: StaticMap<&'static Self> = StaticMap(&[
+        ("rfc-3339", &Self::Rfc3339),
+        ("rfc-2822", &Self::Rfc2822),
+        ("posix", &Self::Posix),
+        ("iso-week", &Self::IsoWeek),
+    ]);
+}
+
+/// Parse a string with the given grammar only, failing on anything else (e.g. trailing text).
+///
+/// The result is in the timezone of `now` which is also the basis of the missing fields.
+pub fn parse_with(grammar: Grammar, s: &str, now: &Zoned) -> Result<Zoned, jiff::Error> {
+    let tz = now.time_zone().clone();
+    let invalid = || jiff::Error::from_args(format_args!("`{}` is not {:?}", s, grammar));
+    match grammar {
+        Grammar::Rfc3339 => {
+            if !is_rfc_3339(s) {
+                return Err(invalid());
+            }
+            Ok(s.parse::<Timestamp>()?.to_zoned(tz))
+        }
+        Grammar::Rfc2822 => Ok(rfc2822::parse(s)?.with_time_zone(tz)),
+        Grammar::Posix => {
+            let mut tm = posix::DateTime::parse(s, Default::default())?;
+            if let Ok(now_tm) = posix::DateTime::try_from(now) {
+                tm.fill_missing_from(&now_tm);
+            }
+            tm.to_datetime(now.year())?.to_zoned(tz)
+        }
+        Grammar::IsoWeek => {
+            let b = s.as_bytes();
+            let digits = |i: &[u8]| i.iter().all(u8::is_ascii_digit);
+            if b.len() != 10 || !digits(&b[..4]) || &b[4..6] != b"-W" || !digits(&b[6..8]) {
+                return Err(invalid());
+            }
+            if b[8] != b'-' || !digits(&b[9..]) {
+                return Err(invalid());
+            }
+            let weekday = civil::Weekday::from_monday_one_offset((b[9] - b'0') as i8)?;
+            let week = ((b[6] - b'0') * 10 + b[7] - b'0') as i8;
+            let year = s[..4].parse().map_err(|_| invalid())?;
+            ISOWeekDate::new(year, week, weekday)?
+                .date()
+                .to_datetime(Time::midnight())
+                .to_zoned(tz)
+        }
+    }
+}
+
+/// Check the shape of an RFC 3339 timestamp ("T", "t" or a space between the date and the time).
+///
+/// The values themselves are left to the parser.
+fn is_rfc_3339(s: &str) -> bool {
+    let b = s.as_bytes();
+    let digits = |i: &[u8]| !i.is_empty() && i.iter().all(u8::is_ascii_digit);
+    if b.len() < 20
+        || !digits(&b[..4])
+        || b[4] != b'-'
+        || !digits(&b[5..7])
+        || b[7] != b'-'
+        || !digits(&b[8..10])
+        || !matches!(b[10], b'T' | b't' | b' ')
+        || !digits(&b[11..13])
+        || b[13] != b':'
+        || !digits(&b[14..16])
+        || b[16] != b':'
+        || !digits(&b[17..19])
+    {
+        return false;
+    }
+
+    let mut rest = &b[19..];
+    if let Some(fraction) = rest.strip_prefix(b".") {
+        let len = fraction.iter().take_while(|i| i.is_ascii_digit()).count();
+        if len == 0 {
+            return false;
+        }
+        rest = &fraction[len..];
+    }
+    match rest {
+        [b'Z' | b'z'] => true,
+        [b'+' | b'-', h @ .., b':', m1, m2] => h.len() == 2 && digits(h) && digits(&[*m1, *m2]),
+        _ => false,
+    }
+}
+
 /// Parse a triplet of "%Y/%m/%d".
 // TODO retire this and add it under the `date.rs` file
-fn parse_ymd_raw(s: &str) -> Result<(i16, i8, i8), jiff::Error> {
+pub(crate) fn parse_ymd_raw(s: &str) -> Result<(i16, i8, i8), jiff::Error> {
     let tm = BrokenDownTime::parse("%Y/%m/%d", s)?;
     Ok((tm.year().unwrap(), tm.month().unwrap(), tm.day().unwrap()))
 }
 
+/// Parse a Gregorian date in "%Y-%m-%d" or "%Y/%m/%d" format.
+pub fn parse_ymd_gregorian(s: &str) -> Result<civil::Date, jiff::Error> {
+    BrokenDownTime::parse("%Y-%m-%d", s)
+        .or_else(|_| BrokenDownTime::parse("%Y/%m/%d", s))?
+        .to_date()
+}
+
 /// Parse a Jalali date in "%Y/%m/%d" format.
 pub fn parse_ymd_jalali(s: &str) -> Result<jelal::Date, jiff::Error> {
     let (y, m, d) = parse_ymd_raw(s)?;
@@ -67,6 +218,25 @@ pub fn parse_ymd_jalali(s: &str) -> Result<jelal::Date, jiff::Error> {
     Ok(jelal::Date::from(date_raw))
 }
 
+/// Parse a Jalali date in "%Y/%m/%d" format with an optional "%H:%M[:%S]" after whitespace.
+///
+/// Without a time, midnight is returned.
+pub fn parse_ymd_hms_jalali(s: &str) -> Result<(jelal::Date, Time), jiff::Error> {
+    let s = s.trim();
+    let (date, time) = match s.split_once(char::is_whitespace) {
+        Some((date, time)) => (date, Some(time.trim_start())),
+        None => (s, None),
+    };
+
+    let time = match time {
+        Some(time) => BrokenDownTime::parse("%H:%M:%S", time)
+            .or_else(|_| BrokenDownTime::parse("%H:%M", time))?
+            .to_time()?,
+        None => Time::midnight(),
+    };
+    Ok((parse_ymd_jalali(date)?, time))
+}
+
 /// Match prefix of strings if uniquely identifiable without casing (ASCII only).
 ///
 /// This is only used for easier parsing of names and values with minor extra checkes for constant
@@ -175,28 +345,52 @@ fn parse_month_numeric(s: &str) -> Result<UMonth, &'static str> {
     Err("month is from 1 to 12 when given as a number")
 }
 
-const JALALI_MATCHER: IgnoreCasePrefixMatch<12> = IgnoreCasePrefixMatch::new(JALALI_MONTHS);
+// Prefixes are only matched against the ASCII names of `en`.
+const PREFIX_NAMES: NameProvider = NameProvider::EN;
+
+const JALALI_MATCHER: IgnoreCasePrefixMatch<12> =
+    IgnoreCasePrefixMatch::new(*PREFIX_NAMES.month_names(CalendarKind::Jalali));
+
+const GREGORIAN_MATCHER: IgnoreCasePrefixMatch<12> =
+    IgnoreCasePrefixMatch::new(*PREFIX_NAMES.month_names(CalendarKind::Gregorian));
 
-const GREGORIAN_MATCHER: IgnoreCasePrefixMatch<12> = IgnoreCasePrefixMatch::new(GREGORIAN_MONTHS);
+const HIJRI_MATCHER: IgnoreCasePrefixMatch<12> =
+    IgnoreCasePrefixMatch::new(*PREFIX_NAMES.month_names(CalendarKind::Hijri));
 
-const WEEKDAYS_MATCHER: IgnoreCasePrefixMatch<7> = IgnoreCasePrefixMatch::new(WEEKDAYS);
+const WEEKDAYS_MATCHER: IgnoreCasePrefixMatch<7> =
+    IgnoreCasePrefixMatch::new(*PREFIX_NAMES.weekdays());
 
-fn parse_month_string(matcher: &IgnoreCasePrefixMatch<12>, s: &str) -> Option<UMonth> {
-    parse_month_numeric(s)
-        .ok()
-        .or_else(|| matcher.position(s).map(|i| i as u8 + 1)) // month is 1 based but index is 0 based
+/// Match a number, an English prefix or a full name in any locale (see [`NameProvider`]).
+fn parse_month_string(calendar: CalendarKind, s: &str) -> Option<UMonth> {
+    let matcher = match calendar {
+        CalendarKind::Jalali => &JALALI_MATCHER,
+        CalendarKind::Gregorian => &GREGORIAN_MATCHER,
+        CalendarKind::Hijri => &HIJRI_MATCHER,
+    };
+    parse_month_numeric(s).ok().or_else(|| {
+        matcher
+            .position(s)
+            .or_else(|| NameProvider::position_in_any(s, |i| i.month_names(calendar)))
+            .map(|i| i as u8 + 1) // month is 1 based but index is 0 based
+    })
 }
 
-/// Parse from 1..=12 the valid month range or name of Gregorian months in English.
+/// Parse from 1..=12 the valid month range or name of Gregorian months.
 pub fn parse_month(s: &str) -> Result<UMonth, &'static str> {
-    parse_month_string(&JALALI_MATCHER, s)
-        .ok_or("invalid month name (\"mehr\" or number where January is 1, up to 12)")
+    parse_month_string(CalendarKind::Gregorian, s)
+        .ok_or("invalid month name (\"september\" or number where January is 1, up to 12)")
 }
 
-/// Parse from 1..=12 the valid month range or name of Gregorian months in English.
+/// Parse from 1..=12 the valid month range or name of Jalali months.
 pub fn parse_jalali_month(s: &str) -> Result<UMonth, &'static str> {
-    parse_month_string(&GREGORIAN_MATCHER, s)
-        .ok_or("invalid month name (\"september\" or number where January is 1, up to 12)")
+    parse_month_string(CalendarKind::Jalali, s)
+        .ok_or("invalid month name (\"mehr\" or number where Farvardin is 1, up to 12)")
+}
+
+/// Parse from 1..=12 the valid month range or name of Hijri months.
+pub fn parse_hijri_month(s: &str) -> Result<UMonth, &'static str> {
+    parse_month_string(CalendarKind::Hijri, s)
+        .ok_or("invalid month name (\"ramadan\" or number where Muharram is 1, up to 12)")
 }
 
 pub fn parse_weekday(s: &str) -> Result<Weekday, &'static str> {
@@ -211,8 +405,203 @@ pub fn parse_weekday(s: &str) -> Result<Weekday, &'static str> {
         return Ok(weekday.into());
     }
 
-    match WEEKDAYS_MATCHER.position(s) {
-        Some(i) => Ok(Weekday::new(i as u8)), // okay since struct & WEEKDAYS are Sunday based
+    let position = WEEKDAYS_MATCHER
+        .position(s)
+        .or_else(|| NameProvider::position_in_any(s, |i| i.weekdays()))
+        .or_else(|| NameProvider::position_in_any(s, |i| i.jalali_weekdays()));
+    match position {
+        Some(i) => Ok(Weekday::new(i as u8)), // okay since struct & names are Sunday based
         None => Err("invalid weekday name (\"sunday\" or number where Sunday is 0, up to 6)"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_at_2024(s: &str) -> Zoned {
+        let now: Zoned = "2024-01-01T10:20:30[UTC]".parse().unwrap();
+        parse_datetime(s, Some(now)).expect("invalid datetime in tests")
+    }
+
+    #[test]
+    fn test_parse_anchors() {
+        let now: Zoned = "2025-10-04T10:20:30[Asia/Tehran]".parse().unwrap();
+        let parse = |s| parse_jalali_datetime(s, Some(now.clone())).unwrap();
+        let at = |s: &str| -> Zoned { format!("{}[Asia/Tehran]", s).parse().unwrap() };
+
+        // only the Jalali inputs know the Jalali days
+        assert!(parse_datetime("next nowruz", Some(now.clone())).is_err());
+
+        assert_eq!(parse("next nowruz"), at("2026-03-21T00:00"));
+        assert_eq!(parse("last Nowruz"), at("2025-03-21T00:00"));
+        assert_eq!(parse("next yalda"), at("2025-12-21T00:00"));
+        assert_eq!(
+            parse("TZ=\"UTC\" next nowruz"),
+            "2026-03-21T00:00[UTC]".parse::<Zoned>().unwrap()
+        );
+        // everything else is left to the other parsers
+        assert_eq!(parse("next day"), at("2025-10-05T10:20:30"));
+    }
+
+    #[test]
+    fn test_parse_bare_dates() {
+        let at = |s: &str| -> Zoned { format!("{}[UTC]", s).parse().unwrap() };
+
+        assert_eq!(parse_at_2024("20251104"), at("2025-11-04T00:00"));
+        assert_eq!(parse_at_2024("14040812"), at("1404-08-12T00:00"));
+        assert_eq!(parse_at_2024("251104"), at("2025-11-04T00:00"));
+
+        // a valid month means "MMDDhhmm" wins the ambiguity
+        assert_eq!(parse_at_2024("12251104"), at("2024-12-25T11:04:00"));
+    }
+
+    #[test]
+    fn test_parse_whitespace() {
+        let at = |s: &str| -> Zoned { format!("{}[UTC]", s).parse().unwrap() };
+
+        assert_eq!(parse_at_2024(" 11041230\n"), at("2024-11-04T12:30:30"));
+        assert_eq!(parse_at_2024("\t251104 "), at("2025-11-04T00:00"));
+
+        // not a POSIX stamp, left to the parsers of spaced formats
+        let now: Zoned = "2024-01-01T10:20:30[UTC]".parse().unwrap();
+        let posix = posix::DateTime::parse_loose("2024-11-04\t12:30", Default::default(), 1, 1);
+        assert_eq!(posix, Err(posix::Error::InteriorWhitespace));
+        assert_eq!(
+            parse_datetime("2024-11-04\t12:30", Some(now)).unwrap(),
+            at("2024-11-04T12:30")
+        );
+    }
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(parse_month("sep"), Ok(9));
+        assert_eq!(parse_month("12"), Ok(12));
+        assert_eq!(parse_month("ژانویه"), Ok(1));
+        assert!(parse_month("mehr").is_err());
+        assert!(parse_month("13").is_err());
+
+        assert_eq!(parse_jalali_month("mehr"), Ok(7));
+        assert_eq!(parse_jalali_month("فروردین"), Ok(1));
+        assert_eq!(parse_jalali_month("اسفند"), Ok(12));
+        assert!(parse_jalali_month("september").is_err());
+
+        assert_eq!(parse_hijri_month("ram"), Ok(9));
+        assert_eq!(parse_hijri_month("dhu al-h"), Ok(12));
+        assert_eq!(parse_hijri_month("رمضان"), Ok(9));
+        assert!(parse_hijri_month("rabi").is_err());
+
+        assert_eq!(parse_weekday("fri"), Ok(Weekday::FRI));
+        assert_eq!(parse_weekday("جمعه"), Ok(Weekday::FRI));
+        assert_eq!(parse_weekday("Jomeh"), Ok(Weekday::FRI));
+        assert_eq!(parse_weekday("6"), Ok(Weekday::SAT));
+    }
+
+    #[test]
+    fn test_month_matchers_follow_their_calendar() {
+        // the Gregorian parser once matched Jalali names and the other way around
+        assert_eq!(parse_month("jan"), Ok(1));
+        assert_eq!(parse_month("Far").ok(), None);
+        assert_eq!(parse_month("dec"), Ok(12));
+        assert_eq!(parse_jalali_month("far"), Ok(1));
+        assert_eq!(parse_jalali_month("Jan").ok(), None);
+        assert_eq!(parse_jalali_month("esf"), Ok(12));
+    }
+
+    #[test]
+    fn test_parse_ymd_hms_jalali() {
+        let date = jelal::Date::from((1404, 7, 12));
+        let parse = |s| parse_ymd_hms_jalali(s).unwrap();
+
+        assert_eq!(parse("1404/07/12"), (date.clone(), Time::midnight()));
+        assert_eq!(
+            parse("1404/07/12 14:30"),
+            (date.clone(), Time::constant(14, 30, 0, 0))
+        );
+        assert_eq!(
+            parse("1404/07/12  14:30:45"),
+            (date, Time::constant(14, 30, 45, 0))
+        );
+
+        assert!(parse_ymd_hms_jalali("1404/07/12 25:00").is_err());
+        assert!(parse_ymd_hms_jalali("1404/07/12 14:30:45 extra").is_err());
+        assert!(parse_ymd_hms_jalali("1404/07/12 noon").is_err());
+    }
+
+    #[test]
+    fn test_parse_trailing_timezone() {
+        let tehran: Zoned = "2024-11-04T12:30:30[Asia/Tehran]".parse().unwrap();
+        assert_eq!(parse_at_2024("11041230 TZ=\"Asia/Tehran\""), tehran);
+        assert_eq!(parse_at_2024("TZ=\"Asia/Tehran\" 11041230"), tehran);
+
+        let now: Zoned = "2024-01-01T10:20:30[UTC]".parse().unwrap();
+        assert!(parse_datetime("TZ=\"UTC\" 11041230 TZ=\"UTC\"", Some(now)).is_err());
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let now: Zoned = "2024-01-01T10:20:30[UTC]".parse().unwrap();
+        let at = |s: &str| -> Zoned { format!("{}[UTC]", s).parse().unwrap() };
+        let parse = |grammar, s| parse_with(grammar, s, &now);
+
+        for (s, expected) in [
+            ("2025-11-07T10:30:05+03:30", "2025-11-07T07:00:05"),
+            ("2025-11-07t07:00:05Z", "2025-11-07T07:00:05"),
+            ("2025-11-07 07:00:05.25z", "2025-11-07T07:00:05.25"),
+            ("2025-11-07T02:00:05-05:00", "2025-11-07T07:00:05"),
+        ] {
+            assert_eq!(parse(Grammar::Rfc3339, s).unwrap(), at(expected), "{}", s);
+        }
+        for s in [
+            "2025-11-07T10:30:05",
+            "2025-11-07T10:30+03:30",
+            "2025-11-07T10:30:05+0330",
+            "2025-11-07T10:30:05Z ",
+            "2025-11-07T10:30:05Zjunk",
+            "2025-11-07T10:30:05.Z",
+            "2025-11-07T10:30:05+03:30[Asia/Tehran]",
+            "20251107T103005Z",
+            "2025-13-07T10:30:05Z",
+            "next friday",
+        ] {
+            assert!(parse(Grammar::Rfc3339, s).is_err(), "{}", s);
+        }
+
+        assert_eq!(
+            parse(Grammar::Rfc2822, "Fri, 07 Nov 2025 10:30:05 +0330").unwrap(),
+            at("2025-11-07T07:00:05")
+        );
+        assert!(parse(Grammar::Rfc2822, "Fri, 07 Nov 2025 10:30:05 +0330 junk").is_err());
+        assert!(parse(Grammar::Rfc2822, "2025-11-07T10:30:05Z").is_err());
+
+        assert_eq!(
+            parse(Grammar::Posix, "202511071030.05").unwrap(),
+            at("2025-11-07T10:30:05")
+        );
+        assert_eq!(
+            parse(Grammar::Posix, "11071030").unwrap(),
+            at("2024-11-07T10:30:30")
+        );
+        assert!(parse(Grammar::Posix, "11071030x").is_err());
+        assert!(parse(Grammar::Posix, "2025-11-07").is_err());
+
+        assert_eq!(
+            parse(Grammar::IsoWeek, "2025-W45-5").unwrap(),
+            at("2025-11-07T00:00")
+        );
+        assert_eq!(
+            parse(Grammar::IsoWeek, "2026-W01-1").unwrap(),
+            at("2025-12-29T00:00")
+        );
+        for s in [
+            "2025-W45",
+            "2025-W45-5 ",
+            "2025-W45-8",
+            "2025-W54-1",
+            "2025W455",
+            "2025-11-07",
+        ] {
+            assert!(parse(Grammar::IsoWeek, s).is_err(), "{}", s);
+        }
+    }
+}
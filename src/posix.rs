@@ -11,6 +11,7 @@
 //! - "MMDDhhmmYY"
 //! - "MMDDhhmm"
 //! - "MMDDhhmm.SS"
+//! - "CCYYMMDD" and "YYMMDD" (dates only, see [`DateTime::parse_date_only`])
 //!
 //! See parser methods for more information.
 //
@@ -29,38 +30,203 @@
 // }
 
 use std::{
+    borrow::Cow,
     fmt::{self, Display},
     ops::RangeInclusive,
 };
 
+use jelal::IYear;
+
+use crate::{
+    date::{CalendarKind, CommonDate},
+    names::NameProvider,
+};
+
 /// The default result of this module.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Errors given by the POSIX format reader.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
     /// Invalid syntax
     Syntax,
+    /// Whitespace between the digits, left to the parsers of spaced formats.
+    InteriorWhitespace,
     /// Syntax is known but not allowed for this instance of parser
     Forbidden,
-    /// Syntax is valid but values are not in POSIX range.
-    OutOfRange,
+    /// The obsolete "MMDDhhmmYY" form was given while rejected (see [`ParseOptions`]).
+    ObsoleteYear,
+    /// More than one reading is plausible and guessing was not allowed (see [`AmbiguityPolicy`]).
+    Ambiguous { interpretations: Vec<String> },
+    /// Syntax is valid but the value of the field is not in its POSIX range.
+    OutOfRange { field: Field, value: i32 },
+    /// The day is in its POSIX range but the month is shorter (without a year, the longest is
+    /// assumed).
+    DayOutOfMonth {
+        jalali: bool,
+        year: Option<i32>,
+        month: u8,
+        days: u8,
+    },
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::Syntax => "value is not a valid POSIX string".fmt(f),
+            Error::InteriorWhitespace => {
+                "POSIX stamps contain only digits and an optional '.SS'".fmt(f)
+            }
             Error::Forbidden => "value is a valid POSIX-like but not allowed".fmt(f),
-            Error::OutOfRange => "one of the POSIX string fields is out of its range".fmt(f),
+            Error::ObsoleteYear => {
+                "a trailing two digit year is obsolete, spell the century (MMDDhhmmCCYY)".fmt(f)
+            }
+            Error::Ambiguous { interpretations } => write!(
+                f,
+                "value is ambiguous, it could be either of: {}",
+                interpretations.join(", ")
+            ),
+            Error::OutOfRange { field, value } => {
+                let range = field.range();
+                write!(
+                    f,
+                    "{} {} is out of range {}..={}",
+                    field,
+                    value,
+                    range.start(),
+                    range.end()
+                )
+            }
+            Error::DayOutOfMonth {
+                jalali,
+                year,
+                month,
+                days,
+            } => {
+                let calendar = if *jalali {
+                    CalendarKind::Jalali
+                } else {
+                    CalendarKind::Gregorian
+                };
+                let name = NameProvider::DEFAULT.month_name(calendar, *month);
+                match year {
+                    Some(year) => write!(f, "{} has {} days in {}", name, days, year),
+                    None => write!(f, "{} has at most {} days", name, days),
+                }
+            }
+        }
+    }
+}
+
+/// The fields of a [`DateTime`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Field {
+    /// The accepted input range of this field.
+    pub fn range(&self) -> RangeInclusive<u16> {
+        let widen = |r: RangeInclusive<u8>| (*r.start() as u16)..=(*r.end() as u16);
+        match self {
+            Field::Year => DateTime::YEAR_RANGE,
+            Field::Month => widen(DateTime::MONTH_RANGE),
+            Field::Day => widen(DateTime::DAY_RANGE),
+            Field::Hour => widen(DateTime::HOUR_RANGE),
+            Field::Minute => widen(DateTime::MINUTE_RANGE),
+            Field::Second => widen(DateTime::SECOND_MIN..=DateTime::SECOND_SATURATING_MAX),
+        }
+    }
+
+    /// Return an error if the value is not in [`Self::range`].
+    pub fn check(self, value: u16) -> Result<()> {
+        if self.range().contains(&value) {
+            Ok(())
+        } else {
+            Err(Error::OutOfRange {
+                field: self,
+                value: value as i32,
+            })
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Year => "year",
+            Field::Month => "month",
+            Field::Day => "day",
+            Field::Hour => "hour",
+            Field::Minute => "minute",
+            Field::Second => "second",
         }
+        .fmt(f)
     }
 }
 
 impl std::error::Error for Error {}
 
+/// How seconds above 59 (leap seconds) are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SecondPolicy {
+    /// Reject the legacy 61 and refuse to convert 60 since it cannot be represented as is.
+    Strict,
+    /// Saturate 61 to 60 on input and 60 to 59 on conversion (as in `touch` and `date`).
+    #[default]
+    SaturateLegacy,
+    /// Saturate 61 to 60 on input but keep 60 on conversion as the start of the next minute.
+    PassThrough,
+}
+
+/// How [`DateTime::parse_loose`] reads the lengths that have more than one plausible reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AmbiguityPolicy {
+    /// Read 5 and 7 digits as a date with a zero padded year ("YMMDD" and "YYYMMDD").
+    #[default]
+    Guess,
+    /// Return [`Error::Ambiguous`] listing the plausible readings instead.
+    Reject,
+}
+
+/// Options for [`DateTime::parse`] and its variants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseOptions {
+    /// Try "MMDDhhmm[[CC]YY]" before "[[CC]YY]MMDDhhmm" when both are possible.
+    pub prioritize_trailing: bool,
+    /// Reject the obsolete "MMDDhhmmYY" with [`Error::ObsoleteYear`].
+    pub reject_obsolete: bool,
+    /// See [`SecondPolicy`].
+    pub second_policy: SecondPolicy,
+    /// See [`AmbiguityPolicy`].
+    pub ambiguity: AmbiguityPolicy,
+}
+
+impl ParseOptions {
+    /// The same options but the other [`Self::prioritize_trailing`].
+    fn flip_priority(self) -> Self {
+        Self {
+            prioritize_trailing: !self.prioritize_trailing,
+            ..self
+        }
+    }
+}
+
 /// A generic broken time holder (by no means guarantees a valid date).
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serde_impl::RawDateTime")
+)]
 pub struct DateTime {
     /// See [`Self::YEAR_RANGE`]
     pub year: Option<u16>,
@@ -74,6 +240,10 @@ pub struct DateTime {
     pub minute: u8,
     /// See [`Self::SECOND_RANGE`] and [`Self::SECOND_LEGACY_RANGE`].
     pub second: Option<u8>,
+    /// The fraction of the second in nanoseconds (see [`Self::FRACTION_MAX_DIGITS`]).
+    pub nanosecond: Option<u32>,
+    /// How [`Self::second`] is set and converted.
+    pub second_policy: SecondPolicy,
 }
 
 impl Default for DateTime {
@@ -85,6 +255,50 @@ impl Default for DateTime {
             hour: 0,
             minute: 0,
             second: None,
+            nanosecond: None,
+            second_policy: Default::default(),
+        }
+    }
+}
+
+/// [`DateTime`] is read through its setters so a field out of its range is rejected as if parsed.
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::Deserialize;
+
+    use super::*;
+
+    /// The fields of a [`DateTime`] before they are checked.
+    #[derive(Deserialize)]
+    pub struct RawDateTime {
+        year: Option<u16>,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: Option<u8>,
+        nanosecond: Option<u32>,
+        second_policy: SecondPolicy,
+    }
+
+    impl TryFrom<RawDateTime> for DateTime {
+        type Error = Error;
+
+        fn try_from(raw: RawDateTime) -> Result<Self> {
+            let mut v = DateTime::new(raw.month, raw.day, raw.hour, raw.minute)?;
+            v.second_policy = raw.second_policy;
+            if let Some(year) = raw.year {
+                v.set_year(year)?;
+            }
+            if let Some(second) = raw.second {
+                v.set_second(second)?;
+            }
+            // no more than the 9 digits of a fraction
+            if raw.nanosecond.is_some_and(|i| i >= 1_000_000_000) {
+                return Err(Error::Syntax);
+            }
+            v.nanosecond = raw.nanosecond;
+            Ok(v)
         }
     }
 }
@@ -121,21 +335,35 @@ impl DateTime {
     /// Values larger than this will be outright rejected as out of range.
     pub const SECOND_SATURATING_MAX: u8 = 61;
 
+    /// How many digits of a fraction of a second are accepted (nanosecond precision).
+    pub const FRACTION_MAX_DIGITS: usize = 9;
+
     /// [`Self::parse`] but if "MMDDhhmm" misses digits, prioritize time and then date to set zero.
     ///
-    /// This supports ".SS" regardless of the given format.
+    /// This supports ".SS" regardless of the given format and also a fraction of the second after
+    /// the two "SS" digits (".SSfff..." up to [`Self::FRACTION_MAX_DIGITS`] digits).
+    ///
+    /// 5 and 7 digits are read as dates unless rejected by [`ParseOptions::ambiguity`].
     pub fn parse_loose(
         chars: &str,
-        mut prioritize_trailing: bool,
+        mut options: ParseOptions,
         now_month: u8,
         now_day: u8,
     ) -> Result<Self> {
+        let chars = Self::trim_stamp(chars)?;
         // easy access to indices
         if !chars.is_ascii() {
             return Err(Error::Syntax);
         }
 
+        let digits = chars.split_once('.').map_or(chars, |(i, _)| i);
+        if options.ambiguity == AmbiguityPolicy::Reject && matches!(digits.len(), 5 | 7) {
+            return Err(Self::ambiguous(chars, options, now_month, now_day));
+        }
+
         let (chars, ss) = chars.split_once('.').unwrap_or((chars, "00"));
+        // the string is ASCII so any byte index is a valid split
+        let (ss, fraction) = ss.split_at(ss.len().min(2));
 
         let (hh, mm);
         let chars = match chars.len() {
@@ -149,11 +377,11 @@ impl DateTime {
                 format_args!("{:0>2}{:0>2}{:0>2}{:0>2}", now_month, now_day, hh, mm)
             }
             5 | 7 => {
-                prioritize_trailing = false;
+                options.prioritize_trailing = false;
                 format_args!("0{:0>7}0000", chars)
             }
             6 => {
-                prioritize_trailing = false;
+                options.prioritize_trailing = false;
                 format_args!("{}0000", chars)
             }
             // since jiff and others don't parse large values, there is no point parsing past 7
@@ -161,7 +389,76 @@ impl DateTime {
         };
 
         let chars = &format!("{}.{:0>2}", chars, ss);
-        Self::parse(chars, prioritize_trailing)
+        let mut candidate = Self::parse(chars, options)?;
+        if !fraction.is_empty() {
+            candidate.set_fraction(&fraction.chars().collect::<Vec<_>>())?;
+        }
+        Ok(candidate)
+    }
+
+    /// Drop the surrounding whitespace, a stamp is never split so any left is an
+    /// [`Error::InteriorWhitespace`] (not a length of digits to guess from).
+    fn trim_stamp(chars: &str) -> Result<&str> {
+        let chars = chars.trim();
+        if chars.contains(char::is_whitespace) {
+            return Err(Error::InteriorWhitespace);
+        }
+        Ok(chars)
+    }
+
+    /// List the readings of a 5 or 7 digit [`Self::parse_loose`] input that are valid.
+    fn ambiguous(chars: &str, options: ParseOptions, now_month: u8, now_day: u8) -> Error {
+        let guess = ParseOptions {
+            ambiguity: AmbiguityPolicy::Guess,
+            ..options
+        };
+        let loose = |s: &str| Self::parse_loose(s, guess, now_month, now_day);
+
+        let (digits, ss) = match chars.split_once('.') {
+            Some((digits, ss)) => (digits, Some(ss)),
+            None => (chars, None),
+        };
+        let mut readings = Vec::with_capacity(2);
+        match (digits.len(), ss) {
+            // a time with seconds mistyped without the dot
+            (5, None) => {
+                readings.push(("YMMDD", loose(chars)));
+                readings.push((
+                    "hmmss",
+                    loose(&format!("{}.{}", &digits[..3], &digits[3..])),
+                ));
+            }
+            (5, Some(_)) => readings.push(("YMMDD", loose(chars))),
+            // a month without its leading zero
+            _ => {
+                readings.push(("YYYMMDD", loose(chars)));
+                readings.push(("MDDhhmm", loose(&format!("0{}", chars))));
+            }
+        }
+
+        let interpretations = readings
+            .into_iter()
+            .filter_map(|(form, v)| Some(format!("{} as \"{}\"", v.ok()?.describe(), form)))
+            .collect::<Vec<_>>();
+        if interpretations.is_empty() {
+            Error::Syntax
+        } else {
+            Error::Ambiguous { interpretations }
+        }
+    }
+
+    /// A human readable "[YYYY-]MM-DD hh:mm:ss" for messages.
+    fn describe(&self) -> String {
+        let year = self.year.map(|i| format!("{:04}-", i)).unwrap_or_default();
+        format!(
+            "{}{:02}-{:02} {:02}:{:02}:{:02}",
+            year,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second.unwrap_or(0)
+        )
     }
 
     /// Parse a POSIX Time format.
@@ -178,15 +475,22 @@ impl DateTime {
     /// - Allow second ("[.SS]") for all variants above.
     ///
     /// "CC" is 20 for 00..=68 and 19 for 69..=99.
-    pub fn parse(chars: &str, prioritize_trailing: bool) -> Result<Self> {
-        let chars = chars.chars().collect::<Vec<_>>();
+    ///
+    /// The second is handled with the given [`SecondPolicy`] which is also kept for conversions.
+    /// The obsolete form is an [`Error::ObsoleteYear`] if [`ParseOptions::reject_obsolete`] is set.
+    pub fn parse(chars: &str, options: ParseOptions) -> Result<Self> {
+        let chars = Self::trim_stamp(chars)?.chars().collect::<Vec<_>>();
         let (chars, ss) = {
             let mut dot_split = chars.as_slice().splitn(2, |&c| c == '.');
             (dot_split.next().unwrap(), dot_split.next())
         };
 
-        let mut candidate = Self::parse_no_second(chars, prioritize_trailing)
-            .or_else(|_| Self::parse_no_second(chars, !prioritize_trailing))?;
+        let mut candidate = Self::parse_no_second(chars, options).or_else(|e| {
+            // the obsolete form is more informative than why the other order failed
+            Self::parse_no_second(chars, options.flip_priority())
+                .map_err(|other| if e == Error::ObsoleteYear { e } else { other })
+        })?;
+        candidate.second_policy = options.second_policy;
 
         if let Some(ss) = ss {
             candidate.set_ss(ss)?;
@@ -195,10 +499,33 @@ impl DateTime {
         Ok(candidate)
     }
 
+    /// Parse a bare date, either "CCYYMMDD" or "YYMMDD", with the time set to midnight.
+    ///
+    /// "CC" is guessed as in [`Self::parse`] when not given. Note that 8 digits are also a valid
+    /// "MMDDhhmm" so callers trying both should decide which one goes first.
+    pub fn parse_date_only(chars: &str) -> Result<Self> {
+        let chars = chars.chars().collect::<Vec<_>>();
+        let (ccyy, mmdd) = chars.split_last_chunk::<4>().ok_or(Error::Syntax)?;
+        let (may_cc, yy) = match ccyy.len() {
+            2 | 4 => ccyy.split_last_chunk::<2>().unwrap(),
+            _ => return Err(Error::Syntax),
+        };
+
+        let mut candidate = Self::new(
+            Self::two_as_num(&mmdd[0..2])?,
+            Self::two_as_num(&mmdd[2..4])?,
+            0,
+            0,
+        )?;
+        candidate.set_cc_yy(may_cc.first_chunk::<2>(), yy)?;
+        candidate.set_second(0)?;
+        Ok(candidate)
+    }
+
     /// Just like [`Self::parse`] but do not process seconds.
-    pub fn parse_no_second(chars: &[char], prioritize_trailing: bool) -> Result<Self> {
+    pub fn parse_no_second(chars: &[char], options: ParseOptions) -> Result<Self> {
         // Take 8 characters from start or end of a value and return the remainer and the taken.
-        let (ccyy, mmddhhmm) = if prioritize_trailing {
+        let (ccyy, mmddhhmm) = if options.prioritize_trailing {
             chars.split_first_chunk::<8>().map(|(a, b)| (b, a))
         } else {
             chars.split_last_chunk::<8>()
@@ -208,6 +535,9 @@ impl DateTime {
         let mut candidate = Self::try_from_mmddhhmm(mmddhhmm)?;
         match ccyy.len() {
             0 => {}
+            2 if options.prioritize_trailing && options.reject_obsolete => {
+                return Err(Error::ObsoleteYear);
+            }
             2 | 4 => {
                 let (may_cc, yy) = ccyy.split_last_chunk::<2>().unwrap();
                 candidate.set_cc_yy(may_cc.first_chunk::<2>(), yy)?;
@@ -224,47 +554,61 @@ impl DateTime {
     }
 
     /// Create a new instance.
+    ///
+    /// The first field that is out of its range is reported in the error.
     pub fn new(month: u8, day: u8, hour: u8, minute: u8) -> Result<Self> {
-        if [
-            Self::MONTH_RANGE.contains(&month),
-            Self::DAY_RANGE.contains(&day),
-            Self::HOUR_RANGE.contains(&hour),
-            Self::MINUTE_RANGE.contains(&minute),
-        ]
-        .into_iter()
-        .all(|i| i)
-        {
-            Ok(Self {
-                month,
-                day,
-                hour,
-                minute,
-                ..Default::default()
-            })
-        } else {
-            Err(Error::OutOfRange)
-        }
+        Field::Month.check(month as u16)?;
+        Field::Day.check(day as u16)?;
+        Field::Hour.check(hour as u16)?;
+        Field::Minute.check(minute as u16)?;
+        Ok(Self {
+            month,
+            day,
+            hour,
+            minute,
+            ..Default::default()
+        })
     }
 
     /// Set the year if in range.
     pub fn set_year(&mut self, year: u16) -> Result<&mut Self> {
-        if Self::YEAR_RANGE.contains(&year) {
-            self.year = Some(year);
-            Ok(self)
-        } else {
-            Err(Error::OutOfRange)
-        }
+        Field::Year.check(year)?;
+        self.year = Some(year);
+        Ok(self)
     }
 
-    /// Set the second if in the current or legacy range.
+    /// Set the second if in the current or legacy range (see [`Self::second_policy`]).
     pub fn set_second(&mut self, second: u8) -> Result<&mut Self> {
-        // The first check does nothing since the number is 0 <= anyways but it must be here for
-        // the sake of consistency
-        if (Self::SECOND_MIN..=Self::SECOND_SATURATING_MAX).contains(&second) {
-            self.second = Some(Self::SECOND_MAX.min(second)); // saturate
-            Ok(self)
+        // The lower bound check does nothing since the number is 0 <= anyways but it must be
+        // there for the sake of consistency
+        Field::Second.check(second as u16)?;
+        if second > Self::SECOND_MAX && self.second_policy == SecondPolicy::Strict {
+            return Err(Error::Forbidden);
+        }
+        self.second = Some(Self::SECOND_MAX.min(second)); // saturate
+        Ok(self)
+    }
+
+    /// The second to give to `jiff` and whether a second must be added after (see
+    /// [`Self::second_policy`]).
+    fn civil_second(&self) -> Result<(i8, bool)> {
+        match (self.second.unwrap_or(0), self.second_policy) {
+            (v, _) if v < Self::SECOND_MAX => Ok((v as i8, false)),
+            (_, SecondPolicy::Strict) => Err(Error::Forbidden),
+            (_, SecondPolicy::SaturateLegacy) => Ok((59, false)),
+            (_, SecondPolicy::PassThrough) => Ok((59, true)),
+        }
+    }
+
+    /// Add the second [`Self::civil_second`] asked for, if any.
+    fn carry_second(
+        datetime: jiff::civil::DateTime,
+        carry: bool,
+    ) -> Result<jiff::civil::DateTime, jiff::Error> {
+        if carry {
+            datetime.checked_add(jiff::Span::new().seconds(1))
         } else {
-            Err(Error::OutOfRange)
+            Ok(datetime)
         }
     }
 
@@ -302,6 +646,26 @@ impl DateTime {
         self.set_second(Self::two_as_num(ss)?)
     }
 
+    /// Set the nanoseconds from the digits written after "SS" (as in ".SSfff").
+    ///
+    /// The integer part (seconds) is not touched so leap seconds saturate as usual.
+    pub fn set_fraction(&mut self, fraction: &[char]) -> Result<&mut Self> {
+        if fraction.is_empty() || fraction.len() > Self::FRACTION_MAX_DIGITS {
+            return Err(Error::Syntax);
+        }
+
+        let mut nanosecond = 0;
+        for i in 0..Self::FRACTION_MAX_DIGITS {
+            let digit = match fraction.get(i) {
+                Some(c) => c.to_digit(10).ok_or(Error::Syntax)?,
+                None => 0,
+            };
+            nanosecond = nanosecond * 10 + digit;
+        }
+        self.nanosecond = Some(nanosecond);
+        Ok(self)
+    }
+
     /// If two digits, convert as if written in succession.
     fn two_as_num(pair: &[char]) -> Result<u8> {
         if pair.len() != 2 {
@@ -318,8 +682,12 @@ impl DateTime {
 ///
 /// This will still pass if `VAR` has whitespace before it.
 ///
-/// The comments here assume that the `open_delimiter` is '"' and `close_delimiter` is also `"` but
-/// they can vary. Same goes for `infix` which is assumed to be '='.
+/// The comments here assume that the `delimiters` are '"' for both opening and closing but they
+/// can vary. Same goes for `infix` which is assumed to be '='.
+///
+/// If `delimiters` is `None`, the value is unquoted (`VAR=XREST`) and terminates at the first
+/// whitespace or the end of the string. In this mode no escape is supported and quotes are not
+/// special, so try the quoted variants first if they are accepted.
 ///
 /// If `VAR="` (ignoring the whitespaces) does not prefix the string, Some(None) will be returned
 /// and the rest just trimmed. In any other case, the function tries to extract `X` and should it
@@ -335,20 +703,29 @@ impl DateTime {
 fn parse_var_prefix<'proc, 'src>(
     var: &'proc str,
     infix: &'proc str,
-    open_delimiter: &'proc char,
-    close_delimiter: &'proc char,
+    delimiters: Option<(char, char)>,
     src: &'src str,
 ) -> Option<(Option<&'src str>, &'src str)> {
-    // if found `VAR="` remove it, else just return that the var is not found (don't touch)
-    let Some(src) = src
+    // if found `VAR=` remove it, else just return that the var is not found (don't touch)
+    let Some(assigned) = src
         .trim_start()
         .strip_prefix(var)
         .and_then(|s| s.strip_prefix(infix))
-        .and_then(|s| s.strip_prefix(*open_delimiter))
     else {
         return Some((None, src));
     };
 
+    let Some((open_delimiter, close_delimiter)) = delimiters else {
+        // unquoted values simply end where the first whitespace is
+        let end_i = assigned.find(char::is_whitespace).unwrap_or(assigned.len());
+        return Some((Some(&assigned[..end_i]), &assigned[end_i..]));
+    };
+
+    // same as above but for the opening delimiter `VAR="`
+    let Some(src) = assigned.strip_prefix(open_delimiter) else {
+        return Some((None, src));
+    };
+
     // determine where it all ends (non-inclusive)
     let end_i = {
         let mut chars = src.char_indices();
@@ -360,11 +737,11 @@ fn parse_var_prefix<'proc, 'src>(
                 // skip the next and don't terminate if its just an escape (using next again)
                 if let Some((next_i, next_c)) = chars.next() {
                     end_i = next_i;
-                    if next_c != '\\' && next_c != *close_delimiter {
+                    if next_c != '\\' && next_c != close_delimiter {
                         return None; // err: format!("unsupported escape sequence '\\{next_c}'");
                     }
                 }
-            } else if c == *close_delimiter {
+            } else if c == close_delimiter {
                 closed = true;
                 break;
             }
@@ -385,28 +762,84 @@ fn parse_var_prefix<'proc, 'src>(
 
 /// Given a string, try to take out the trimmed initial `TZ="X"` and return "X" and also the rest.
 ///
+/// Double quoted (`TZ="X"`), single quoted (`TZ='X'`) and unquoted (`TZ=X` up to the first
+/// whitespace) forms are tried in order.
+///
 /// This does not perform any checks on the string whatsoever.
 pub fn take_timezone(s: &str) -> Option<(Option<&str>, &str)> {
-    parse_var_prefix("TZ", "=", &'"', &'"', s)
+    for delimiters in [Some(('"', '"')), Some(('\'', '\''))] {
+        if let (Some(v), rest) = parse_var_prefix("TZ", "=", delimiters, s)? {
+            return Some((Some(v), rest));
+        }
+    }
+    parse_var_prefix("TZ", "=", None, s)
 }
 
-/// Parse a `TZ="TIMEZONE"` prefix. If cannot parse, will return None.
-// this was initially a dedicated implementation using both TimeZone::{get,posix} but it's delegated
-// to parse_datetime since their implementation covers more altho it also throws in some fixed
-// offset which jiff frowns upon
-pub fn parse_timezone(s: &str) -> (Option<jiff::tz::TimeZone>, &str) {
-    let original_s = s;
-    let s = s.trim_start();
+/// Like [`take_timezone`] but the `TZ="X"` assignment may be anywhere in the string.
+///
+/// The assignment must start the string or follow a whitespace. It is removed and the gap it leaves
+/// is collapsed into a single space (a prefix leaves the rest untouched as [`take_timezone`] does).
+///
+/// More than one assignment is [`Error::Forbidden`] and a malformed one is [`Error::Syntax`].
+pub fn find_timezone(s: &str) -> Result<(Option<&str>, Cow<'_, str>)> {
+    let start = s
+        .char_indices()
+        .find(|&(i, _)| {
+            s[i..].starts_with("TZ=") && s[..i].chars().next_back().is_none_or(char::is_whitespace)
+        })
+        .map(|(i, _)| i);
+    let Some(start) = start else {
+        return Ok((None, Cow::Borrowed(s)));
+    };
 
-    // parse taking out TZ="" to validate and trim whitespaces then giving it back
-    // https://github.com/uutils/parse_datetime/issues/240
-    if let Some((Some(s), rest)) = take_timezone(s) {
-        if let Ok(v) = parse_datetime::parse_datetime(&format!("TZ=\"{}\"", s)) {
-            return (Some(v.time_zone().clone()), rest);
-        }
+    let before = &s[..start];
+    let (value, after) = match take_timezone(&s[start..]) {
+        Some((Some(value), after)) => (value, after),
+        _ => return Err(Error::Syntax),
+    };
+    if find_timezone(after)?.0.is_some() {
+        return Err(Error::Forbidden);
     }
 
-    (None, original_s)
+    let rest = if before.trim().is_empty() {
+        Cow::Borrowed(after)
+    } else if after.trim().is_empty() {
+        Cow::Borrowed(before.trim_end())
+    } else {
+        Cow::Owned(format!("{} {}", before.trim_end(), after.trim_start()))
+    };
+    Ok((Some(value), rest))
+}
+
+/// Parse a `TZ="TIMEZONE"` assignment and return the timezone and the rest of the string.
+///
+/// The value is tried as an IANA name (case-insensitive), then as a POSIX TZ rule and only then
+/// with `parse_datetime` for the legacy forms it accepts.
+///
+/// The assignment may be anywhere in the string (see [`find_timezone`]).
+///
+/// If there is no `TZ=`, `(None, s)` is returned untouched. If there is one but its value is not a
+/// valid timezone, an error is returned instead of ignoring what was explicitly written.
+pub fn parse_timezone(s: &str) -> Result<(Option<jiff::tz::TimeZone>, Cow<'_, str>), jiff::Error> {
+    use jiff::tz::TimeZone;
+
+    let (Some(value), rest) = find_timezone(s)? else {
+        return Ok((None, Cow::Borrowed(s)));
+    };
+
+    // parse_datetime is given the value with the whitespaces trimmed since it does not relax them
+    // https://github.com/uutils/parse_datetime/issues/240
+    let tz = TimeZone::get(value)
+        .ok()
+        .or_else(|| TimeZone::posix(value).ok())
+        .or_else(|| {
+            parse_datetime::parse_datetime(&format!("TZ=\"{}\"", value))
+                .ok()
+                .map(|i| i.time_zone().clone())
+        })
+        .ok_or_else(|| jiff::Error::from_args(format_args!("invalid timezone `{}`", value)))?;
+
+    Ok((Some(tz), rest))
 }
 
 impl From<Error> for jiff::Error {
@@ -416,17 +849,109 @@ impl From<Error> for jiff::Error {
 }
 
 impl DateTime {
+    /// A Gregorian leap year to validate the days against when no year is given.
+    const GREGORIAN_LEAP_YEAR: IYear = 2024;
+
+    /// A Jalali leap year to validate the days against when no year is given.
+    const JALALI_LEAP_YEAR: IYear = 1403;
+
+    /// Check if the day exists in the month of the given calendar (leap years included).
+    ///
+    /// Without a year, the day is checked against a leap year so February 29 and Esfand 30 pass.
+    pub fn validate_in_calendar(&self, jalali: bool) -> Result<()> {
+        let leap_year = if jalali {
+            Self::JALALI_LEAP_YEAR
+        } else {
+            Self::GREGORIAN_LEAP_YEAR
+        };
+        self.validate_day(jalali, self.year.map(|i| i as IYear), leap_year)
+    }
+
+    /// Check the day against the month in the given year or the basis if no year is given.
+    fn validate_day(&self, jalali: bool, year: Option<IYear>, year_basis: IYear) -> Result<()> {
+        let days = Self::days_in_month(jalali, year.unwrap_or(year_basis), self.month);
+        if self.day <= days {
+            return Ok(());
+        }
+        Err(Error::DayOutOfMonth {
+            jalali,
+            year,
+            month: self.month,
+            days,
+        })
+    }
+
+    /// How many days are in the month of the year (month must be in [`Self::MONTH_RANGE`]).
+    fn days_in_month(jalali: bool, year: IYear, month: u8) -> u8 {
+        if jalali {
+            CommonDate::month_end_day(&jelal::Date::from((year, month, 1)))
+        } else {
+            jiff::civil::Date::new(year as i16, month as i8, 1)
+                .map(|i| i.days_in_month() as u8)
+                .unwrap_or(31) // years are in range so this never happens
+        }
+    }
+
+    /// Interpret the fields as a Jalali date and convert it to Gregorian like [`Self::to_datetime`].
+    ///
+    /// The day is validated with [`Self::validate_in_calendar`] rules so the error is meaningful.
+    pub fn to_jalali_datetime(self, year_basis: i16) -> Result<jiff::civil::DateTime, jiff::Error> {
+        let year = self.year.map(|i| i as IYear);
+        self.validate_day(true, year, year_basis as IYear)?;
+
+        let date = jelal::Date::from((year.unwrap_or(year_basis as IYear), self.month, self.day));
+        let (second, carry) = self.civil_second()?;
+        let datetime = jiff::civil::DateTime::from_parts(
+            date.try_into()?,
+            jiff::civil::Time::new(
+                self.hour as i8,
+                self.minute as i8,
+                second,
+                self.nanosecond.unwrap_or(0) as i32,
+            )?,
+        );
+        Self::carry_second(datetime, carry)
+    }
+
     /// Convert to Zoned with the given year basis and keep seconds 0 if not given (as in `date`).
+    ///
+    /// Leap seconds are converted as in [`Self::second_policy`].
     pub fn to_datetime(self, year_basis: i16) -> Result<jiff::civil::DateTime, jiff::Error> {
-        jiff::civil::DateTime::new(
+        let (second, carry) = self.civil_second()?;
+        let datetime = jiff::civil::DateTime::new(
             self.year.map(|i| i as i16).unwrap_or(year_basis),
             self.month as i8,
             self.day as i8,
             self.hour as i8,
             self.minute as i8,
-            self.second_min_59().unwrap_or(0) as i8,
-            0,
-        )
+            second,
+            self.nanosecond.unwrap_or(0) as i32,
+        )?;
+        Self::carry_second(datetime, carry)
+    }
+
+    /// The signed span from `now` to this in the timezone of `now` (negative if already past).
+    ///
+    /// The missing year and second are taken from `now` as [`crate::parser::parse_datetime`] does.
+    /// If `jalali`, the fields (and so the missing year) are Jalali.
+    pub fn until(&self, now: &jiff::Zoned, jalali: bool) -> Result<jiff::Span, jiff::Error> {
+        let mut basis = DateTime::try_from(now)?;
+        let year_basis = if jalali {
+            let year = jelal::Date::from(now.date()).year().get();
+            basis.year = u16::try_from(year).ok();
+            year as i16
+        } else {
+            now.year()
+        };
+
+        let mut tm = self.clone();
+        tm.fill_missing_from(&basis);
+        let datetime = if jalali {
+            tm.to_jalali_datetime(year_basis)?
+        } else {
+            tm.to_datetime(year_basis)?
+        };
+        now.until(&datetime.to_zoned(now.time_zone().clone())?)
     }
 }
 
@@ -441,12 +966,45 @@ impl TryFrom<jiff::civil::DateTime> for DateTime {
             hour: value.hour() as u8,
             minute: value.minute() as u8,
             second: Some(value.second() as u8),
+            nanosecond: match value.subsec_nanosecond() {
+                0 => None,
+                v => Some(v as u32),
+            },
+            second_policy: Default::default(),
         };
-        candidate.set_year(value.year() as u16)?; // large values will fail before `as` effects
+        let year = u16::try_from(value.year()).map_err(|_| Error::OutOfRange {
+            field: Field::Year,
+            value: value.year() as i32,
+        })?;
+        candidate.set_year(year)?;
         Ok(candidate)
     }
 }
 
+impl TryFrom<&jiff::Zoned> for DateTime {
+    type Error = Error;
+
+    /// The wall clock of the value (the timezone is dropped).
+    fn try_from(value: &jiff::Zoned) -> std::result::Result<Self, Self::Error> {
+        value.datetime().try_into()
+    }
+}
+
+impl DateTime {
+    /// Copy the year and the second of the other only if they are not set in this one.
+    ///
+    /// Useful to default the fields an input omitted to `now`.
+    pub fn fill_missing_from(&mut self, other: &DateTime) -> &mut Self {
+        if self.year.is_none() {
+            self.year = other.year;
+        }
+        if self.second.is_none() {
+            self.second = other.second;
+        }
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -455,7 +1013,14 @@ mod tests {
     use std::str::FromStr;
 
     fn parse_posix(s: &str, trailing: bool, year: Option<u16>, second: u8) -> DateTime {
-        let mut dt = DateTime::parse(s, trailing).expect("invalid POSIX in tests");
+        let mut dt = DateTime::parse(
+            s,
+            ParseOptions {
+                prioritize_trailing: trailing,
+                ..Default::default()
+            },
+        )
+        .expect("invalid POSIX in tests");
         if let Some(year) = year {
             dt.set_year(year).expect("cannot set year");
         }
@@ -470,14 +1035,26 @@ mod tests {
         month: u8,
         day: u8,
     ) -> DateTime {
-        let mut dt =
-            DateTime::parse_loose(s, trailing, month, day).expect("invalid POSIX in tests");
+        let mut dt = DateTime::parse_loose(
+            s,
+            ParseOptions {
+                prioritize_trailing: trailing,
+                ..Default::default()
+            },
+            month,
+            day,
+        )
+        .expect("invalid POSIX in tests");
         if let Some(year) = year {
             dt.set_year(year).expect("cannot set year");
         }
         dt
     }
 
+    fn tz(s: &str) -> (Option<jiff::tz::TimeZone>, Cow<'_, str>) {
+        parse_timezone(s).expect("invalid timezone in tests")
+    }
+
     fn parse_jiff(s: &str) -> DateTime {
         Jdt::from_str(s)
             .expect("invalid string")
@@ -485,11 +1062,196 @@ mod tests {
             .unwrap()
     }
 
+    #[test]
+    fn test_out_of_range_field() {
+        fn err<T>(field: Field, value: i32) -> Result<T> {
+            Err(Error::OutOfRange { field, value })
+        }
+
+        assert_eq!(
+            DateTime::parse("13150704", Default::default()),
+            err(Field::Month, 13)
+        );
+        assert_eq!(
+            DateTime::parse("12320704", Default::default()),
+            err(Field::Day, 32)
+        );
+        assert_eq!(
+            DateTime::parse("12312404", Default::default()),
+            err(Field::Hour, 24)
+        );
+        assert_eq!(
+            DateTime::parse("12312360", Default::default()),
+            err(Field::Minute, 60)
+        );
+        assert_eq!(
+            DateTime::parse("12312359.62", Default::default()),
+            err(Field::Second, 62)
+        );
+        assert_eq!(
+            DateTime::default().set_year(10000).map(|_| ()),
+            err(Field::Year, 10000)
+        );
+        assert_eq!(
+            DateTime::try_from(jiff::civil::date(-1, 1, 1).at(0, 0, 0, 0)),
+            err(Field::Year, -1)
+        );
+
+        assert_eq!(
+            DateTime::parse("13150704", Default::default())
+                .unwrap_err()
+                .to_string(),
+            "month 13 is out of range 1..=12"
+        );
+        assert_eq!(
+            DateTime::parse("12312359.62", Default::default())
+                .unwrap_err()
+                .to_string(),
+            "second 62 is out of range 0..=61"
+        );
+    }
+
+    #[test]
+    fn test_second_policy() {
+        use SecondPolicy::*;
+
+        let parse = |s, second_policy| {
+            DateTime::parse(
+                s,
+                ParseOptions {
+                    second_policy,
+                    ..Default::default()
+                },
+            )
+        };
+        let convert = |s, policy| parse(s, policy).unwrap().to_datetime(2024);
+        let at = |minute, second| jiff::civil::date(2024, 12, 31).at(23, minute, second, 0);
+
+        // the default is the legacy behavior
+        assert_eq!(
+            parse("12312359.61", SecondPolicy::default()),
+            parse("12312359.61", SaturateLegacy)
+        );
+
+        assert_eq!(parse("12312359.61", Strict), Err(Error::Forbidden));
+        assert_eq!(parse("12312359.60", Strict).unwrap().second, Some(60));
+        assert!(convert("12312359.60", Strict).is_err());
+        assert_eq!(convert("12312359.59", Strict).unwrap(), at(59, 59));
+
+        assert_eq!(
+            parse("12312359.61", SaturateLegacy).unwrap().second,
+            Some(60)
+        );
+        assert_eq!(convert("12312359.61", SaturateLegacy).unwrap(), at(59, 59));
+        assert_eq!(convert("12312359.60", SaturateLegacy).unwrap(), at(59, 59));
+
+        assert_eq!(parse("12312359.61", PassThrough).unwrap().second, Some(60));
+        assert_eq!(
+            convert("12312359.61", PassThrough).unwrap(),
+            jiff::civil::date(2025, 1, 1).at(0, 0, 0, 0)
+        );
+        assert_eq!(convert("12312358.60", PassThrough).unwrap(), at(59, 0));
+    }
+
+    #[test]
+    fn test_fill_missing_from_zoned() {
+        let now: Zoned = "2024-03-05T06:07:08[UTC]".parse().unwrap();
+        let now = DateTime::try_from(&now).unwrap();
+        assert_eq!(now, parse_jiff("2024-03-05T06:07:08"));
+
+        let mut tm = DateTime::parse("11041230", Default::default()).unwrap();
+        assert_eq!(tm.second, None);
+        tm.fill_missing_from(&now);
+        assert_eq!(tm, parse_jiff("2024-11-04T12:30:08"));
+
+        let mut tm = DateTime::parse("11041230.45", Default::default()).unwrap();
+        tm.fill_missing_from(&now);
+        assert_eq!(tm, parse_jiff("2024-11-04T12:30:45"));
+
+        // a given year is kept too
+        let mut tm = DateTime::parse("202511041230", Default::default()).unwrap();
+        tm.fill_missing_from(&now);
+        assert_eq!(tm.year, Some(2025));
+    }
+
+    #[test]
+    fn test_until() {
+        let seconds = |stamp: &str, now: &str, jalali| {
+            let now: Zoned = now.parse().unwrap();
+            DateTime::parse(stamp, Default::default())
+                .unwrap()
+                .until(&now, jalali)
+                .unwrap()
+                .total(jiff::Unit::Second)
+                .unwrap()
+        };
+
+        // earlier than now is negative and the missing second is the one of now
+        assert_eq!(
+            seconds("11041130", "2025-11-04T12:00:30[UTC]", false),
+            -3600.0
+        );
+        assert_eq!(
+            seconds("11041130.00", "2025-11-04T12:00:30[UTC]", false),
+            -3630.0
+        );
+        // the clocks go forward an hour at 02:00 so three hours on the wall are two
+        assert_eq!(
+            seconds("03300430", "2025-03-30T01:30[Europe/Berlin]", false),
+            7200.0
+        );
+        // 1404/01/02 is 2025-03-22
+        assert_eq!(
+            seconds("01021200", "2025-03-21T00:00[UTC]", true),
+            36.0 * 3600.0
+        );
+    }
+
+    #[test]
+    fn test_reject_obsolete() {
+        let parse = |reject_obsolete| {
+            DateTime::parse(
+                "0704192469",
+                ParseOptions {
+                    prioritize_trailing: true,
+                    reject_obsolete,
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert_eq!(parse(false), Ok(parse_jiff("1969-07-04T19:24")));
+        assert_eq!(parse(true), Err(Error::ObsoleteYear));
+        assert!(parse(true).unwrap_err().to_string().contains("century"));
+
+        // spelling the century or the leading forms are not affected
+        let options = ParseOptions {
+            prioritize_trailing: true,
+            reject_obsolete: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            DateTime::parse("070419241969", options),
+            Ok(parse_jiff("1969-07-04T19:24"))
+        );
+        assert_eq!(
+            DateTime::parse("6907041924", options),
+            Ok(parse_jiff("1969-07-04T19:24"))
+        );
+    }
+
     #[test]
     fn test_mmddhhmm_priority_ignore() {
         assert_eq!(
-            DateTime::parse("06150704", false).unwrap(),
-            DateTime::parse("06150704", true).unwrap(),
+            DateTime::parse("06150704", Default::default()).unwrap(),
+            DateTime::parse(
+                "06150704",
+                ParseOptions {
+                    prioritize_trailing: true,
+                    ..Default::default()
+                }
+            )
+            .unwrap(),
         );
     }
 
@@ -639,6 +1401,233 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_loose_ambiguity_reject() {
+        let reject = |s| {
+            let options = ParseOptions {
+                ambiguity: AmbiguityPolicy::Reject,
+                ..Default::default()
+            };
+            DateTime::parse_loose(s, options, 07, 04)
+        };
+
+        assert_eq!(
+            reject("81122"),
+            Err(Error::Ambiguous {
+                interpretations: vec![
+                    "0008-11-22 00:00:00 as \"YMMDD\"".to_owned(),
+                    "07-04 08:11:22 as \"hmmss\"".to_owned(),
+                ]
+            })
+        );
+        assert_eq!(
+            reject("4121113"),
+            Err(Error::Ambiguous {
+                interpretations: vec![
+                    "0412-11-13 00:00:00 as \"YYYMMDD\"".to_owned(),
+                    "04-12 11:13:00 as \"MDDhhmm\"".to_owned(),
+                ]
+            })
+        );
+        // invalid readings are not listed
+        assert_eq!(
+            reject("81359"),
+            Err(Error::Ambiguous {
+                interpretations: vec!["07-04 08:13:59 as \"hmmss\"".to_owned()]
+            })
+        );
+        assert!(
+            reject("81122")
+                .unwrap_err()
+                .to_string()
+                .contains("0008-11-22")
+        );
+
+        // unambiguous lengths are not affected
+        assert_eq!(
+            reject("2123"),
+            Ok(parse_posix_loose("2123", false, None, 07, 04))
+        );
+        assert_eq!(reject("121113"), Ok(parse_jiff("2012-11-13T00:00")));
+    }
+
+    #[test]
+    fn test_date_only() {
+        assert_eq!(
+            parse_jiff("2025-11-04T00:00"),
+            DateTime::parse_date_only("20251104").unwrap()
+        );
+        assert_eq!(
+            parse_jiff("1404-08-12T00:00"),
+            DateTime::parse_date_only("14040812").unwrap()
+        );
+        assert_eq!(
+            parse_jiff("2025-11-04T00:00"),
+            DateTime::parse_date_only("251104").unwrap()
+        );
+        assert_eq!(
+            parse_jiff("1969-11-04T00:00"),
+            DateTime::parse_date_only("691104").unwrap()
+        );
+
+        assert_eq!(DateTime::parse_date_only("2511041"), Err(Error::Syntax));
+        assert_eq!(DateTime::parse_date_only("2025-1104"), Err(Error::Syntax));
+        assert_eq!(DateTime::parse_date_only("2025110"), Err(Error::Syntax));
+        assert_eq!(
+            DateTime::parse_date_only("20251304"),
+            Err(Error::OutOfRange {
+                field: Field::Month,
+                value: 13
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_in_calendar() {
+        let at = |year: Option<u16>, month, day| {
+            let mut dt = DateTime::new(month, day, 0, 0).unwrap();
+            if let Some(year) = year {
+                dt.set_year(year).unwrap();
+            }
+            dt
+        };
+
+        assert_eq!(at(None, 2, 29).validate_in_calendar(false), Ok(()));
+        assert_eq!(at(Some(2024), 2, 29).validate_in_calendar(false), Ok(()));
+        assert_eq!(
+            at(Some(2025), 2, 29)
+                .validate_in_calendar(false)
+                .unwrap_err()
+                .to_string(),
+            "February has 28 days in 2025"
+        );
+        assert_eq!(
+            at(None, 2, 31)
+                .validate_in_calendar(false)
+                .unwrap_err()
+                .to_string(),
+            "February has at most 29 days"
+        );
+
+        // months 1..=6 have 31 days in Jalali
+        assert_eq!(at(Some(1404), 2, 31).validate_in_calendar(true), Ok(()));
+        assert_eq!(at(Some(1403), 12, 30).validate_in_calendar(true), Ok(()));
+        assert_eq!(
+            at(Some(1404), 12, 30).validate_in_calendar(true),
+            Err(Error::DayOutOfMonth {
+                jalali: true,
+                year: Some(1404),
+                month: 12,
+                days: 29
+            })
+        );
+        assert_eq!(
+            at(Some(1404), 12, 30)
+                .validate_in_calendar(true)
+                .unwrap_err()
+                .to_string(),
+            "Esfand has 29 days in 1404"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_validates() {
+        let mut dt = DateTime::new(11, 7, 10, 30).unwrap();
+        dt.set_year(2025).unwrap();
+        let json = serde_json::to_string(&dt).unwrap();
+        assert_eq!(
+            json,
+            r#"{"year":2025,"month":11,"day":7,"hour":10,"minute":30,"second":null,"nanosecond":null,"second_policy":"SaturateLegacy"}"#
+        );
+        assert_eq!(serde_json::from_str::<DateTime>(&json).unwrap(), dt);
+
+        // each field as its setter would
+        let parse = |from: &str, to: &str| {
+            serde_json::from_str::<DateTime>(&json.replace(from, to)).map_err(|e| e.to_string())
+        };
+        let e = parse(r#""month":11"#, r#""month":13"#).unwrap_err();
+        assert!(e.contains("month 13 is out of range"), "{}", e);
+        assert!(parse(r#""year":2025"#, r#""year":10000"#).is_err());
+        assert!(parse(r#""minute":30"#, r#""minute":60"#).is_err());
+        assert!(parse(r#""nanosecond":null"#, r#""nanosecond":1000000000"#).is_err());
+        // the legacy 61 saturates unless strict
+        let dt = parse(r#""second":null"#, r#""second":61"#).unwrap();
+        assert_eq!(dt.second, Some(60));
+        let strict = json
+            .replace(r#""second":null"#, r#""second":61"#)
+            .replace("SaturateLegacy", "Strict");
+        assert!(serde_json::from_str::<DateTime>(&strict).is_err());
+    }
+
+    #[test]
+    fn test_to_jalali_datetime() {
+        let mut dt = DateTime::new(02, 31, 19, 24).unwrap();
+        assert_eq!(
+            dt.clone().to_jalali_datetime(1404).unwrap(),
+            jiff::civil::date(2025, 5, 21).at(19, 24, 0, 0)
+        );
+        dt.set_year(1403).unwrap();
+        assert_eq!(
+            dt.to_jalali_datetime(1404).unwrap(),
+            jiff::civil::date(2024, 5, 20).at(19, 24, 0, 0)
+        );
+
+        let dt = DateTime::new(12, 30, 0, 0).unwrap();
+        assert!(dt.clone().to_jalali_datetime(1403).is_ok());
+        assert!(dt.to_jalali_datetime(1404).is_err());
+    }
+
+    #[test]
+    fn test_whitespace() {
+        let loose = |s| DateTime::parse_loose(s, Default::default(), 07, 04);
+        let parse = |s| DateTime::parse(s, Default::default());
+
+        // only the surrounding whitespace is dropped
+        assert_eq!(loose(" 07041924 "), loose("07041924"));
+        assert_eq!(loose("\t1924.30\n"), loose("1924.30"));
+        assert_eq!(parse(" 07041924 "), parse("07041924"));
+        assert!(parse(" 07041924 ").is_ok());
+
+        for s in [" 0704 1924", "0704\t1924", "07041924 .30", "0704 19"] {
+            assert_eq!(loose(s), Err(Error::InteriorWhitespace), "{:?}", s);
+            assert_eq!(parse(s), Err(Error::InteriorWhitespace), "{:?}", s);
+        }
+        assert_eq!(
+            Error::InteriorWhitespace.to_string(),
+            "POSIX stamps contain only digits and an optional '.SS'"
+        );
+    }
+
+    #[test]
+    fn test_loose_fraction() {
+        let loose = |s| DateTime::parse_loose(s, Default::default(), 07, 04);
+        let expect = |second, nanosecond| {
+            let mut dt = DateTime::new(07, 04, 19, 24).unwrap();
+            dt.set_second(second).unwrap();
+            dt.nanosecond = nanosecond;
+            Ok(dt)
+        };
+
+        // a single digit is still the (padded) second and not a fraction
+        assert_eq!(loose("07041924.3"), expect(3, None));
+        assert_eq!(loose("07041924.30"), expect(30, None));
+        assert_eq!(loose("07041924.305"), expect(30, Some(500_000_000)));
+        assert_eq!(loose("07041924.305999999"), expect(30, Some(599_999_900)));
+        assert_eq!(loose("07041924.30123456789"), expect(30, Some(123_456_789)));
+        // leap seconds still saturate while the fraction is kept
+        assert_eq!(loose("07041924.615"), expect(60, Some(500_000_000)));
+
+        assert_eq!(loose("07041924.30x"), Err(Error::Syntax));
+        assert_eq!(loose("07041924.30-5"), Err(Error::Syntax));
+        assert_eq!(loose("07041924.301234567890"), Err(Error::Syntax));
+
+        assert_eq!(
+            loose("07041924.305").unwrap().to_datetime(2024).unwrap(),
+            jiff::civil::date(2024, 7, 4).at(19, 24, 30, 500_000_000)
+        );
+    }
+
     #[test]
     fn test_parse_tz() {
         // the current parser is compared with `parse_datetime`'s since that's the most complete
@@ -648,21 +1637,12 @@ mod tests {
             Err(e) => panic!("{s:?} throws: {e}"),
         };
         // test that this does not confuse named offsets with TZ
+        assert_eq!(tz("TZ=\"UTC+1\""), (Some(op("TZ=\"UTC+1\"")), "".into()));
+        assert_eq!(tz("TZ=\"UTC-1\""), (Some(op("TZ=\"UTC-1\"")), "".into()));
+        assert_eq!(tz("TZ=\"UTC-1\" "), (Some(op("TZ=\"UTC-1\" ")), " ".into()));
         assert_eq!(
-            parse_timezone("TZ=\"UTC+1\""),
-            (Some(op("TZ=\"UTC+1\"")), "")
-        );
-        assert_eq!(
-            parse_timezone("TZ=\"UTC-1\""),
-            (Some(op("TZ=\"UTC-1\"")), "")
-        );
-        assert_eq!(
-            parse_timezone("TZ=\"UTC-1\" "),
-            (Some(op("TZ=\"UTC-1\" ")), " ")
-        );
-        assert_eq!(
-            parse_timezone("TZ=\"UTC-1\"\t"),
-            (Some(op("TZ=\"UTC-1\"")), "\t")
+            tz("TZ=\"UTC-1\"\t"),
+            (Some(op("TZ=\"UTC-1\"")), "\t".into())
         );
 
         // when quotes have inner whitespace, `parse_datetime` produces invalid results.
@@ -672,36 +1652,121 @@ mod tests {
         // and the default implementation. Note the whitespaces inside the quotation.
         //
         // See also https://github.com/uutils/parse_datetime/pull/232#issuecomment-3421283917
+        assert_eq!(tz("TZ=\"UTC-1 \""), (Some(op("TZ=\"UTC-1\"")), "".into()));
         assert_eq!(
-            parse_timezone("TZ=\"UTC-1 \""),
-            (Some(op("TZ=\"UTC-1\"")), "")
-        );
-        assert_eq!(
-            parse_timezone("TZ=\"\tUTC-1\"\t"),
-            (Some(op("TZ=\"UTC-1\"")), "\t")
+            tz("TZ=\"\tUTC-1\"\t"),
+            (Some(op("TZ=\"UTC-1\"")), "\t".into())
         );
         assert_eq!(
-            parse_timezone("\tTZ=\"UTC-1\"\t"),
-            (Some(op("TZ=\"UTC-1\"")), "\t")
+            tz("\tTZ=\"UTC-1\"\t"),
+            (Some(op("TZ=\"UTC-1\"")), "\t".into())
         );
 
-        assert_eq!(parse_timezone("UTC+1"), (None, "UTC+1"));
-        assert_eq!(parse_timezone("UTC-1"), (None, "UTC-1"));
-        assert_eq!(parse_timezone("UTC-1 "), (None, "UTC-1 "));
-        assert_eq!(parse_timezone("UTC-1\t"), (None, "UTC-1\t"));
-        assert_eq!(parse_timezone("\tUTC-1\t"), (None, "\tUTC-1\t"));
+        assert_eq!(tz("UTC+1"), (None, "UTC+1".into()));
+        assert_eq!(tz("UTC-1"), (None, "UTC-1".into()));
+        assert_eq!(tz("UTC-1 "), (None, "UTC-1 ".into()));
+        assert_eq!(tz("UTC-1\t"), (None, "UTC-1\t".into()));
+        assert_eq!(tz("\tUTC-1\t"), (None, "\tUTC-1\t".into()));
 
-        assert_eq!(parse_timezone("\tNO TIME ZONE"), (None, "\tNO TIME ZONE"));
+        assert_eq!(tz("\tNO TIME ZONE"), (None, "\tNO TIME ZONE".into()));
         // assert_eq!(op(""), jiff::tz::TimeZone::system());
 
         assert_eq!(
-            parse_timezone("\tTZ=\"\"\tELSE"),
-            (Some(jiff::tz::TimeZone::UTC), "\tELSE")
+            tz("\tTZ=\"\"\tELSE"),
+            (Some(jiff::tz::TimeZone::UTC), "\tELSE".into())
         );
         assert_eq!(
-            parse_timezone("\tTZ=\"\t\"\tELSE"),
-            (Some(jiff::tz::TimeZone::UTC), "\tELSE")
+            tz("\tTZ=\"\t\"\tELSE"),
+            (Some(jiff::tz::TimeZone::UTC), "\tELSE".into())
         );
         // assert_eq!(op("TZ=\"\""), jiff::tz::TimeZone::UTC);
     }
+
+    #[test]
+    fn test_parse_tz_single_quoted_and_unquoted() {
+        let op = |s: &str| match parse_datetime::parse_datetime(s) {
+            Ok(v) => v.time_zone().clone(),
+            Err(e) => panic!("{s:?} throws: {e}"),
+        };
+
+        assert_eq!(
+            tz("TZ='UTC-1' rest"),
+            (Some(op("TZ=\"UTC-1\"")), " rest".into())
+        );
+        assert_eq!(
+            tz("TZ=UTC-1 rest"),
+            (Some(op("TZ=\"UTC-1\"")), " rest".into())
+        );
+        // unquoted value terminated by the end of the string
+        assert_eq!(tz("TZ=UTC-1"), (Some(op("TZ=\"UTC-1\"")), "".into()));
+        assert_eq!(tz("\tTZ=UTC-1\t"), (Some(op("TZ=\"UTC-1\"")), "\t".into()));
+
+        // the quoted forms are still preferred and mismatched quotes are rejected
+        assert_eq!(
+            take_timezone("TZ=\"UTC\" rest"),
+            Some((Some("UTC"), " rest"))
+        );
+        assert_eq!(take_timezone("TZ='UTC\" rest"), None);
+        assert_eq!(take_timezone("TZ=\"UTC' rest"), None);
+        assert_eq!(take_timezone("TZ= rest"), Some((Some(""), " rest")));
+    }
+
+    #[test]
+    fn test_find_timezone_anywhere() {
+        assert_eq!(
+            find_timezone("12:00 TZ=\"Asia/Tehran\""),
+            Ok((Some("Asia/Tehran"), "12:00".into()))
+        );
+        assert_eq!(
+            find_timezone("12:00  TZ='UTC'\ttomorrow"),
+            Ok((Some("UTC"), "12:00 tomorrow".into()))
+        );
+        // escaped quotes are part of the value and do not end it
+        assert_eq!(
+            find_timezone("noon TZ=\"A\\\"B\" rest"),
+            Ok((Some("A\\\"B"), "noon rest".into()))
+        );
+        // not an assignment if glued to a previous word
+        assert_eq!(
+            find_timezone("XTZ=\"UTC\""),
+            Ok((None, "XTZ=\"UTC\"".into()))
+        );
+
+        assert_eq!(
+            find_timezone("TZ=\"UTC\" 12:00 TZ=\"UTC\""),
+            Err(Error::Forbidden)
+        );
+        assert_eq!(find_timezone("12:00 TZ=\"UTC"), Err(Error::Syntax));
+
+        assert_eq!(
+            tz("12:00 TZ=\"Asia/Tehran\" tomorrow"),
+            (
+                Some(jiff::tz::TimeZone::get("Asia/Tehran").unwrap()),
+                "12:00 tomorrow".into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_tz_jiff_first() {
+        use jiff::tz::TimeZone;
+
+        assert_eq!(
+            tz("TZ=\"Asia/Tehran\" rest"),
+            (Some(TimeZone::get("Asia/Tehran").unwrap()), " rest".into())
+        );
+        assert_eq!(
+            tz("TZ=\"america/new_york\""),
+            (Some(TimeZone::get("America/New_York").unwrap()), "".into())
+        );
+        assert_eq!(
+            tz("TZ='IRST-3:30'"),
+            (Some(TimeZone::posix("IRST-3:30").unwrap()), "".into())
+        );
+
+        let e = parse_timezone("TZ=\"Not/AZone\" rest").unwrap_err();
+        assert!(e.to_string().contains("Not/AZone"), "{}", e);
+        // without the prefix nothing is a timezone
+        assert_eq!(tz("Not/AZone"), (None, "Not/AZone".into()));
+    }
 }
@@ -1,9 +1,13 @@
 //! Holds `strftime`-like functions and related helpers.
 
 use jelal::UMonth;
-use jiff::{Zoned, fmt::strtime::BrokenDownTime};
+use jiff::{Zoned, civil, fmt::strtime::BrokenDownTime};
 
-use crate::{JALALI_MONTHS, JALALI_MONTHS_ABB, date::CommonDate};
+use crate::{
+    clap_helper::StaticMap,
+    date::{CalendarKind, CommonDate, Date, gregorian_span_of_jalali_year},
+    names::NameProvider,
+};
 
 /// Holds an exploded list of directives and literals.
 #[derive(Debug, Clone, PartialEq)]
@@ -13,6 +17,12 @@ pub struct Formatter<'a> {
 }
 
 impl<'a> Formatter<'a> {
+    /// The widest padding a directive may ask for (`%255d`).
+    ///
+    /// Wider ones are rejected before formatting so a typo like `%0999999999d` cannot allocate
+    /// gigabytes (see [`Self::check_widths`]).
+    pub const MAX_WIDTH: usize = 255;
+
     pub fn new(format: &'a str) -> Self {
         let mut chars = format.char_indices().peekable();
         let mut directives = Vec::new();
@@ -72,6 +82,25 @@ impl<'a> Formatter<'a> {
         }
     }
 
+    /// Fail on the first directive wider than [`Self::MAX_WIDTH`].
+    ///
+    /// This only reads the already exploded directives once, so it is cheap to do before every
+    /// formatting whether the directive is for `jiff` or one of the resolvers here.
+    pub fn check_widths(&self) -> Result<(), jiff::Error> {
+        let too_wide = self
+            .directives
+            .iter()
+            .find(|(_, directive)| directive_width(directive).is_some_and(|i| i > Self::MAX_WIDTH));
+        match too_wide {
+            Some((_, directive)) => Err(jiff::Error::from_args(format_args!(
+                "the padding width of `{}` is larger than {}",
+                directive,
+                Self::MAX_WIDTH
+            ))),
+            None => Ok(()),
+        }
+    }
+
     /// Reconstruct the values given a "reconstructor" function.
     ///
     /// A reconstructor function takes a value that necessarily starts with "%" and ends with a
@@ -100,8 +129,37 @@ impl<'a> Formatter<'a> {
         new
     }
 
-    // /// Like [`Self::lenient_reconstruct_with`] but with functions that may fail.
-    // pub fn reconstruct_with<F, E>(f: F) -> Result<String, E> {}
+    /// Like [`Self::lenient_reconstruct_with`] but with functions that may fail.
+    ///
+    /// Since every directive must be handled by `f`, "%%" is also unescaped here.
+    pub fn reconstruct_with<F, E>(&self, f: F) -> Result<String, E>
+    where
+        F: Fn(&str) -> Result<String, E>,
+    {
+        let mut new = String::with_capacity(self.original.len());
+        let mut previous_end = 0;
+        for (start_index, directive) in self.directives.iter() {
+            new.push_str(&self.original[previous_end..*start_index].replace("%%", "%"));
+            new.push_str(&f(directive)?);
+            previous_end = start_index + directive.len();
+        }
+        new.push_str(&self.original[previous_end..].replace("%%", "%"));
+        Ok(new)
+    }
+}
+
+/// The padding width of a directive if given (`%-10d` is 10), saturating if it does not fit.
+fn directive_width(directive: &str) -> Option<usize> {
+    let rest = directive
+        .strip_prefix('%')?
+        .trim_start_matches(['-', '_', '0', '^', '#']);
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    if end == 0 {
+        return None;
+    }
+    Some(rest[..end].parse().unwrap_or(usize::MAX))
 }
 
 /// Given a Jalali month (1..=12), create a function that formats `%s`-like directives to its name.
@@ -119,10 +177,11 @@ pub fn jalali_month_format_resolve(jalali_month: UMonth) -> impl Fn(&str) -> Opt
             return None;
         }
 
+        let names = NameProvider::DEFAULT;
         let arr = if s.ends_with('B') {
-            JALALI_MONTHS
+            names.month_names(CalendarKind::Jalali)
         } else if s.ends_with('b') || s.ends_with('h') {
-            JALALI_MONTHS_ABB
+            names.month_abbreviations(CalendarKind::Jalali)
         } else {
             return None;
         };
@@ -138,6 +197,191 @@ pub fn jalali_month_format_resolve(jalali_month: UMonth) -> impl Fn(&str) -> Opt
     }
 }
 
+/// Directives added on top of `strftime` to mix calendars (available in both calendars).
+///
+/// `E` is the POSIX modifier for alternative representations so these never clash with `jiff`.
+pub const EXTENSIONS: &[(&str, &str)] = &[
+    ("%EJ", "day of the Jalali year (001..=366)"),
+    ("%EN", "the Jalali year"),
+    (
+        "%Ed",
+        "day of the Jalali month (01..=31, `%-Ed` for no padding)",
+    ),
+    ("%EB", "the Jalali month name (Aban)"),
+    ("%EA", "the Persian weekday name (Jomeh)"),
+    ("%EF", "the Jalali date (1404-08-16)"),
+    ("%EG", "the Gregorian years of the Jalali year (2025–2026)"),
+    (
+        "%Ei",
+        "the sortable Jalali timestamp (1404-08-16T10:30:05+03:30)",
+    ),
+];
+
+/// Given a date, create a function that formats [`EXTENSIONS`] in Jalali.
+///
+/// This is a "reconstructor" function for [`Formatter`]. The date is converted only if needed.
+///
+/// `%Ei` expands to the Jalali date followed by time directives so it must be formatted again with
+/// the time at hand (as the `strftime` functions here do).
+pub fn jalali_extension_resolve(date: civil::Date) -> impl Fn(&str) -> Option<String> {
+    move |s: &str| {
+        let jalali = || jelal::Date::from(date);
+        let names = NameProvider::DEFAULT;
+        match s {
+            "%EJ" => Some(format!("{:03}", CommonDate::ordinal(&jalali()))),
+            "%EN" => Some(format!("{:04}", CommonDate::year(&jalali()))),
+            "%Ed" => Some(format!("{:02}", CommonDate::day(&jalali()))),
+            "%-Ed" => Some(CommonDate::day(&jalali()).to_string()),
+            "%EB" => Some(
+                names
+                    .month_name(CalendarKind::Jalali, CommonDate::month(&jalali()))
+                    .to_owned(),
+            ),
+            "%EA" => {
+                Some(names.jalali_weekdays()[CommonDate::weekday(&date).get() as usize].to_owned())
+            }
+            "%EF" => {
+                let jalali = jalali();
+                Some(format!(
+                    "{:04}-{:02}-{:02}",
+                    CommonDate::year(&jalali),
+                    CommonDate::month(&jalali),
+                    CommonDate::day(&jalali)
+                ))
+            }
+            "%EG" => {
+                let (first, last) = gregorian_span_of_jalali_year(CommonDate::year(&jalali()));
+                Some(format!("{:04}–{:04}", first, last))
+            }
+            "%Ei" => {
+                let jalali = jalali();
+                Some(format!(
+                    "{:04}-{:02}-{:02}T%H:%M:%S%:z",
+                    CommonDate::year(&jalali),
+                    CommonDate::month(&jalali),
+                    CommonDate::day(&jalali)
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A long phrase of both calendars, the time and the offset for each locale (see
+/// [`crate::config::LOCALES`]).
+///
+/// The locales decide which calendar is first, their names are put in by [`localized_strftime`].
+pub const HUMAN_FORMATS: StaticMap<&'static str> = StaticMap(&[
+    ("en", "%A, %-d %B %Y (%EA, %-Ed %EB %EN), %H:%M %:z"),
+    ("fa", "%EA, %-Ed %EB %EN (%A, %-d %B %Y), %H:%M %:z"),
+]);
+
+/// Directives a [`Date`] alone can format with [`date_strftime`] (no time of the day).
+pub const DATE_DIRECTIVES: &[&str] = &["%B", "%^B", "%b", "%^b", "%h", "%Y", "%y", "%m"];
+
+/// Given a date in any calendar, create a function that formats [`DATE_DIRECTIVES`] and
+/// [`EXTENSIONS`] (except `%Ei`) or fails for any other directive.
+///
+/// This is a "reconstructor" function for [`Formatter::reconstruct_with`].
+pub fn date_format_resolve(date: &Date) -> impl Fn(&str) -> Result<String, jiff::Error> + '_ {
+    move |s: &str| {
+        let month = date.month() as usize - 1;
+        let abbreviation = || NameProvider::DEFAULT.month_abbreviations(date.kind())[month];
+        Ok(match s {
+            "%B" => date.month_name().to_owned(),
+            "%^B" => date.month_name().to_uppercase(),
+            "%b" | "%h" => abbreviation().to_owned(),
+            "%^b" => abbreviation().to_uppercase(),
+            "%Y" => format!("{:04}", date.year()),
+            "%y" => format!("{:02}", date.year().rem_euclid(100)),
+            "%m" => format!("{:02}", date.month()),
+            _ => {
+                let gregorian = match date {
+                    Date::Gregorian(v) => *v,
+                    Date::Jalali(v) => v.clone().try_into()?,
+                    Date::Hijri(v) => (*v).into(),
+                };
+                // `%Ei` needs the time of the day
+                let extension = jalali_extension_resolve(gregorian)(s).filter(|_| s != "%Ei");
+                extension.ok_or_else(|| {
+                    jiff::Error::from_args(format_args!(
+                        "`{}` cannot be formatted with a date alone (supported: {})",
+                        s,
+                        DATE_DIRECTIVES.join(", ")
+                    ))
+                })?
+            }
+        })
+    }
+}
+
+/// Format a date alone (see [`date_format_resolve`]).
+pub fn date_strftime(format: &str, date: &Date) -> Result<String, jiff::Error> {
+    let formatter = Formatter::new(format);
+    formatter.check_widths()?;
+    formatter.reconstruct_with(date_format_resolve(date))
+}
+
+/// Format in Gregorian like [`Zoned::strftime`] but with [`EXTENSIONS`] support.
+pub fn gregorian_strftime(format: &str, now: &Zoned) -> Result<String, jiff::Error> {
+    let formatter = Formatter::new(format);
+    formatter.check_widths()?;
+    let format = formatter.lenient_reconstruct_with(jalali_extension_resolve(now.date()));
+    let mut buf = String::new();
+    BrokenDownTime::from(now).format(format, &mut buf)?;
+    Ok(buf)
+}
+
+/// Given a date and the names of a locale, create a function that formats the full names of its
+/// weekday and month (`%A` and `%B` in the given calendar, `%EA` and `%EB` in Jalali).
+///
+/// This is a "reconstructor" function for [`Formatter`]. The directives with flags are left as is.
+pub fn names_resolve(
+    date: civil::Date,
+    jalali: bool,
+    names: NameProvider,
+) -> impl Fn(&str) -> Option<String> {
+    move |s: &str| {
+        let jalali_month = || CommonDate::month(&jelal::Date::from(date));
+        let weekday = CommonDate::weekday(&date);
+        Some(match s {
+            "%A" => names.weekday(weekday).to_owned(),
+            "%B" if jalali => names
+                .month_name(CalendarKind::Jalali, jalali_month())
+                .to_owned(),
+            "%B" => names
+                .month_name(CalendarKind::Gregorian, date.month() as UMonth)
+                .to_owned(),
+            "%EA" => names.jalali_weekdays()[weekday.get() as usize].to_owned(),
+            "%EB" => names
+                .month_name(CalendarKind::Jalali, jalali_month())
+                .to_owned(),
+            _ => return None,
+        })
+    }
+}
+
+/// Like [`calendar_strftime`] but with the names of the given locale (see [`names_resolve`]).
+pub fn localized_strftime(
+    format: &str,
+    now: &Zoned,
+    jalali: bool,
+    names: NameProvider,
+) -> Result<String, jiff::Error> {
+    let format =
+        Formatter::new(format).lenient_reconstruct_with(names_resolve(now.date(), jalali, names));
+    calendar_strftime(&format, now, jalali)
+}
+
+/// Format in Jalali ([`jalali_strftime`]) or Gregorian ([`gregorian_strftime`]).
+pub fn calendar_strftime(format: &str, now: &Zoned, jalali: bool) -> Result<String, jiff::Error> {
+    if jalali {
+        jalali_strftime(format, now)
+    } else {
+        gregorian_strftime(format, now)
+    }
+}
+
 /// [`jalali_strftime_to`] a newly created string.
 pub fn jalali_strftime(format: &str, now: &Zoned) -> Result<String, jiff::Error> {
     let mut buf = String::new();
@@ -146,12 +390,17 @@ pub fn jalali_strftime(format: &str, now: &Zoned) -> Result<String, jiff::Error>
 }
 
 /// Convert this date to Jalali and put it in the given formatter.
+///
+/// The time zone is kept as is so the zone directives behave the same in both calendars: `%Q` is
+/// the IANA name when known (`UTC` included) and `%z` otherwise (`%:Q` falls back to `%:z`).
 // TODO move to `jelal`
 pub fn jalali_strftime_to<W: jiff::fmt::Write>(
     format: &str,
     now: &Zoned,
     mut wtr: W,
 ) -> Result<(), jiff::Error> {
+    let formatter = Formatter::new(format);
+    formatter.check_widths()?;
     let jdate = jelal::Date::from(now.date());
 
     // jdate.set_to_broken with a BrokenDownTime that is created from a Zoned initializes all fields
@@ -160,8 +409,15 @@ pub fn jalali_strftime_to<W: jiff::fmt::Write>(
 
     // This identifies the formatters and replaces them with the given function
     // [`jalali_month_format_resolve`] replaces the aforementioned directives
-    let format =
-        Formatter::new(format).lenient_reconstruct_with(jalali_month_format_resolve(jdate.month()));
+    let month_resolve = jalali_month_format_resolve(jdate.month());
+    let extension_resolve = jalali_extension_resolve(now.date());
+    // the quarter is not left to `jiff` so it always follows the Jalali months
+    let quarter_resolve = |s: &str| (s == "%q").then(|| CommonDate::quarter(&jdate).to_string());
+    let format = formatter.lenient_reconstruct_with(|s| {
+        month_resolve(s)
+            .or_else(|| quarter_resolve(s))
+            .or_else(|| extension_resolve(s))
+    });
 
     bdt.format(format, &mut wtr)
 }
@@ -193,6 +449,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_width_cap() {
+        assert_eq!(directive_width("%d"), None);
+        assert_eq!(directive_width("%-d"), None);
+        assert_eq!(directive_width("%:z"), None);
+        assert_eq!(directive_width("%_10d"), Some(10));
+        assert_eq!(directive_width("%0999999999d"), Some(999999999));
+        assert_eq!(
+            directive_width("%99999999999999999999999d"),
+            Some(usize::MAX)
+        );
+
+        let now: Zoned = "2025-11-07T10:30:05[UTC]".parse().unwrap();
+        let at_cap = format!("%{}d", Formatter::MAX_WIDTH);
+        let above = format!("%0{}d", Formatter::MAX_WIDTH + 1);
+        for jalali in [false, true] {
+            let v = calendar_strftime(&at_cap, &now, jalali).unwrap();
+            assert_eq!(v.len(), Formatter::MAX_WIDTH);
+            assert!(calendar_strftime(&above, &now, jalali).is_err());
+            assert!(calendar_strftime("%0999999999d", &now, jalali).is_err());
+        }
+        assert!(date_strftime("%999999999EN", &Date::default()).is_err());
+
+        // rejected before a single directive is formatted
+        let many = at_cap.repeat(100_000) + &above;
+        let error = gregorian_strftime(&many, &now).unwrap_err().to_string();
+        assert!(error.contains(&above), "{}", error);
+    }
+
+    #[test]
+    fn test_date_strftime() {
+        let gregorian = Date::Gregorian(civil::date(2025, 11, 1));
+        let jalali = Date::Jalali(jelal::Date::from((1404, 8, 10)));
+
+        assert_eq!(date_strftime("%b '%y", &gregorian).unwrap(), "Nov '25");
+        assert_eq!(date_strftime("%m/%Y", &gregorian).unwrap(), "11/2025");
+        assert_eq!(
+            date_strftime("%^B %EN", &gregorian).unwrap(),
+            "NOVEMBER 1404"
+        );
+        assert_eq!(
+            date_strftime("%B %Y (%%)", &jalali).unwrap(),
+            "Aban 1404 (%)"
+        );
+        assert_eq!(date_strftime("%b '%y", &jalali).unwrap(), "Aba '04");
+
+        let e = date_strftime("%B %H", &gregorian).unwrap_err();
+        assert!(e.to_string().contains("%H"), "{}", e);
+    }
+
+    #[test]
+    fn test_human_formats() {
+        let tm: Zoned = "2025-11-07T10:30[Asia/Tehran]".parse().unwrap();
+        let human = |locale| gregorian_strftime(HUMAN_FORMATS.get(locale).unwrap(), &tm).unwrap();
+        assert_eq!(
+            human("en"),
+            "Friday, 7 November 2025 (Jomeh, 16 Aban 1404), 10:30 +03:30"
+        );
+        assert_eq!(
+            human("fa"),
+            "Jomeh, 16 Aban 1404 (Friday, 7 November 2025), 10:30 +03:30"
+        );
+        for locale in crate::config::LOCALES {
+            assert!(HUMAN_FORMATS.get(locale).is_some(), "{}", locale);
+        }
+
+        // in the names of the locale
+        let human = |locale| {
+            let names = NameProvider::get(locale).unwrap();
+            localized_strftime(HUMAN_FORMATS.get(locale).unwrap(), &tm, false, names).unwrap()
+        };
+        assert_eq!(
+            human("en"),
+            "Friday, 7 November 2025 (Jomeh, 16 Aban 1404), 10:30 +03:30"
+        );
+        assert_eq!(
+            human("fa"),
+            "جمعه, 16 آبان 1404 (جمعه, 7 نوامبر 2025), 10:30 +03:30"
+        );
+        let fa = NameProvider::get("fa").unwrap();
+        assert_eq!(localized_strftime("%B", &tm, true, fa).unwrap(), "آبان");
+        // the flags are left to the formatters
+        assert_eq!(localized_strftime("%^B", &tm, true, fa).unwrap(), "ABAN");
+
+        assert_eq!(gregorian_strftime("%Ed/%-Ed", &tm).unwrap(), "16/16");
+    }
+
     #[test]
     fn test_strftime_invalid_greg_date_valid_jalali() {
         // 1404/2/31 (2/31 is invalid in Gregorian so if formatter checks the input on that basis,
@@ -279,4 +622,108 @@ mod tests {
         // jiff does not provide more complex behavior like `%#^#010A` so it's not added to this
         // resolver either
     }
+
+    #[test]
+    fn test_strftime_zone_names() {
+        let at = |s: &str| -> Zoned { format!("2025-05-21T00:00{}", s).parse().unwrap() };
+        // a zone without a name like a system zone that could not be identified
+        let unnamed = civil::date(2025, 5, 21)
+            .at(0, 0, 0, 0)
+            .to_zoned(jiff::tz::TimeZone::posix("IRST-3:30").unwrap())
+            .unwrap();
+
+        for (tm, q, colon_q) in [
+            (at("[Asia/Tehran]"), "Asia/Tehran", "Asia/Tehran"),
+            (at("+03:30[+03:30]"), "+0330", "+03:30"),
+            (at("[UTC]"), "UTC", "UTC"),
+            (unnamed, "+0330", "+03:30"),
+        ] {
+            for strftime in [jalali_strftime, gregorian_strftime] {
+                assert_eq!(strftime("%Q", &tm).unwrap(), q);
+                assert_eq!(strftime("%:Q", &tm).unwrap(), colon_q);
+            }
+        }
+    }
+
+    #[test]
+    fn test_strftime_jalali_extensions() {
+        // 1404/02/31 with Gregorian ordinal 141 and Jalali ordinal 62
+        let tm = Zoned::strptime("%Y/%m/%d %z", "2025/05/21 +0000").unwrap();
+        assert_eq!(
+            "2025 141 1404 062",
+            gregorian_strftime("%Y %j %EN %EJ", &tm).unwrap()
+        );
+        // in Jalali, these are the same as the usual directives
+        assert_eq!(
+            "1404 062 1404 062",
+            jalali_strftime("%Y %j %EN %EJ", &tm).unwrap()
+        );
+        // the Jalali date is the same in both calendars
+        assert_eq!("1404-02-31", gregorian_strftime("%EF", &tm).unwrap());
+        assert_eq!(
+            jalali_strftime("%F %EF", &tm).unwrap(),
+            "1404-02-31 1404-02-31"
+        );
+        assert_eq!(
+            date_strftime("%EF", &Date::Gregorian(civil::date(2025, 5, 21))).unwrap(),
+            "1404-02-31"
+        );
+        assert_eq!(
+            gregorian_strftime("%EN %EG", &tm).unwrap(),
+            "1404 2025–2026"
+        );
+        assert_eq!(
+            date_strftime("%Y / %EG", &Date::Jalali(jelal::Date::from((1404, 1, 1)))).unwrap(),
+            "1404 / 2025–2026"
+        );
+
+        // 1403/12/30 (the last day of a leap year) while Gregorian is early in the year
+        let tm = Zoned::strptime("%Y/%m/%d %z", "2025/03/20 +0000").unwrap();
+        // Esfand is in the last quarter
+        assert_eq!("4", jalali_strftime("%q", &tm).unwrap());
+        assert_eq!("1", gregorian_strftime("%q", &tm).unwrap());
+        assert_eq!(
+            "day 366 of 1403 (079 of 2025)",
+            gregorian_strftime("day %EJ of %EN (%j of %Y)", &tm).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_strftime_jalali_timestamp() {
+        use jiff::tz::{Offset, TimeZone};
+
+        let composed = "%Y-%m-%dT%H:%M:%S%:z";
+        let utc: Zoned = "2025-03-20T21:00[UTC]".parse().unwrap();
+        for (tm, expected) in [
+            (
+                "2025-11-07T10:30:05[UTC]".parse().unwrap(),
+                "1404-08-16T10:30:05+00:00",
+            ),
+            (
+                "2025-11-07T10:30:05[+03:30]".parse().unwrap(),
+                "1404-08-16T10:30:05+03:30",
+            ),
+            // the same instant is the last day of 1403 in UTC and the first of 1404 in Tehran
+            (utc.clone(), "1403-12-30T21:00:00+00:00"),
+            (
+                utc.with_time_zone(TimeZone::fixed(Offset::from_seconds(12600).unwrap())),
+                "1404-01-01T00:30:00+03:30",
+            ),
+            (
+                utc.in_tz("Asia/Tehran").unwrap(),
+                "1404-01-01T00:30:00+03:30",
+            ),
+        ] {
+            assert_eq!(jalali_strftime(composed, &tm).unwrap(), expected);
+            assert_eq!(jalali_strftime("%Ei", &tm).unwrap(), expected);
+            assert_eq!(gregorian_strftime("%Ei", &tm).unwrap(), expected);
+        }
+
+        let tm: Zoned = "2025-11-07T10:30:05[UTC]".parse().unwrap();
+        assert_eq!(
+            gregorian_strftime("%F (%Ei)", &tm).unwrap(),
+            "2025-11-07 (1404-08-16T10:30:05+00:00)"
+        );
+        assert!(date_strftime("%Ei", &Date::Gregorian(civil::date(2025, 11, 7))).is_err());
+    }
 }